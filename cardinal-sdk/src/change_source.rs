@@ -0,0 +1,181 @@
+use crate::{FsEvent, current_event_id, event_id_to_timestamp};
+use crossbeam_channel::Receiver;
+use libc::dev_t;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Position of an event in the id space of the source that reported it.
+///
+/// Ids only grow within one source; ids of different backends can't be
+/// compared with each other.
+pub type EventId = u64;
+
+/// The backend behind a [`ChangeSource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SourceKind {
+    /// macOS FSEvents. Ids come from the volume journal, which can replay the
+    /// history after any id it handed out.
+    FsEvents,
+    /// Linux inotify watches. Ids are synthesized from the wall clock and there
+    /// is no history.
+    Inotify,
+    /// Linux fanotify filesystem marks, used instead of inotify when the
+    /// process may. Ids come from the same clock as [`Self::Inotify`].
+    Fanotify,
+    /// Directory mtime polling, numbered with `current_event_id()`.
+    Polling,
+    /// Batches read back from an event log, carrying the recorded ids.
+    Replay,
+}
+
+impl SourceKind {
+    /// The backend `EventWatcher::spawn` uses on this platform. On Linux it
+    /// reports [`Self::Fanotify`] instead when fanotify is available.
+    pub const fn native() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::FsEvents
+        } else {
+            Self::Inotify
+        }
+    }
+
+    /// Whether a source started from a cursor of this kind reports what
+    /// happened since, instead of only what happens from now on.
+    pub const fn replays_history(self) -> bool {
+        matches!(self, Self::FsEvents)
+    }
+}
+
+/// Where a consumer left off in a change stream, tagged with the backend whose
+/// ids it counts in. Persist it and hand it back to the same kind of source to
+/// resume; to any other backend it means nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumeCursor {
+    kind: SourceKind,
+    event_id: EventId,
+}
+
+impl ResumeCursor {
+    pub const fn new(kind: SourceKind, event_id: EventId) -> Self {
+        Self { kind, event_id }
+    }
+
+    /// The current position of this platform's native backend.
+    pub fn now() -> Self {
+        Self::new(SourceKind::native(), current_event_id())
+    }
+
+    pub fn kind(&self) -> SourceKind {
+        self.kind
+    }
+
+    /// The last event id seen, in the id space of [`Self::kind`].
+    pub fn event_id(&self) -> EventId {
+        self.event_id
+    }
+
+    /// Move past `event_id` reported by a `kind` source. An event from another
+    /// backend restarts the cursor in that backend's ids.
+    ///
+    /// Returns false when the cursor was already past `event_id`.
+    pub fn advance(&mut self, kind: SourceKind, event_id: EventId) -> bool {
+        if kind != self.kind {
+            *self = Self::new(kind, event_id);
+            true
+        } else if event_id > self.event_id {
+            self.event_id = event_id;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The id a `kind` source should start from, or `None` if this cursor
+    /// counts in another backend's ids.
+    pub fn since(&self, kind: SourceKind) -> Option<EventId> {
+        (kind == self.kind).then_some(self.event_id)
+    }
+}
+
+/// A stream of filesystem change batches, whatever produces them.
+///
+/// Every backend translates its notifications into [`FsEvent`]s: an
+/// [`EventFlag`](crate::EventFlag) set in FSEvents' vocabulary, the path it
+/// concerns and an [`EventId`] in the source's own id space.
+pub trait ChangeSource: Send {
+    fn kind(&self) -> SourceKind;
+
+    /// Event batches in the order they were reported. Live watchers never
+    /// disconnect; replays do once the log is exhausted.
+    fn events(&self) -> &Receiver<Vec<FsEvent>>;
+
+    /// Converts this source's event ids into wall-clock time.
+    fn clock(&self) -> EventClock;
+}
+
+/// Maps event ids of one source to Unix timestamps, caching the lookups.
+pub struct EventClock {
+    kind: SourceKind,
+    dev: dev_t,
+    cache: HashMap<i64, u64>,
+}
+
+impl EventClock {
+    pub fn new(kind: SourceKind, dev: dev_t) -> Self {
+        Self {
+            kind,
+            dev,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Approximate Unix time of `event_id`, or `None` for replayed events whose
+    /// ids were issued on another machine or another boot.
+    pub fn timestamp(&mut self, event_id: EventId) -> Option<i64> {
+        match self.kind {
+            SourceKind::Replay => None,
+            // Polling numbers events with `current_event_id()`, the same
+            // clock the native backend uses.
+            SourceKind::FsEvents
+            | SourceKind::Inotify
+            | SourceKind::Fanotify
+            | SourceKind::Polling => {
+                Some(event_id_to_timestamp(self.dev, event_id, &mut self.cache))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_only_resumes_its_own_backend() {
+        let cursor = ResumeCursor::new(SourceKind::FsEvents, 42);
+        assert_eq!(cursor.since(SourceKind::FsEvents), Some(42));
+        assert_eq!(cursor.since(SourceKind::Inotify), None);
+        assert_eq!(cursor.since(SourceKind::Replay), None);
+    }
+
+    #[test]
+    fn cursor_advances_monotonically_and_restarts_on_backend_switch() {
+        let mut cursor = ResumeCursor::new(SourceKind::Inotify, 10);
+        assert!(cursor.advance(SourceKind::Inotify, 12));
+        assert!(!cursor.advance(SourceKind::Inotify, 11));
+        assert_eq!(cursor.event_id(), 12);
+
+        assert!(cursor.advance(SourceKind::Replay, 3));
+        assert_eq!(cursor, ResumeCursor::new(SourceKind::Replay, 3));
+    }
+
+    #[test]
+    fn replayed_ids_have_no_wall_clock_time() {
+        assert_eq!(EventClock::new(SourceKind::Replay, 0).timestamp(1), None);
+        assert!(
+            EventClock::new(SourceKind::native(), 0)
+                .timestamp(current_event_id())
+                .is_some()
+        );
+    }
+}
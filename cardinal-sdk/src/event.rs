@@ -1,8 +1,9 @@
-use crate::{EventFlag, FSEventStreamEventId, ScanType};
+use crate::{EventFlag, EventId, ScanType};
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
 use std::{
     ffi::{CStr, OsStr},
     os::unix::ffi::OsStrExt,
-    path::{Path, PathBuf},
 };
 
 #[derive(Debug)]
@@ -12,10 +13,11 @@ pub struct FsEvent {
     /// The event type.
     pub flag: EventFlag,
     /// The event id.
-    pub id: FSEventStreamEventId,
+    pub id: EventId,
 }
 
 impl FsEvent {
+    #[cfg(target_os = "macos")]
     pub(crate) unsafe fn from_raw(path: *const i8, flag: u32, id: u64) -> Self {
         let path = unsafe { CStr::from_ptr(path) };
         let path = OsStr::from_bytes(path.to_bytes());
@@ -24,11 +26,19 @@ impl FsEvent {
         FsEvent { path, flag, id }
     }
 
+    /// Whether everything under `root` has to be rebuilt: the root itself was
+    /// replaced, or the event asks for a recursive scan of `root` or one of its
+    /// ancestors (e.g. after the kernel dropped events).
+    ///
+    /// Recursive scans of a subtree below `root` and plain changes to the root
+    /// directory's own entries can be recovered incrementally.
     pub fn should_rescan(&self, root: &Path) -> bool {
         match self.flag.scan_type() {
             ScanType::ReScan => true,
-            ScanType::SingleNode | ScanType::Folder if self.path == root => true,
-            ScanType::SingleNode | ScanType::Folder | ScanType::Nop => false,
+            ScanType::SingleNode | ScanType::Folder => {
+                self.flag.contains(EventFlag::MustScanSubDirs) && root.starts_with(&self.path)
+            }
+            ScanType::Nop => false,
         }
     }
 }
@@ -50,14 +60,38 @@ mod tests {
         };
         assert!(event.should_rescan(root));
 
-        // SingleNode at root true
+        // Plain change of the root directory itself false
         let event = FsEvent {
             path: PathBuf::from("/root"),
-            flag: EventFlag::ItemModified | EventFlag::ItemIsFile,
+            flag: EventFlag::ItemModified | EventFlag::ItemIsDir,
+            id: 1,
+        };
+        assert!(!event.should_rescan(root));
+
+        // Recursive scan of the root true
+        let event = FsEvent {
+            path: PathBuf::from("/root"),
+            flag: EventFlag::MustScanSubDirs | EventFlag::KernelDropped,
             id: 1,
         };
         assert!(event.should_rescan(root));
 
+        // Recursive scan of an ancestor of the root true
+        let event = FsEvent {
+            path: PathBuf::from("/"),
+            flag: EventFlag::MustScanSubDirs | EventFlag::UserDropped,
+            id: 1,
+        };
+        assert!(event.should_rescan(root));
+
+        // Recursive scan of a subtree false
+        let event = FsEvent {
+            path: PathBuf::from("/root/Library"),
+            flag: EventFlag::MustScanSubDirs | EventFlag::UserDropped,
+            id: 1,
+        };
+        assert!(!event.should_rescan(root));
+
         // SingleNode not at root false
         let event = FsEvent {
             path: PathBuf::from("/root/sub/file"),
@@ -1,31 +1,80 @@
 use bitflags::bitflags;
+
+#[cfg(target_os = "macos")]
+mod raw {
+    pub use objc2_core_services::{
+        kFSEventStreamEventFlagEventIdsWrapped, kFSEventStreamEventFlagHistoryDone,
+        kFSEventStreamEventFlagItemChangeOwner, kFSEventStreamEventFlagItemCloned,
+        kFSEventStreamEventFlagItemCreated, kFSEventStreamEventFlagItemFinderInfoMod,
+        kFSEventStreamEventFlagItemInodeMetaMod, kFSEventStreamEventFlagItemIsDir,
+        kFSEventStreamEventFlagItemIsFile, kFSEventStreamEventFlagItemIsHardlink,
+        kFSEventStreamEventFlagItemIsLastHardlink, kFSEventStreamEventFlagItemIsSymlink,
+        kFSEventStreamEventFlagItemModified, kFSEventStreamEventFlagItemRemoved,
+        kFSEventStreamEventFlagItemRenamed, kFSEventStreamEventFlagItemXattrMod,
+        kFSEventStreamEventFlagKernelDropped, kFSEventStreamEventFlagMount,
+        kFSEventStreamEventFlagMustScanSubDirs, kFSEventStreamEventFlagNone,
+        kFSEventStreamEventFlagOwnEvent, kFSEventStreamEventFlagRootChanged,
+        kFSEventStreamEventFlagUnmount, kFSEventStreamEventFlagUserDropped,
+    };
+}
+
+/// Mirrors the FSEvents flag values so other backends can speak the same vocabulary.
+#[cfg(not(target_os = "macos"))]
+#[allow(non_upper_case_globals)]
+mod raw {
+    pub const kFSEventStreamEventFlagNone: u32 = 0x00000000;
+    pub const kFSEventStreamEventFlagMustScanSubDirs: u32 = 0x00000001;
+    pub const kFSEventStreamEventFlagUserDropped: u32 = 0x00000002;
+    pub const kFSEventStreamEventFlagKernelDropped: u32 = 0x00000004;
+    pub const kFSEventStreamEventFlagEventIdsWrapped: u32 = 0x00000008;
+    pub const kFSEventStreamEventFlagHistoryDone: u32 = 0x00000010;
+    pub const kFSEventStreamEventFlagRootChanged: u32 = 0x00000020;
+    pub const kFSEventStreamEventFlagMount: u32 = 0x00000040;
+    pub const kFSEventStreamEventFlagUnmount: u32 = 0x00000080;
+    pub const kFSEventStreamEventFlagItemCreated: u32 = 0x00000100;
+    pub const kFSEventStreamEventFlagItemRemoved: u32 = 0x00000200;
+    pub const kFSEventStreamEventFlagItemInodeMetaMod: u32 = 0x00000400;
+    pub const kFSEventStreamEventFlagItemRenamed: u32 = 0x00000800;
+    pub const kFSEventStreamEventFlagItemModified: u32 = 0x00001000;
+    pub const kFSEventStreamEventFlagItemFinderInfoMod: u32 = 0x00002000;
+    pub const kFSEventStreamEventFlagItemChangeOwner: u32 = 0x00004000;
+    pub const kFSEventStreamEventFlagItemXattrMod: u32 = 0x00008000;
+    pub const kFSEventStreamEventFlagItemIsFile: u32 = 0x00010000;
+    pub const kFSEventStreamEventFlagItemIsDir: u32 = 0x00020000;
+    pub const kFSEventStreamEventFlagItemIsSymlink: u32 = 0x00040000;
+    pub const kFSEventStreamEventFlagOwnEvent: u32 = 0x00080000;
+    pub const kFSEventStreamEventFlagItemIsHardlink: u32 = 0x00100000;
+    pub const kFSEventStreamEventFlagItemIsLastHardlink: u32 = 0x00200000;
+    pub const kFSEventStreamEventFlagItemCloned: u32 = 0x00400000;
+}
+
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct EventFlag: u32 {
-        const None = objc2_core_services::kFSEventStreamEventFlagNone;
-        const MustScanSubDirs = objc2_core_services::kFSEventStreamEventFlagMustScanSubDirs;
-        const UserDropped = objc2_core_services::kFSEventStreamEventFlagUserDropped;
-        const KernelDropped = objc2_core_services::kFSEventStreamEventFlagKernelDropped;
-        const EventIdsWrapped = objc2_core_services::kFSEventStreamEventFlagEventIdsWrapped;
-        const HistoryDone = objc2_core_services::kFSEventStreamEventFlagHistoryDone;
-        const RootChanged = objc2_core_services::kFSEventStreamEventFlagRootChanged;
-        const Mount = objc2_core_services::kFSEventStreamEventFlagMount;
-        const Unmount = objc2_core_services::kFSEventStreamEventFlagUnmount;
-        const ItemCreated = objc2_core_services::kFSEventStreamEventFlagItemCreated;
-        const ItemRemoved = objc2_core_services::kFSEventStreamEventFlagItemRemoved;
-        const ItemInodeMetaMod = objc2_core_services::kFSEventStreamEventFlagItemInodeMetaMod;
-        const ItemRenamed = objc2_core_services::kFSEventStreamEventFlagItemRenamed;
-        const ItemModified = objc2_core_services::kFSEventStreamEventFlagItemModified;
-        const ItemFinderInfoMod = objc2_core_services::kFSEventStreamEventFlagItemFinderInfoMod;
-        const ItemChangeOwner = objc2_core_services::kFSEventStreamEventFlagItemChangeOwner;
-        const ItemXattrMod = objc2_core_services::kFSEventStreamEventFlagItemXattrMod;
-        const ItemIsFile = objc2_core_services::kFSEventStreamEventFlagItemIsFile;
-        const ItemIsDir = objc2_core_services::kFSEventStreamEventFlagItemIsDir;
-        const ItemIsSymlink = objc2_core_services::kFSEventStreamEventFlagItemIsSymlink;
-        const OwnEvent = objc2_core_services::kFSEventStreamEventFlagOwnEvent;
-        const IsHardlink = objc2_core_services::kFSEventStreamEventFlagItemIsHardlink;
-        const IsLastHardlink = objc2_core_services::kFSEventStreamEventFlagItemIsLastHardlink;
-        const Cloned = objc2_core_services::kFSEventStreamEventFlagItemCloned;
+        const None = raw::kFSEventStreamEventFlagNone;
+        const MustScanSubDirs = raw::kFSEventStreamEventFlagMustScanSubDirs;
+        const UserDropped = raw::kFSEventStreamEventFlagUserDropped;
+        const KernelDropped = raw::kFSEventStreamEventFlagKernelDropped;
+        const EventIdsWrapped = raw::kFSEventStreamEventFlagEventIdsWrapped;
+        const HistoryDone = raw::kFSEventStreamEventFlagHistoryDone;
+        const RootChanged = raw::kFSEventStreamEventFlagRootChanged;
+        const Mount = raw::kFSEventStreamEventFlagMount;
+        const Unmount = raw::kFSEventStreamEventFlagUnmount;
+        const ItemCreated = raw::kFSEventStreamEventFlagItemCreated;
+        const ItemRemoved = raw::kFSEventStreamEventFlagItemRemoved;
+        const ItemInodeMetaMod = raw::kFSEventStreamEventFlagItemInodeMetaMod;
+        const ItemRenamed = raw::kFSEventStreamEventFlagItemRenamed;
+        const ItemModified = raw::kFSEventStreamEventFlagItemModified;
+        const ItemFinderInfoMod = raw::kFSEventStreamEventFlagItemFinderInfoMod;
+        const ItemChangeOwner = raw::kFSEventStreamEventFlagItemChangeOwner;
+        const ItemXattrMod = raw::kFSEventStreamEventFlagItemXattrMod;
+        const ItemIsFile = raw::kFSEventStreamEventFlagItemIsFile;
+        const ItemIsDir = raw::kFSEventStreamEventFlagItemIsDir;
+        const ItemIsSymlink = raw::kFSEventStreamEventFlagItemIsSymlink;
+        const OwnEvent = raw::kFSEventStreamEventFlagOwnEvent;
+        const IsHardlink = raw::kFSEventStreamEventFlagItemIsHardlink;
+        const IsLastHardlink = raw::kFSEventStreamEventFlagItemIsLastHardlink;
+        const Cloned = raw::kFSEventStreamEventFlagItemCloned;
     }
 }
 
@@ -73,6 +122,9 @@ impl EventFlag {
             ScanType::Nop
         } else if self.contains(EventFlag::RootChanged) {
             ScanType::ReScan
+        } else if self.contains(EventFlag::MustScanSubDirs) {
+            // The whole subtree below this path may have changed, whatever its type.
+            ScanType::Folder
         } else {
             // Strange event, doesn't know when it happens, processing it using a generic way
             // e.g. new event: fs_event=FsEvent { path: "/.docid/16777229/changed/782/src=0,dst=41985052", flag: kFSEventStreamEventFlagNone, id: 471533015 }
@@ -149,5 +201,9 @@ mod tests {
             (EventFlag::MustScanSubDirs | EventFlag::ItemIsDir).scan_type(),
             ScanType::Folder
         ));
+        assert!(matches!(
+            (EventFlag::MustScanSubDirs | EventFlag::ItemIsFile).scan_type(),
+            ScanType::Folder
+        ));
     }
 }
@@ -0,0 +1,335 @@
+//! Recordings of event batches, written by `was record` and replayed by
+//! `search-cache`.
+//!
+//! The log is JSON lines: an [`EventLogHeader`] first, then one
+//! [`RecordedBatch`] per delivered batch.
+
+use crate::{ChangeSource, EventClock, EventFlag, EventId, FsEvent, SourceKind};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, bounded, unbounded};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, BufRead, Lines, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Format version written into every header. Bump it on incompatible changes.
+pub const EVENT_LOG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventLogHeader {
+    pub version: u32,
+    /// The watched root; replays rebase event paths from it.
+    pub root: PathBuf,
+    /// Event id the recording was started from.
+    pub since: EventId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub path: PathBuf,
+    /// Raw `EventFlag` bits, so unknown flags survive a round trip.
+    pub flag: u32,
+    pub id: EventId,
+}
+
+impl From<&FsEvent> for RecordedEvent {
+    fn from(event: &FsEvent) -> Self {
+        Self {
+            path: event.path.clone(),
+            flag: event.flag.bits(),
+            id: event.id,
+        }
+    }
+}
+
+impl From<RecordedEvent> for FsEvent {
+    fn from(event: RecordedEvent) -> Self {
+        Self {
+            path: event.path,
+            flag: EventFlag::from_bits_retain(event.flag),
+            id: event.id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBatch {
+    /// Milliseconds between the start of the recording and this batch.
+    pub elapsed_ms: u64,
+    pub events: Vec<RecordedEvent>,
+}
+
+pub struct EventLogWriter<W: Write> {
+    writer: W,
+    started: Instant,
+}
+
+impl<W: Write> EventLogWriter<W> {
+    pub fn new(mut writer: W, root: PathBuf, since: EventId) -> io::Result<Self> {
+        let header = EventLogHeader {
+            version: EVENT_LOG_VERSION,
+            root,
+            since,
+        };
+        write_line(&mut writer, &header)?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+        })
+    }
+
+    /// Append one batch and flush it, so an interrupted recording stays readable.
+    pub fn write_batch(&mut self, events: &[FsEvent]) -> io::Result<()> {
+        let batch = RecordedBatch {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            events: events.iter().map(RecordedEvent::from).collect(),
+        };
+        write_line(&mut self.writer, &batch)?;
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn write_line<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")
+}
+
+/// Reads a log written by [`EventLogWriter`], yielding its batches in order.
+pub struct EventLogReader<R: BufRead> {
+    header: EventLogHeader,
+    lines: Lines<R>,
+}
+
+impl<R: BufRead> EventLogReader<R> {
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let Some(line) = lines.next() else {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "event log has no header",
+            ));
+        };
+        let header: EventLogHeader = serde_json::from_str(&line?)?;
+        if header.version != EVENT_LOG_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported event log version {}", header.version),
+            ));
+        }
+        Ok(Self { header, lines })
+    }
+
+    pub fn header(&self) -> &EventLogHeader {
+        &self.header
+    }
+}
+
+impl<R: BufRead> Iterator for EventLogReader<R> {
+    type Item = io::Result<RecordedBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err)),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Some(serde_json::from_str(&line).map_err(io::Error::from));
+        }
+    }
+}
+
+/// Plays an event log back as a [`ChangeSource`].
+///
+/// Batches are sent from a background thread with their paths rebased onto
+/// `root`; the receiver disconnects after the last one, or early if the log
+/// turns out to be unreadable.
+pub struct ReplaySource {
+    receiver: Receiver<Vec<FsEvent>>,
+    _cancellation_token: Sender<()>,
+}
+
+impl ReplaySource {
+    /// With `paced`, the gaps between batches are slept through as recorded;
+    /// otherwise batches are sent back to back.
+    pub fn spawn<R: BufRead + Send + 'static>(
+        log: EventLogReader<R>,
+        root: PathBuf,
+        paced: bool,
+    ) -> Self {
+        let (_cancellation_token, cancellation_token_rx) = bounded::<()>(1);
+        let (sender, receiver) = unbounded();
+        let recorded_root = log.header().root.clone();
+        std::thread::Builder::new()
+            .name("cardinal-sdk-event-replay".to_string())
+            .spawn(move || {
+                let mut last_elapsed_ms = 0;
+                for batch in log {
+                    let Ok(batch) = batch else {
+                        return;
+                    };
+                    if paced {
+                        let gap = batch.elapsed_ms.saturating_sub(last_elapsed_ms);
+                        // Wake up early when the `ReplaySource` is dropped.
+                        if let Err(RecvTimeoutError::Disconnected) =
+                            cancellation_token_rx.recv_timeout(Duration::from_millis(gap))
+                        {
+                            return;
+                        }
+                        last_elapsed_ms = batch.elapsed_ms;
+                    }
+                    let events = batch
+                        .events
+                        .into_iter()
+                        .map(|event| {
+                            let mut event = FsEvent::from(event);
+                            event.path = rebase_path(&event.path, &recorded_root, &root);
+                            event
+                        })
+                        .collect();
+                    if sender.send(events).is_err() {
+                        return;
+                    }
+                }
+            })
+            .unwrap();
+        Self {
+            receiver,
+            _cancellation_token,
+        }
+    }
+}
+
+impl ChangeSource for ReplaySource {
+    fn kind(&self) -> SourceKind {
+        SourceKind::Replay
+    }
+
+    fn events(&self) -> &Receiver<Vec<FsEvent>> {
+        &self.receiver
+    }
+
+    fn clock(&self) -> EventClock {
+        EventClock::new(SourceKind::Replay, 0)
+    }
+}
+
+/// Move `path` from under `from` to under `to`. Ancestors of `from` become the
+/// ancestors of `to` at the same distance, so events on them keep their meaning.
+pub fn rebase_path(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    if let Ok(relative) = path.strip_prefix(from) {
+        return to.join(relative);
+    }
+    if from.starts_with(path) {
+        let levels = from.components().count() - path.components().count();
+        return to
+            .ancestors()
+            .nth(levels)
+            .or_else(|| to.ancestors().last())
+            .unwrap_or(to)
+            .to_path_buf();
+    }
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_batches() {
+        let mut writer = EventLogWriter::new(Vec::new(), PathBuf::from("/watched"), 42).unwrap();
+        writer
+            .write_batch(&[FsEvent {
+                path: PathBuf::from("/watched/a.txt"),
+                flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+                id: 43,
+            }])
+            .unwrap();
+        writer.write_batch(&[]).unwrap();
+        let bytes = writer.into_inner();
+
+        let mut reader = EventLogReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(
+            reader.header(),
+            &EventLogHeader {
+                version: EVENT_LOG_VERSION,
+                root: PathBuf::from("/watched"),
+                since: 42,
+            }
+        );
+        let first = reader.next().unwrap().unwrap();
+        let event = FsEvent::from(first.events[0].clone());
+        assert_eq!(event.path, PathBuf::from("/watched/a.txt"));
+        assert_eq!(event.flag, EventFlag::ItemCreated | EventFlag::ItemIsFile);
+        assert_eq!(event.id, 43);
+        assert!(reader.next().unwrap().unwrap().events.is_empty());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let log = b"{\"version\":999,\"root\":\"/\",\"since\":0}\n";
+        let err = EventLogReader::new(&log[..]).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rebases_paths_under_recorded_root() {
+        let from = Path::new("/Users/me/project");
+        let to = Path::new("/tmp/fixture");
+        assert_eq!(
+            rebase_path(Path::new("/Users/me/project/src/a.rs"), from, to),
+            PathBuf::from("/tmp/fixture/src/a.rs")
+        );
+        assert_eq!(rebase_path(from, from, to), PathBuf::from("/tmp/fixture"));
+        assert_eq!(
+            rebase_path(Path::new("/Users/me"), from, to),
+            PathBuf::from("/tmp")
+        );
+        assert_eq!(rebase_path(Path::new("/"), from, to), PathBuf::from("/"));
+        // Paths outside the recorded tree are kept as they are.
+        assert_eq!(
+            rebase_path(Path::new("/Users/other/x"), from, to),
+            PathBuf::from("/Users/other/x")
+        );
+    }
+
+    #[test]
+    fn replay_source_sends_rebased_batches_then_disconnects() {
+        let mut writer = EventLogWriter::new(Vec::new(), PathBuf::from("/recorded"), 0).unwrap();
+        for id in [1, 2] {
+            writer
+                .write_batch(&[FsEvent {
+                    path: PathBuf::from(format!("/recorded/{id}.txt")),
+                    flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+                    id,
+                }])
+                .unwrap();
+        }
+        let log = EventLogReader::new(io::Cursor::new(writer.into_inner())).unwrap();
+
+        let source = ReplaySource::spawn(log, PathBuf::from("/fixture"), false);
+        assert_eq!(source.kind(), SourceKind::Replay);
+        let batches: Vec<Vec<FsEvent>> = source.events().iter().collect();
+        let paths: Vec<_> = batches
+            .iter()
+            .flatten()
+            .map(|event| (event.path.clone(), event.id))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (PathBuf::from("/fixture/1.txt"), 1),
+                (PathBuf::from("/fixture/2.txt"), 2),
+            ]
+        );
+    }
+}
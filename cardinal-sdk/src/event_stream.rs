@@ -1,5 +1,5 @@
-use crate::{EventFlag, FsEvent};
-use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
+use crate::{EventWatcher, FsEvent, SourceKind, event_watcher::filter_events_by_paths};
+use crossbeam_channel::{bounded, unbounded};
 use dispatch2::{DispatchQueue, DispatchQueueAttr, DispatchRetained};
 use libc::dev_t;
 use objc2_core_foundation::{CFArray, CFString, CFTimeInterval};
@@ -10,14 +10,7 @@ use objc2_core_services::{
     FSEventStreamStop, kFSEventStreamCreateFlagFileEvents, kFSEventStreamCreateFlagNoDefer,
     kFSEventStreamCreateFlagWatchRoot,
 };
-use std::{
-    ffi::c_void,
-    ops::{Deref, DerefMut},
-    path::PathBuf,
-    ptr::NonNull,
-    slice,
-    sync::LazyLock,
-};
+use std::{ffi::c_void, path::PathBuf, ptr::NonNull, slice};
 
 type EventsCallback = Box<dyn FnMut(Vec<FsEvent>) + Send>;
 
@@ -134,37 +127,7 @@ impl Drop for EventStreamWithQueue {
     }
 }
 
-pub struct EventWatcher {
-    receiver: Receiver<Vec<FsEvent>>,
-    _cancellation_token: Sender<()>,
-}
-
-impl Deref for EventWatcher {
-    type Target = Receiver<Vec<FsEvent>>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.receiver
-    }
-}
-
-impl DerefMut for EventWatcher {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.receiver
-    }
-}
-
 impl EventWatcher {
-    pub fn noop() -> Self {
-        #[allow(clippy::type_complexity)]
-        static BLACK_HOLE1: LazyLock<(Sender<Vec<FsEvent>>, Receiver<Vec<FsEvent>>)> =
-            LazyLock::new(unbounded);
-        static BLACK_HOLE2: LazyLock<(Sender<()>, Receiver<()>)> = LazyLock::new(|| bounded(1));
-        Self {
-            receiver: BLACK_HOLE1.1.clone(),
-            _cancellation_token: BLACK_HOLE2.0.clone(),
-        }
-    }
-
     pub fn spawn(
         path: String,
         since_event_id: FSEventStreamEventId,
@@ -198,70 +161,21 @@ impl EventWatcher {
             EventWatcher {
                 receiver,
                 _cancellation_token,
+                kind: SourceKind::FsEvents,
+                dev,
             },
         )
     }
 }
 
-fn filter_events_by_paths(
-    events: Vec<FsEvent>,
-    ignore_paths: &[PathBuf],
-    include_paths: &[PathBuf],
-) -> Vec<FsEvent> {
-    events
-        .into_iter()
-        .filter(|event| {
-            event.flag.contains(EventFlag::HistoryDone)
-                || !fswalk::should_ignore_path(&event.path, ignore_paths, include_paths)
-        })
-        .collect()
-}
-
 #[cfg(all(test, target_os = "macos"))]
 mod tests {
     use super::*;
     use crate::{EventFlag, utils::current_event_id};
     use crossbeam_channel::RecvTimeoutError;
-    use std::{
-        path::PathBuf,
-        time::{Duration, Instant},
-    };
+    use std::time::{Duration, Instant};
     use tempfile::tempdir;
 
-    #[test]
-    fn noop_event_watcher_recv_timeout_never_disconnects() {
-        let watcher = EventWatcher::noop();
-        let result = watcher.recv_timeout(Duration::from_millis(50));
-        assert!(
-            matches!(result, Err(RecvTimeoutError::Timeout)),
-            "noop watcher should block waiting for events instead of disconnecting"
-        );
-    }
-
-    /// Before the LazyLock fix each `noop()` call created a fresh channel pair
-    /// and immediately dropped its sender, causing `Disconnected` on the very
-    /// first `recv_timeout`. This test locks in the correct shared-channel behaviour:
-    /// multiple concurrent noop watchers must all time out, never disconnect.
-    #[test]
-    fn multiple_noop_watchers_all_timeout_not_disconnected() {
-        let watchers: Vec<_> = (0..4).map(|_| EventWatcher::noop()).collect();
-        for (i, w) in watchers.iter().enumerate() {
-            let result = w.recv_timeout(Duration::from_millis(30));
-            assert!(
-                matches!(result, Err(RecvTimeoutError::Timeout)),
-                "noop watcher #{i} disconnected — shared BLACK_HOLE channel not working"
-            );
-        }
-        // Dropping them should not affect the shared channel used by others.
-        drop(watchers);
-        let late = EventWatcher::noop();
-        let result = late.recv_timeout(Duration::from_millis(30));
-        assert!(
-            matches!(result, Err(RecvTimeoutError::Timeout)),
-            "noop watcher created after previous ones dropped must still timeout"
-        );
-    }
-
     #[test]
     fn event_watcher_on_non_existent_path() {
         let (_dev, watcher) = EventWatcher::spawn(
@@ -356,115 +270,4 @@ mod tests {
             "respawned watcher failed to deliver file change event"
         );
     }
-
-    #[test]
-    fn filter_events_by_paths_uses_fswalk_include_ignore_semantics() {
-        fn paths(raw: &[&str]) -> Vec<PathBuf> {
-            raw.iter().map(PathBuf::from).collect()
-        }
-
-        fn item(id: u64, path: &str) -> FsEvent {
-            FsEvent {
-                path: PathBuf::from(path),
-                flag: EventFlag::ItemCreated,
-                id,
-            }
-        }
-
-        fn history_done(id: u64, path: &str) -> FsEvent {
-            FsEvent {
-                path: PathBuf::from(path),
-                flag: EventFlag::HistoryDone,
-                id,
-            }
-        }
-
-        let cases = [
-            (
-                "keeps visible paths without ignores",
-                paths(&[]),
-                paths(&[]),
-                vec![item(1, "/root/visible/file.txt")],
-                vec![1],
-            ),
-            (
-                "drops paths under an ignored directory",
-                paths(&["/root/ignored"]),
-                paths(&[]),
-                vec![
-                    item(1, "/root/ignored/file.txt"),
-                    item(2, "/root/visible/file.txt"),
-                ],
-                vec![2],
-            ),
-            (
-                "keeps included subtree under ignored parent",
-                paths(&["/root/ignored"]),
-                paths(&["/root/ignored/included"]),
-                vec![
-                    item(1, "/root/ignored/file.txt"),
-                    item(2, "/root/ignored/included/file.txt"),
-                    item(3, "/root/ignored/included"),
-                ],
-                vec![2, 3],
-            ),
-            (
-                "keeps strict ancestors of include paths",
-                paths(&["/root/ignored"]),
-                paths(&["/root/ignored/included/file.txt"]),
-                vec![
-                    item(1, "/root/ignored"),
-                    item(2, "/root/ignored/included"),
-                    item(3, "/root/ignored/other"),
-                ],
-                vec![1, 2],
-            ),
-            (
-                "drops deeper re-ignored subtree below included path",
-                paths(&["/root/ignored", "/root/ignored/included/reignored"]),
-                paths(&["/root/ignored/included"]),
-                vec![
-                    item(1, "/root/ignored/included/file.txt"),
-                    item(2, "/root/ignored/included/reignored/file.txt"),
-                ],
-                vec![1],
-            ),
-            (
-                "keeps ties between ignore and include paths",
-                paths(&["/root/tie"]),
-                paths(&["/root/tie"]),
-                vec![item(1, "/root/tie"), item(2, "/root/tie/file.txt")],
-                vec![1, 2],
-            ),
-            (
-                "keeps history done events even under ignored paths",
-                paths(&["/root/ignored"]),
-                paths(&[]),
-                vec![
-                    item(1, "/root/ignored/file.txt"),
-                    history_done(2, "/root/ignored"),
-                ],
-                vec![2],
-            ),
-            (
-                "does not let similar path prefixes match",
-                paths(&["/root/ignored"]),
-                paths(&[]),
-                vec![
-                    item(1, "/root/ignored/file.txt"),
-                    item(2, "/root/ignored-sibling/file.txt"),
-                ],
-                vec![2],
-            ),
-        ];
-
-        for (name, ignore_paths, include_paths, events, expected_ids) in cases {
-            let filtered = filter_events_by_paths(events, &ignore_paths, &include_paths);
-            let actual_ids = filtered
-                .into_iter()
-                .map(|event| event.id)
-                .collect::<Vec<_>>();
-            assert_eq!(actual_ids, expected_ids, "{name}");
-        }
-    }
 }
@@ -0,0 +1,511 @@
+use crate::{
+    EventFlag, EventId, EventWatcher, FsEvent, SourceKind, current_event_id,
+    linux::{self, NotifySource},
+};
+use crossbeam_channel::{bounded, unbounded};
+use libc::dev_t;
+use std::{
+    collections::HashMap,
+    ffi::{CString, OsStr},
+    fs, io,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        unix::{ffi::OsStrExt, fs::MetadataExt},
+    },
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const MARK_MASK: u64 = libc::FAN_CREATE
+    | libc::FAN_DELETE
+    | libc::FAN_MOVED_FROM
+    | libc::FAN_MOVED_TO
+    | libc::FAN_MODIFY
+    | libc::FAN_ATTRIB
+    | libc::FAN_DELETE_SELF
+    | libc::FAN_MOVE_SELF
+    | libc::FAN_ONDIR;
+
+const READ_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Filesystem id as reported by both `statfs` and fanotify fid records.
+type Fsid = [libc::c_int; 2];
+
+/// Whole-filesystem watcher built on fanotify.
+///
+/// One `FAN_MARK_FILESYSTEM` mark covers every directory of a filesystem, so
+/// the kernel cost no longer grows with the directory count like inotify's
+/// does. Each filesystem mounted at or below the root gets its own mark;
+/// events outside the root are dropped here.
+struct Fanotify {
+    fd: OwnedFd,
+    /// Canonical root. Resolved event paths are always canonical, so this is
+    /// what they are compared against.
+    root: PathBuf,
+    /// The root as the caller spelled it; emitted paths are rebased onto it.
+    reported_root: PathBuf,
+    /// An open directory on each marked filesystem, used to resolve file handles.
+    mounts: HashMap<Fsid, OwnedFd>,
+}
+
+impl Fanotify {
+    /// Fails with `PermissionDenied` without `CAP_SYS_ADMIN`, and with
+    /// `InvalidInput` on kernels that predate `FAN_REPORT_DFID_NAME`.
+    fn new(
+        root: PathBuf,
+        reported_root: PathBuf,
+        ignore_paths: &[PathBuf],
+        include_paths: &[PathBuf],
+    ) -> io::Result<Self> {
+        let fd = unsafe {
+            libc::fanotify_init(
+                libc::FAN_CLASS_NOTIF
+                    | libc::FAN_CLOEXEC
+                    | libc::FAN_NONBLOCK
+                    | libc::FAN_REPORT_DFID_NAME,
+                (libc::O_RDONLY | libc::O_CLOEXEC | libc::O_LARGEFILE) as u32,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut fanotify = Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            root,
+            reported_root,
+            mounts: HashMap::new(),
+        };
+        // The filesystem holding the root must be watchable; nested mounts are best effort.
+        fanotify.mark(&fanotify.root.clone())?;
+        for mount_point in mount_points() {
+            if mount_point != fanotify.root
+                && mount_point.starts_with(&fanotify.root)
+                && !fswalk::should_ignore_path(&mount_point, ignore_paths, include_paths)
+            {
+                let _ = fanotify.mark(&mount_point);
+            }
+        }
+        Ok(fanotify)
+    }
+
+    fn mark(&mut self, path: &Path) -> io::Result<()> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let ret = unsafe {
+            libc::fanotify_mark(
+                self.fd.as_raw_fd(),
+                libc::FAN_MARK_ADD | libc::FAN_MARK_FILESYSTEM,
+                MARK_MASK,
+                libc::AT_FDCWD,
+                c_path.as_ptr(),
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let dir = unsafe {
+            libc::open(
+                c_path.as_ptr(),
+                libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC,
+            )
+        };
+        if dir < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let dir = unsafe { OwnedFd::from_raw_fd(dir) };
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::fstatfs(dir.as_raw_fd(), &mut stat) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fsid: Fsid = unsafe { std::mem::transmute(stat.f_fsid) };
+        self.mounts.entry(fsid).or_insert(dir);
+        Ok(())
+    }
+
+    /// Turn a directory file handle back into a path.
+    ///
+    /// Returns `None` when the directory is already gone; the event for its
+    /// removal still arrives through the parent.
+    fn resolve(&self, fsid: Fsid, handle: &[u8]) -> Option<PathBuf> {
+        let mount = self.mounts.get(&fsid)?;
+        // `file_handle` is read through a pointer, so copy it into aligned storage.
+        let mut storage = vec![0u32; handle.len().div_ceil(size_of::<u32>())];
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                handle.as_ptr(),
+                storage.as_mut_ptr().cast(),
+                handle.len(),
+            )
+        };
+        let fd = unsafe {
+            libc::open_by_handle_at(
+                mount.as_raw_fd(),
+                storage.as_mut_ptr().cast(),
+                libc::O_PATH | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return None;
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        fs::read_link(format!("/proc/self/fd/{}", fd.as_raw_fd())).ok()
+    }
+
+    /// Rebase a canonical path below the root onto the caller's root.
+    fn reported(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) if relative.as_os_str().is_empty() => self.reported_root.clone(),
+            Ok(relative) => self.reported_root.join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    /// Translate one fanotify record into a folder-scan event.
+    ///
+    /// `FAN_REPORT_DFID_NAME` identifies the parent directory plus the entry
+    /// name, so every change is surfaced as `MustScanSubDirs` on that entry.
+    /// `scan_path_recursive` then refreshes it whether it is a file or a folder.
+    fn translate(&self, mask: u64, info: &[u8], events: &mut Vec<FsEvent>) {
+        let id = current_event_id();
+        if mask & libc::FAN_Q_OVERFLOW != 0 {
+            // The kernel dropped events, so nothing short of a rescan is trustworthy.
+            events.push(FsEvent {
+                path: self.reported_root.clone(),
+                flag: EventFlag::MustScanSubDirs | EventFlag::KernelDropped | EventFlag::ItemIsDir,
+                id,
+            });
+            return;
+        }
+        let Some((fsid, handle, name)) = parse_dfid_name(info) else {
+            return;
+        };
+        let Some(dir) = self.resolve(fsid, handle) else {
+            return;
+        };
+        let is_self = name.is_empty() || name == b".";
+        if mask & (libc::FAN_DELETE_SELF | libc::FAN_MOVE_SELF) != 0 {
+            // Non-root directories are reported through their parent.
+            if is_self && dir == self.root {
+                events.push(FsEvent {
+                    path: self.reported_root.clone(),
+                    flag: EventFlag::RootChanged,
+                    id,
+                });
+            }
+            return;
+        }
+        if is_self {
+            return;
+        }
+        let path = dir.join(OsStr::from_bytes(name));
+        if !path.starts_with(&self.root) {
+            return;
+        }
+        let mut flag = EventFlag::MustScanSubDirs;
+        flag |= if mask & libc::FAN_ONDIR != 0 {
+            EventFlag::ItemIsDir
+        } else {
+            EventFlag::ItemIsFile
+        };
+        if mask & libc::FAN_CREATE != 0 {
+            flag |= EventFlag::ItemCreated;
+        }
+        if mask & libc::FAN_DELETE != 0 {
+            flag |= EventFlag::ItemRemoved;
+        }
+        if mask & (libc::FAN_MOVED_FROM | libc::FAN_MOVED_TO) != 0 {
+            flag |= EventFlag::ItemRenamed;
+        }
+        if mask & libc::FAN_MODIFY != 0 {
+            flag |= EventFlag::ItemModified;
+        }
+        if mask & libc::FAN_ATTRIB != 0 {
+            flag |= EventFlag::ItemInodeMetaMod;
+        }
+        events.push(FsEvent {
+            path: self.reported(&path),
+            flag,
+            id,
+        });
+    }
+}
+
+/// Pick the `(fsid, file_handle, name)` triple out of an event's info records.
+fn parse_dfid_name(mut info: &[u8]) -> Option<(Fsid, &[u8], &[u8])> {
+    const HEADER: usize = size_of::<libc::fanotify_event_info_header>();
+    const FID: usize = size_of::<libc::fanotify_event_info_fid>();
+    const HANDLE: usize = size_of::<libc::file_handle>();
+    while info.len() >= HEADER {
+        let header: libc::fanotify_event_info_header =
+            unsafe { std::ptr::read_unaligned(info.as_ptr().cast()) };
+        let len = (header.len as usize).min(info.len());
+        let (record, rest) = info.split_at(len);
+        info = rest;
+        if len < FID + HANDLE
+            || !matches!(
+                header.info_type,
+                libc::FAN_EVENT_INFO_TYPE_DFID_NAME | libc::FAN_EVENT_INFO_TYPE_DFID
+            )
+        {
+            continue;
+        }
+        let fid: libc::fanotify_event_info_fid =
+            unsafe { std::ptr::read_unaligned(record.as_ptr().cast()) };
+        let handle_header: libc::file_handle =
+            unsafe { std::ptr::read_unaligned(record[FID..].as_ptr().cast()) };
+        let handle_end = (FID + HANDLE + handle_header.handle_bytes as usize).min(record.len());
+        let handle = &record[FID..handle_end];
+        let name = &record[handle_end..];
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+        return Some((fid.fsid.val, handle, name));
+    }
+    None
+}
+
+/// Mount points from `/proc/self/mountinfo`, with octal escapes decoded.
+fn mount_points() -> Vec<PathBuf> {
+    let Ok(mountinfo) = fs::read("/proc/self/mountinfo") else {
+        return Vec::new();
+    };
+    mountinfo
+        .split(|&b| b == b'\n')
+        .filter_map(|line| line.split(|&b| b == b' ').nth(4))
+        .map(|raw| PathBuf::from(OsStr::from_bytes(&unescape_mount_point(raw))))
+        .collect()
+}
+
+fn unescape_mount_point(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'\\'
+            && let Some(octal) = raw.get(i + 1..i + 4)
+            && let Some(byte) = std::str::from_utf8(octal)
+                .ok()
+                .and_then(|octal| u8::from_str_radix(octal, 8).ok())
+        {
+            out.push(byte);
+            i += 4;
+        } else {
+            out.push(raw[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+impl NotifySource for Fanotify {
+    fn raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+
+    fn read_events(&mut self, events: &mut Vec<FsEvent>) {
+        const METADATA: usize = size_of::<libc::fanotify_event_metadata>();
+        // `u64` storage keeps the buffer aligned for `fanotify_event_metadata`.
+        let mut buffer = vec![0u64; READ_BUFFER_BYTES / size_of::<u64>()];
+        loop {
+            let read = unsafe {
+                libc::read(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr().cast(),
+                    READ_BUFFER_BYTES,
+                )
+            };
+            if read <= 0 {
+                // EAGAIN: the queue is drained.
+                return;
+            }
+            let bytes =
+                unsafe { std::slice::from_raw_parts(buffer.as_ptr().cast::<u8>(), read as usize) };
+            let mut offset = 0;
+            while offset + METADATA <= bytes.len() {
+                let metadata: libc::fanotify_event_metadata =
+                    unsafe { std::ptr::read_unaligned(bytes[offset..].as_ptr().cast()) };
+                let event_len = metadata.event_len as usize;
+                if event_len < METADATA || offset + event_len > bytes.len() {
+                    break;
+                }
+                if metadata.fd >= 0 {
+                    // Not expected in fid mode, but never leak a descriptor.
+                    drop(unsafe { OwnedFd::from_raw_fd(metadata.fd) });
+                }
+                let info = &bytes[offset + metadata.metadata_len as usize..offset + event_len];
+                self.translate(metadata.mask, info, events);
+                offset += event_len;
+            }
+        }
+    }
+}
+
+impl EventWatcher {
+    /// Watch every filesystem at or below `path` with fanotify.
+    ///
+    /// Returns an error instead of a watcher when fanotify is unavailable,
+    /// typically for lack of `CAP_SYS_ADMIN`. Like inotify it keeps no history,
+    /// so `since_event_id` is not replayed and `HistoryDone` arrives right away.
+    ///
+    /// The kernel reports canonical paths; they are rebased onto `path` as
+    /// given, so a symlinked or non-canonical root still sees its events.
+    pub fn spawn_fanotify(
+        path: String,
+        _since_event_id: EventId,
+        latency: f64,
+        ignore_paths: Box<[PathBuf]>,
+        include_paths: Box<[PathBuf]>,
+    ) -> io::Result<(dev_t, EventWatcher)> {
+        let root = PathBuf::from(path);
+        let dev = fs::metadata(&root)?.dev();
+        let fanotify = Fanotify::new(
+            root.canonicalize()?,
+            root.clone(),
+            &ignore_paths,
+            &include_paths,
+        )?;
+        let (_cancellation_token, cancellation_token_rx) = bounded::<()>(1);
+        let (sender, receiver) = unbounded();
+        let latency = Duration::from_secs_f64(latency.max(0.0));
+        std::thread::Builder::new()
+            .name("cardinal-sdk-event-watcher".to_string())
+            .spawn(move || {
+                let _ = sender.send(vec![FsEvent {
+                    path: root,
+                    flag: EventFlag::HistoryDone,
+                    id: current_event_id(),
+                }]);
+                linux::run(
+                    fanotify,
+                    latency,
+                    &ignore_paths,
+                    &include_paths,
+                    sender,
+                    cancellation_token_rx,
+                );
+            })
+            .unwrap();
+        Ok((
+            dev,
+            EventWatcher {
+                receiver,
+                _cancellation_token,
+                kind: SourceKind::Fanotify,
+                dev,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScanType;
+    use crossbeam_channel::RecvTimeoutError;
+    use std::time::Instant;
+    use tempfile::tempdir;
+
+    /// `None` when the test process may not use fanotify.
+    fn spawn_watcher(root: &Path, ignore_paths: Vec<PathBuf>) -> Option<EventWatcher> {
+        let (_, watcher) = EventWatcher::spawn_fanotify(
+            root.to_str().unwrap().to_string(),
+            current_event_id(),
+            0.05,
+            ignore_paths.into_boxed_slice(),
+            Vec::new().into_boxed_slice(),
+        )
+        .ok()?;
+        assert_eq!(watcher.kind, SourceKind::Fanotify);
+        let initial_events = watcher.recv().unwrap();
+        assert!(initial_events[0].flag.contains(EventFlag::HistoryDone));
+        Some(watcher)
+    }
+
+    fn collect_until(watcher: &EventWatcher, target: &Path) -> Vec<FsEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut seen = Vec::new();
+        while Instant::now() < deadline && !seen.iter().any(|e: &FsEvent| e.path == target) {
+            match watcher.recv_timeout(Duration::from_millis(200)) {
+                Ok(batch) => seen.extend(batch),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn unescapes_mount_points() {
+        assert_eq!(
+            unescape_mount_point(br"/mnt/with\040space\134x"),
+            b"/mnt/with space\\x"
+        );
+        assert_eq!(unescape_mount_point(br"/plain\0"), b"/plain\\0");
+    }
+
+    #[test]
+    fn reports_changes_as_folder_scans() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let Some(watcher) = spawn_watcher(&root, Vec::new()) else {
+            return;
+        };
+
+        let nested = root.join("nested");
+        fs::create_dir(&nested).unwrap();
+        let file = nested.join("created.txt");
+        fs::write(&file, "cardinal").unwrap();
+
+        let seen = collect_until(&watcher, &file);
+        let created_dir = seen.iter().find(|e| e.path == nested).unwrap();
+        assert!(
+            created_dir
+                .flag
+                .contains(EventFlag::ItemCreated | EventFlag::ItemIsDir)
+        );
+        let created_file = seen.iter().find(|e| e.path == file).unwrap();
+        assert!(created_file.flag.contains(EventFlag::ItemCreated));
+        assert!(
+            seen.iter()
+                .all(|event| event.flag.scan_type() == ScanType::Folder)
+        );
+        assert!(seen.iter().all(|event| event.path.starts_with(&root)));
+    }
+
+    #[test]
+    fn reports_paths_under_a_symlinked_root() {
+        let temp_dir = tempdir().unwrap();
+        let real = temp_dir.path().join("real");
+        fs::create_dir(&real).unwrap();
+        let root = temp_dir.path().join("link");
+        std::os::unix::fs::symlink(&real, &root).unwrap();
+        let Some(watcher) = spawn_watcher(&root, Vec::new()) else {
+            return;
+        };
+
+        let file = root.join("created.txt");
+        fs::write(&file, "cardinal").unwrap();
+
+        let seen = collect_until(&watcher, &file);
+        assert!(seen.iter().any(|event| event.path == file));
+        assert!(seen.iter().all(|event| event.path.starts_with(&root)));
+    }
+
+    #[test]
+    fn drops_ignored_paths() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir(root.join("ignored")).unwrap();
+        let Some(watcher) = spawn_watcher(&root, vec![root.join("ignored")]) else {
+            return;
+        };
+
+        fs::write(root.join("ignored/hidden.txt"), "cardinal").unwrap();
+        let visible = root.join("visible.txt");
+        fs::write(&visible, "cardinal").unwrap();
+
+        let seen = collect_until(&watcher, &visible);
+        assert!(seen.iter().any(|event| event.path == visible));
+        assert!(
+            seen.iter()
+                .all(|event| !event.path.starts_with(root.join("ignored")))
+        );
+    }
+}
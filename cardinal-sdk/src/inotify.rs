@@ -0,0 +1,485 @@
+use crate::{
+    EventFlag, EventId, EventWatcher, FsEvent, SourceKind, current_event_id,
+    linux::{self, NotifySource},
+    utils::reserve_event_ids,
+};
+use crossbeam_channel::{bounded, unbounded};
+use libc::dev_t;
+use std::{
+    collections::HashMap,
+    ffi::{CString, OsStr},
+    fs, io,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        unix::{ffi::OsStrExt, fs::MetadataExt},
+    },
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_MODIFY
+    | libc::IN_ATTRIB
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF
+    | libc::IN_ONLYDIR
+    | libc::IN_DONT_FOLLOW
+    | libc::IN_EXCL_UNLINK;
+
+const READ_BUFFER_BYTES: usize = 64 * 1024;
+
+/// A recursive set of inotify watches rooted at one directory.
+///
+/// inotify only watches single directories, so every directory below the root
+/// gets its own watch descriptor. Directories created or moved into the tree
+/// are picked up as their events arrive; directories moved out are dropped.
+///
+/// The two halves of a rename share a cookie and arrive back to back; they are
+/// given adjacent event ids so consumers can pair them like FSEvents renames.
+struct Inotify {
+    fd: OwnedFd,
+    root: PathBuf,
+    watches: HashMap<i32, PathBuf>,
+    /// Cookie and event id of the `IN_MOVED_FROM` that was translated last.
+    pending_move: Option<(u32, EventId)>,
+    ignore_paths: Box<[PathBuf]>,
+    include_paths: Box<[PathBuf]>,
+}
+
+impl Inotify {
+    fn new(
+        root: PathBuf,
+        ignore_paths: Box<[PathBuf]>,
+        include_paths: Box<[PathBuf]>,
+    ) -> io::Result<Self> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut inotify = Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            root,
+            watches: HashMap::new(),
+            pending_move: None,
+            ignore_paths,
+            include_paths,
+        };
+        let root = inotify.root.clone();
+        inotify.add_tree(&root);
+        Ok(inotify)
+    }
+
+    /// Watch `dir` and every directory below it, skipping ignored subtrees.
+    ///
+    /// Symlinks are never followed, matching `fswalk`. Directories that vanish
+    /// or can't be read are skipped silently; their parent's events still
+    /// cover them.
+    fn add_tree(&mut self, dir: &Path) {
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            if fswalk::should_ignore_path(&dir, &self.ignore_paths, &self.include_paths) {
+                continue;
+            }
+            if !self.add_watch(&dir) {
+                continue;
+            }
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+                    pending.push(entry.path());
+                }
+            }
+        }
+    }
+
+    fn add_watch(&mut self, dir: &Path) -> bool {
+        let Ok(c_path) = CString::new(dir.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let wd =
+            unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            return false;
+        }
+        self.watches.insert(wd, dir.to_path_buf());
+        true
+    }
+
+    /// Drop the watches for `dir` and everything below it.
+    fn remove_tree(&mut self, dir: &Path) {
+        let fd = self.fd.as_raw_fd();
+        self.watches.retain(|&wd, path| {
+            if path.starts_with(dir) {
+                unsafe { libc::inotify_rm_watch(fd, wd) };
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Translate one inotify record into FSEvents-style events, keeping the watch
+    /// set in sync with directory creations, moves and removals.
+    fn translate(
+        &mut self,
+        wd: i32,
+        mask: u32,
+        cookie: u32,
+        name: &OsStr,
+        events: &mut Vec<FsEvent>,
+    ) {
+        let id = match self.pending_move.take() {
+            Some((from_cookie, from_id))
+                if mask & libc::IN_MOVED_TO != 0 && cookie == from_cookie =>
+            {
+                from_id + 1
+            }
+            _ if mask & libc::IN_MOVED_FROM != 0 => {
+                // Reserve the next id for the matching `IN_MOVED_TO`.
+                let id = reserve_event_ids(2);
+                self.pending_move = Some((cookie, id));
+                id
+            }
+            _ => current_event_id(),
+        };
+        if mask & libc::IN_Q_OVERFLOW != 0 {
+            // The kernel dropped events, so nothing short of a rescan is trustworthy.
+            events.push(FsEvent {
+                path: self.root.clone(),
+                flag: EventFlag::MustScanSubDirs | EventFlag::KernelDropped | EventFlag::ItemIsDir,
+                id,
+            });
+            return;
+        }
+        if mask & libc::IN_IGNORED != 0 {
+            self.watches.remove(&wd);
+            return;
+        }
+        let Some(dir) = self.watches.get(&wd) else {
+            return;
+        };
+        if mask & (libc::IN_DELETE_SELF | libc::IN_MOVE_SELF) != 0 {
+            // Non-root directories are reported through their parent's watch.
+            if *dir == self.root {
+                events.push(FsEvent {
+                    path: self.root.clone(),
+                    flag: EventFlag::RootChanged,
+                    id,
+                });
+            }
+            return;
+        }
+        if name.is_empty() {
+            // Self events duplicate what the parent watch already reports.
+            return;
+        }
+        let path = dir.join(name);
+        let is_dir = mask & libc::IN_ISDIR != 0;
+        let mut flag = if is_dir {
+            EventFlag::ItemIsDir
+        } else {
+            EventFlag::ItemIsFile
+        };
+        if mask & libc::IN_CREATE != 0 {
+            flag |= EventFlag::ItemCreated;
+        }
+        if mask & libc::IN_DELETE != 0 {
+            flag |= EventFlag::ItemRemoved;
+        }
+        if mask & (libc::IN_MOVED_FROM | libc::IN_MOVED_TO) != 0 {
+            flag |= EventFlag::ItemRenamed;
+        }
+        if mask & libc::IN_MODIFY != 0 {
+            flag |= EventFlag::ItemModified;
+        }
+        if mask & libc::IN_ATTRIB != 0 {
+            flag |= EventFlag::ItemInodeMetaMod;
+        }
+        if is_dir {
+            if mask & libc::IN_MOVED_FROM != 0 {
+                self.remove_tree(&path);
+            }
+            if mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                self.add_tree(&path);
+            }
+        }
+        events.push(FsEvent { path, flag, id });
+    }
+}
+
+impl NotifySource for Inotify {
+    fn raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+
+    fn read_events(&mut self, events: &mut Vec<FsEvent>) {
+        // `u64` storage keeps the buffer aligned for `inotify_event`.
+        let mut buffer = vec![0u64; READ_BUFFER_BYTES / size_of::<u64>()];
+        loop {
+            let read = unsafe {
+                libc::read(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr().cast(),
+                    READ_BUFFER_BYTES,
+                )
+            };
+            if read <= 0 {
+                // EAGAIN: the queue is drained.
+                return;
+            }
+            let bytes =
+                unsafe { std::slice::from_raw_parts(buffer.as_ptr().cast::<u8>(), read as usize) };
+            let mut offset = 0;
+            while offset + size_of::<libc::inotify_event>() <= bytes.len() {
+                let raw: libc::inotify_event =
+                    unsafe { std::ptr::read_unaligned(bytes[offset..].as_ptr().cast()) };
+                let name_start = offset + size_of::<libc::inotify_event>();
+                let name_end = (name_start + raw.len as usize).min(bytes.len());
+                let name = &bytes[name_start..name_end];
+                let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+                self.translate(
+                    raw.wd,
+                    raw.mask,
+                    raw.cookie,
+                    OsStr::from_bytes(name),
+                    events,
+                );
+                offset = name_end;
+            }
+        }
+    }
+}
+
+impl EventWatcher {
+    /// Watch `path` recursively with inotify, one watch per directory.
+    ///
+    /// inotify keeps no history, so `since_event_id` can't be replayed:
+    /// `HistoryDone` is delivered as soon as the watches are installed, and
+    /// changes made before that point are not reported.
+    pub fn spawn_inotify(
+        path: String,
+        _since_event_id: EventId,
+        latency: f64,
+        ignore_paths: Box<[PathBuf]>,
+        include_paths: Box<[PathBuf]>,
+    ) -> (dev_t, EventWatcher) {
+        let (_cancellation_token, cancellation_token_rx) = bounded::<()>(1);
+        let (sender, receiver) = unbounded();
+        let root = PathBuf::from(path);
+        let dev = fs::metadata(&root).map(|meta| meta.dev()).unwrap_or(0);
+        let latency = Duration::from_secs_f64(latency.max(0.0));
+        std::thread::Builder::new()
+            .name("cardinal-sdk-event-watcher".to_string())
+            .spawn(move || {
+                let inotify = Inotify::new(root.clone(), ignore_paths, include_paths);
+                let _ = sender.send(vec![FsEvent {
+                    path: root,
+                    flag: EventFlag::HistoryDone,
+                    id: current_event_id(),
+                }]);
+                match inotify {
+                    Ok(inotify) => {
+                        let (ignore_paths, include_paths) =
+                            (inotify.ignore_paths.clone(), inotify.include_paths.clone());
+                        linux::run(
+                            inotify,
+                            latency,
+                            &ignore_paths,
+                            &include_paths,
+                            sender,
+                            cancellation_token_rx,
+                        )
+                    }
+                    Err(_) => {
+                        let _ = cancellation_token_rx.recv();
+                    }
+                }
+            })
+            .unwrap();
+        (
+            dev,
+            EventWatcher {
+                receiver,
+                _cancellation_token,
+                kind: SourceKind::Inotify,
+                dev,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::RecvTimeoutError;
+    use std::time::Instant;
+    use tempfile::tempdir;
+
+    fn spawn_watcher(root: &Path) -> EventWatcher {
+        let (_, watcher) = EventWatcher::spawn_inotify(
+            root.to_str().unwrap().to_string(),
+            current_event_id(),
+            0.05,
+            Vec::new().into_boxed_slice(),
+            Vec::new().into_boxed_slice(),
+        );
+        let initial_events = watcher.recv().unwrap();
+        assert!(initial_events[0].flag.contains(EventFlag::HistoryDone));
+        watcher
+    }
+
+    fn wait_for(watcher: &EventWatcher, pred: impl Fn(&FsEvent) -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            match watcher.recv_timeout(Duration::from_millis(200)) {
+                Ok(batch) if batch.iter().any(&pred) => return true,
+                Ok(_) | Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn event_watcher_on_non_existent_path() {
+        let (dev, watcher) = EventWatcher::spawn_inotify(
+            "/e mm".to_string(),
+            current_event_id(),
+            0.05,
+            Vec::new().into_boxed_slice(),
+            Vec::new().into_boxed_slice(),
+        );
+        assert_eq!(dev, 0);
+        let initial_events = watcher.recv().unwrap();
+        assert_eq!(initial_events.len(), 1);
+        assert!(initial_events[0].flag.contains(EventFlag::HistoryDone));
+        assert!(matches!(
+            watcher.recv_timeout(Duration::from_millis(300)),
+            Err(RecvTimeoutError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn reports_created_and_removed_files() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let watcher = spawn_watcher(&root);
+
+        let file = root.join("created.txt");
+        fs::write(&file, "cardinal").unwrap();
+        assert!(wait_for(&watcher, |event| {
+            event.path == file
+                && event
+                    .flag
+                    .contains(EventFlag::ItemCreated | EventFlag::ItemIsFile)
+        }));
+
+        fs::remove_file(&file).unwrap();
+        assert!(wait_for(&watcher, |event| {
+            event.path == file && event.flag.contains(EventFlag::ItemRemoved)
+        }));
+    }
+
+    #[test]
+    fn watches_directories_created_after_spawn() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let watcher = spawn_watcher(&root);
+
+        let nested = root.join("a");
+        fs::create_dir(&nested).unwrap();
+        assert!(wait_for(&watcher, |event| {
+            event.path == nested
+                && event
+                    .flag
+                    .contains(EventFlag::ItemCreated | EventFlag::ItemIsDir)
+        }));
+
+        let file = nested.join("inner.txt");
+        fs::write(&file, "cardinal").unwrap();
+        assert!(wait_for(&watcher, |event| event.path == file));
+    }
+
+    #[test]
+    fn follows_directories_moved_within_the_tree() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("from/deep")).unwrap();
+        let watcher = spawn_watcher(&root);
+
+        fs::rename(root.join("from"), root.join("to")).unwrap();
+        assert!(wait_for(&watcher, |event| {
+            event.path == root.join("to") && event.flag.contains(EventFlag::ItemRenamed)
+        }));
+
+        let file = root.join("to/deep/moved.txt");
+        fs::write(&file, "cardinal").unwrap();
+        assert!(wait_for(&watcher, |event| event.path == file));
+    }
+
+    #[test]
+    fn gives_rename_halves_adjacent_event_ids() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir(root.join("a")).unwrap();
+        fs::create_dir(root.join("b")).unwrap();
+        fs::write(root.join("a/file.txt"), "cardinal").unwrap();
+        let watcher = spawn_watcher(&root);
+
+        fs::rename(root.join("a/file.txt"), root.join("b/renamed.txt")).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut renames = Vec::new();
+        while renames.len() < 2 && Instant::now() < deadline {
+            if let Ok(batch) = watcher.recv_timeout(Duration::from_millis(200)) {
+                renames.extend(
+                    batch
+                        .into_iter()
+                        .filter(|event| event.flag.contains(EventFlag::ItemRenamed)),
+                );
+            }
+        }
+        assert_eq!(renames.len(), 2, "{renames:?}");
+        assert_eq!(renames[0].path, root.join("a/file.txt"));
+        assert_eq!(renames[1].path, root.join("b/renamed.txt"));
+        assert_eq!(renames[1].id, renames[0].id + 1);
+
+        // Ids handed out afterwards stay ahead of the reserved pair.
+        assert!(current_event_id() > renames[1].id);
+    }
+
+    #[test]
+    fn skips_ignored_subtrees() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir(root.join("ignored")).unwrap();
+        let (_, watcher) = EventWatcher::spawn_inotify(
+            root.to_str().unwrap().to_string(),
+            current_event_id(),
+            0.05,
+            vec![root.join("ignored")].into_boxed_slice(),
+            Vec::new().into_boxed_slice(),
+        );
+        watcher.recv().unwrap();
+
+        fs::write(root.join("ignored/hidden.txt"), "cardinal").unwrap();
+        let visible = root.join("visible.txt");
+        fs::write(&visible, "cardinal").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut seen = Vec::new();
+        while Instant::now() < deadline && !seen.contains(&visible) {
+            if let Ok(batch) = watcher.recv_timeout(Duration::from_millis(200)) {
+                seen.extend(batch.into_iter().map(|event| event.path));
+            }
+        }
+        assert!(seen.contains(&visible));
+        assert!(
+            seen.iter()
+                .all(|path| !path.starts_with(root.join("ignored")))
+        );
+    }
+}
@@ -1,10 +1,34 @@
+mod change_source;
 mod event;
 mod event_flag;
+mod event_log;
+#[cfg(target_os = "macos")]
 mod event_stream;
+mod event_watcher;
+#[cfg(target_os = "linux")]
+mod fanotify;
+#[cfg(target_os = "linux")]
+mod inotify;
+#[cfg(target_os = "linux")]
+mod linux;
+mod poll;
 mod utils;
 
+pub use change_source::{ChangeSource, EventClock, EventId, ResumeCursor, SourceKind};
 pub use event::FsEvent;
 pub use event_flag::{EventFlag, EventType, ScanType};
-pub use event_stream::{EventStream, EventWatcher};
+pub use event_log::{
+    EVENT_LOG_VERSION, EventLogHeader, EventLogReader, EventLogWriter, RecordedBatch,
+    RecordedEvent, ReplaySource, rebase_path,
+};
+#[cfg(target_os = "macos")]
+pub use event_stream::EventStream;
+pub use event_watcher::EventWatcher;
+#[cfg(target_os = "macos")]
 pub use objc2_core_services::FSEventStreamEventId;
+pub use poll::{PollConfig, PolledDir};
 pub use utils::{current_event_id, event_id_to_timestamp};
+
+/// Event ids outside FSEvents are synthesized by `current_event_id()`.
+#[cfg(not(target_os = "macos"))]
+pub type FSEventStreamEventId = u64;
@@ -0,0 +1,99 @@
+use crate::{EventId, EventWatcher, FsEvent, event_watcher::filter_events_by_paths};
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use libc::dev_t;
+use std::{
+    os::fd::RawFd,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// How often a worker wakes up to notice that its `EventWatcher` was dropped.
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A non-blocking notification fd (inotify or fanotify) that can be drained into `FsEvent`s.
+pub(crate) trait NotifySource {
+    fn raw_fd(&self) -> RawFd;
+
+    /// Drain every queued record into `events`.
+    fn read_events(&mut self, events: &mut Vec<FsEvent>);
+}
+
+/// Wait up to `timeout` for `fd` to become readable.
+fn wait_readable(fd: RawFd, timeout: Duration) -> bool {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+    unsafe { libc::poll(&mut pollfd, 1, timeout) > 0 }
+}
+
+fn is_cancelled(cancellation_token_rx: &Receiver<()>) -> bool {
+    matches!(
+        cancellation_token_rx.try_recv(),
+        Err(TryRecvError::Disconnected)
+    )
+}
+
+/// Pump `source` until the owning `EventWatcher` is dropped.
+pub(crate) fn run(
+    mut source: impl NotifySource,
+    latency: Duration,
+    ignore_paths: &[PathBuf],
+    include_paths: &[PathBuf],
+    sender: Sender<Vec<FsEvent>>,
+    cancellation_token_rx: Receiver<()>,
+) {
+    let fd = source.raw_fd();
+    while !is_cancelled(&cancellation_token_rx) {
+        if !wait_readable(fd, CANCELLATION_POLL_INTERVAL) {
+            continue;
+        }
+        let mut events = Vec::new();
+        source.read_events(&mut events);
+        // Coalesce bursts for `latency`, the same way FSEvents batches callbacks.
+        let deadline = Instant::now() + latency;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !wait_readable(fd, remaining) {
+                break;
+            }
+            source.read_events(&mut events);
+        }
+        let events = filter_events_by_paths(events, ignore_paths, include_paths);
+        if !events.is_empty() && sender.send(events).is_err() {
+            return;
+        }
+    }
+}
+
+impl EventWatcher {
+    /// Watch `path` with fanotify when the process may, otherwise with inotify.
+    ///
+    /// fanotify needs `CAP_SYS_ADMIN` and a kernel with `FAN_REPORT_DFID_NAME`
+    /// (5.9+); without either the watcher logs why and uses per-directory
+    /// inotify watches instead.
+    pub fn spawn(
+        path: String,
+        since_event_id: EventId,
+        latency: f64,
+        ignore_paths: Box<[PathBuf]>,
+        include_paths: Box<[PathBuf]>,
+    ) -> (dev_t, EventWatcher) {
+        match Self::spawn_fanotify(
+            path.clone(),
+            since_event_id,
+            latency,
+            ignore_paths.clone(),
+            include_paths.clone(),
+        ) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                info!("fanotify unavailable ({err}), falling back to inotify");
+                Self::spawn_inotify(path, since_event_id, latency, ignore_paths, include_paths)
+            }
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use crate::{
+    EventFlag, EventWatcher, FsEvent, SourceKind, current_event_id,
+    event_watcher::filter_events_by_paths,
+};
+use crossbeam_channel::{RecvTimeoutError, bounded, unbounded};
+use libc::dev_t;
+use std::{
+    collections::HashSet,
+    fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A directory the polling watcher keeps an eye on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolledDir {
+    pub path: PathBuf,
+    /// Modification time in seconds since the epoch as last recorded by the
+    /// index, or `None` when the index never fetched it.
+    pub mtime: Option<u32>,
+}
+
+/// Knobs for [`EventWatcher::spawn_polling`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollConfig {
+    /// Pause between two polling cycles.
+    pub interval: Duration,
+    /// Maximum number of `stat`/`read_dir` calls per cycle. Trees larger than
+    /// this are covered round-robin over several cycles.
+    pub io_budget: usize,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            io_budget: 4096,
+        }
+    }
+}
+
+/// What a directory's mtime is compared against on its next visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Baseline {
+    /// No trusted mtime yet. The directory is listed once to discover its
+    /// subdirectories but not reported.
+    Unknown,
+    /// Whole seconds recorded by the index, which is all it keeps.
+    Seeded(u32),
+    /// Full-resolution mtime from the poller's own previous visit, so two
+    /// changes within one second are still told apart.
+    Observed(Option<SystemTime>),
+}
+
+#[derive(Debug)]
+struct WatchedDir {
+    path: PathBuf,
+    baseline: Baseline,
+}
+
+/// Round-robin mtime poller over every directory below `root`.
+///
+/// A directory's mtime only moves when entries are added, removed or renamed
+/// in it, so content-only changes to existing files are not noticed.
+struct Poller {
+    root: PathBuf,
+    dirs: Vec<WatchedDir>,
+    known: HashSet<PathBuf>,
+    cursor: usize,
+    ignore_paths: Box<[PathBuf]>,
+    include_paths: Box<[PathBuf]>,
+}
+
+fn mtime_secs(modified: Option<SystemTime>) -> Option<u32> {
+    modified
+        .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+        .and_then(|x| u32::try_from(x.as_secs()).ok())
+        .filter(|&x| x != 0)
+}
+
+impl Poller {
+    fn new(
+        root: PathBuf,
+        seeds: Vec<PolledDir>,
+        ignore_paths: Box<[PathBuf]>,
+        include_paths: Box<[PathBuf]>,
+    ) -> Self {
+        let mut poller = Self {
+            root: root.clone(),
+            dirs: Vec::with_capacity(seeds.len() + 1),
+            known: HashSet::with_capacity(seeds.len() + 1),
+            cursor: 0,
+            ignore_paths,
+            include_paths,
+        };
+        poller.track(PolledDir {
+            path: root,
+            mtime: None,
+        });
+        for seed in seeds {
+            if seed.path.starts_with(&poller.root) {
+                poller.track(seed);
+            }
+        }
+        poller
+    }
+
+    fn track(&mut self, PolledDir { path, mtime }: PolledDir) {
+        if fswalk::should_ignore_path(&path, &self.ignore_paths, &self.include_paths) {
+            return;
+        }
+        let baseline = mtime.map_or(Baseline::Unknown, Baseline::Seeded);
+        if let Some(existing) = self.dirs.iter_mut().take(1).find(|dir| dir.path == path) {
+            // The root is tracked first; let a seed provide its recorded mtime.
+            existing.baseline = baseline;
+            return;
+        }
+        if self.known.insert(path.clone()) {
+            self.dirs.push(WatchedDir { path, baseline });
+        }
+    }
+
+    /// Start tracking the subdirectories of `dir` that aren't tracked yet.
+    fn discover(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            // Symlinks are not followed, matching `fswalk`.
+            if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+                self.track(PolledDir {
+                    path: entry.path(),
+                    mtime: None,
+                });
+            }
+        }
+    }
+
+    /// Stat up to `io_budget` directories and report those whose mtime moved.
+    fn cycle(&mut self, io_budget: usize) -> Vec<FsEvent> {
+        let mut events = Vec::new();
+        let mut spent = 0;
+        let mut remaining = self.dirs.len();
+        while spent < io_budget && remaining > 0 && !self.dirs.is_empty() {
+            if self.cursor >= self.dirs.len() {
+                self.cursor = 0;
+            }
+            remaining -= 1;
+            spent += 1;
+            let dir = &mut self.dirs[self.cursor];
+            let is_root = dir.path == self.root;
+            match fs::symlink_metadata(&dir.path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    let modified = metadata.modified().ok();
+                    let changed = match dir.baseline {
+                        Baseline::Unknown => false,
+                        Baseline::Seeded(secs) => mtime_secs(modified) != Some(secs),
+                        Baseline::Observed(previous) => previous != modified,
+                    };
+                    let relist = changed || dir.baseline == Baseline::Unknown;
+                    dir.baseline = Baseline::Observed(modified);
+                    let path = dir.path.clone();
+                    self.cursor += 1;
+                    if changed {
+                        events.push(FsEvent {
+                            path: path.clone(),
+                            flag: EventFlag::ItemIsDir | EventFlag::ItemModified,
+                            id: current_event_id(),
+                        });
+                    }
+                    if relist {
+                        spent += 1;
+                        self.discover(&path);
+                    }
+                }
+                _ if is_root => {
+                    if dir.baseline != Baseline::Unknown {
+                        events.push(FsEvent {
+                            path: self.root.clone(),
+                            flag: EventFlag::RootChanged,
+                            id: current_event_id(),
+                        });
+                    }
+                    dir.baseline = Baseline::Unknown;
+                    self.cursor += 1;
+                }
+                _ => {
+                    // Gone or no longer a directory; the parent's mtime covers the change.
+                    let gone = self.dirs.swap_remove(self.cursor);
+                    self.known.remove(&gone.path);
+                }
+            }
+        }
+        events
+    }
+}
+
+impl EventWatcher {
+    /// Watch `path` by polling directory mtimes, for filesystems that deliver
+    /// no change notifications (NFS, SMB, most FUSE mounts).
+    ///
+    /// `dirs` seeds the poller with the directories the index knows about and
+    /// the mtimes it recorded for them; directories without a recorded mtime
+    /// get a baseline on their first visit and are listed once to find
+    /// subdirectories the index missed. Every cycle stats at most
+    /// `config.io_budget` directories and reports each changed one as a
+    /// `ScanType::Folder` event.
+    pub fn spawn_polling(
+        path: String,
+        dirs: Vec<PolledDir>,
+        config: PollConfig,
+        ignore_paths: Box<[PathBuf]>,
+        include_paths: Box<[PathBuf]>,
+    ) -> (dev_t, EventWatcher) {
+        let (_cancellation_token, cancellation_token_rx) = bounded::<()>(1);
+        let (sender, receiver) = unbounded();
+        let root = PathBuf::from(path);
+        let dev = fs::metadata(&root).map(|meta| meta.dev()).unwrap_or(0);
+        std::thread::Builder::new()
+            .name("cardinal-sdk-event-watcher".to_string())
+            .spawn(move || {
+                let _ = sender.send(vec![FsEvent {
+                    path: root.clone(),
+                    flag: EventFlag::HistoryDone,
+                    id: current_event_id(),
+                }]);
+                let mut poller = Poller::new(root, dirs, ignore_paths, include_paths);
+                loop {
+                    let events = poller.cycle(config.io_budget.max(1));
+                    let events =
+                        filter_events_by_paths(events, &poller.ignore_paths, &poller.include_paths);
+                    if !events.is_empty() && sender.send(events).is_err() {
+                        return;
+                    }
+                    match cancellation_token_rx.recv_timeout(config.interval) {
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+            })
+            .unwrap();
+        (
+            dev,
+            EventWatcher {
+                receiver,
+                _cancellation_token,
+                kind: SourceKind::Polling,
+                dev,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScanType;
+    use std::fs::FileTimes;
+    use tempfile::tempdir;
+
+    fn set_mtime(path: &Path, secs: u64) {
+        set_mtime_millis(path, secs * 1_000);
+    }
+
+    fn set_mtime_millis(path: &Path, millis: u64) {
+        let dir = fs::File::open(path).unwrap();
+        dir.set_times(FileTimes::new().set_modified(UNIX_EPOCH + Duration::from_millis(millis)))
+            .unwrap();
+    }
+
+    fn poller(root: &Path, seeds: Vec<PolledDir>) -> Poller {
+        Poller::new(
+            root.to_path_buf(),
+            seeds,
+            Vec::new().into_boxed_slice(),
+            Vec::new().into_boxed_slice(),
+        )
+    }
+
+    #[test]
+    fn reports_directories_whose_mtime_differs_from_the_index() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("b")).unwrap();
+        set_mtime(&root.join("a"), 1_000);
+        set_mtime(&root.join("b"), 2_000);
+
+        let mut poller = poller(
+            root,
+            vec![
+                PolledDir {
+                    path: root.join("a"),
+                    mtime: Some(1_000),
+                },
+                PolledDir {
+                    path: root.join("b"),
+                    mtime: Some(1_500),
+                },
+            ],
+        );
+        let events = poller.cycle(usize::MAX);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, root.join("b"));
+        assert_eq!(events[0].flag.scan_type(), ScanType::Folder);
+
+        // The new mtime becomes the baseline.
+        assert!(poller.cycle(usize::MAX).is_empty());
+        set_mtime(&root.join("a"), 3_000);
+        let events = poller.cycle(usize::MAX);
+        assert_eq!(
+            events.iter().map(|e| e.path.clone()).collect::<Vec<_>>(),
+            vec![root.join("a")]
+        );
+    }
+
+    #[test]
+    fn notices_changes_within_the_same_second() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let dir = root.join("a");
+        fs::create_dir(&dir).unwrap();
+        set_mtime_millis(&dir, 1_000_600);
+
+        // The index only records whole seconds, so a seed matching the
+        // second is not a change.
+        let mut poller = poller(
+            root,
+            vec![PolledDir {
+                path: dir.clone(),
+                mtime: Some(1_000),
+            }],
+        );
+        assert!(poller.cycle(usize::MAX).is_empty());
+
+        // The poller's own baseline keeps the sub-second part.
+        set_mtime_millis(&dir, 1_000_700);
+        let events = poller.cycle(usize::MAX);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, dir);
+        set_mtime_millis(&dir, 1_000_800);
+        let events = poller.cycle(usize::MAX);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, dir);
+        assert!(poller.cycle(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn discovers_unknown_directories_without_reporting_them() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("x/y/z")).unwrap();
+
+        let mut poller = poller(root, Vec::new());
+        while poller.dirs.len() < 4 {
+            assert!(poller.cycle(usize::MAX).is_empty());
+        }
+        assert!(poller.cycle(usize::MAX).is_empty());
+
+        set_mtime(&root.join("x/y/z"), 42);
+        let events = poller.cycle(usize::MAX);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, root.join("x/y/z"));
+    }
+
+    #[test]
+    fn io_budget_spreads_work_over_cycles() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let seeds = (0..10)
+            .map(|i| {
+                let path = root.join(format!("d{i}"));
+                fs::create_dir(&path).unwrap();
+                set_mtime(&path, 1_000);
+                PolledDir {
+                    path,
+                    mtime: Some(999),
+                }
+            })
+            .collect();
+        let mut poller = poller(root, seeds);
+        // Listing the root and every changed directory costs a stat plus a read_dir.
+        let per_cycle: Vec<_> = (0..4).map(|_| poller.cycle(5).len()).collect();
+        assert_eq!(per_cycle, vec![2, 3, 3, 2]);
+        assert!(poller.cycle(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn drops_removed_directories_and_reports_root_removal() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("gone")).unwrap();
+        let mut poller = poller(&root, Vec::new());
+        poller.cycle(usize::MAX);
+        poller.cycle(usize::MAX);
+        assert_eq!(poller.dirs.len(), 2);
+
+        fs::remove_dir(root.join("gone")).unwrap();
+        poller.cycle(usize::MAX);
+        assert_eq!(poller.dirs.len(), 1);
+
+        fs::remove_dir(&root).unwrap();
+        let events = poller.cycle(usize::MAX);
+        assert!(
+            events
+                .iter()
+                .any(|e| e.path == root && e.flag.contains(EventFlag::RootChanged))
+        );
+    }
+
+    #[test]
+    fn spawned_poller_delivers_history_done_then_changes() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir(root.join("watched")).unwrap();
+        set_mtime(&root.join("watched"), 1_000);
+        let (_, watcher) = EventWatcher::spawn_polling(
+            root.to_str().unwrap().to_string(),
+            vec![PolledDir {
+                path: root.join("watched"),
+                mtime: Some(1_000),
+            }],
+            PollConfig {
+                interval: Duration::from_millis(20),
+                io_budget: 64,
+            },
+            Vec::new().into_boxed_slice(),
+            Vec::new().into_boxed_slice(),
+        );
+        let initial_events = watcher.recv().unwrap();
+        assert!(initial_events[0].flag.contains(EventFlag::HistoryDone));
+
+        fs::write(root.join("watched/new.txt"), "cardinal").unwrap();
+        let batch = watcher.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(batch[0].path, root.join("watched"));
+    }
+}
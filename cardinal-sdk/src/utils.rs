@@ -1,4 +1,5 @@
 use libc::dev_t;
+#[cfg(target_os = "macos")]
 use objc2_core_services::{FSEventsGetCurrentEventId, FSEventsGetLastEventIdForDeviceBeforeTime};
 use std::{collections::HashMap, time::SystemTime};
 
@@ -9,14 +10,50 @@ pub fn current_timestamp() -> i64 {
         .unwrap_or_default()
 }
 
+#[cfg(target_os = "macos")]
 pub fn current_event_id() -> u64 {
     unsafe { FSEventsGetCurrentEventId() }
 }
 
+/// inotify has no journal, so event ids are synthesized from the wall clock in
+/// microseconds. They stay strictly increasing within a process and remain
+/// comparable across restarts, which is all the cache needs from them.
+#[cfg(not(target_os = "macos"))]
+pub fn current_event_id() -> u64 {
+    reserve_event_ids(1)
+}
+
+/// Reserve `count` consecutive event ids and return the first one.
+///
+/// Backends use this to give both halves of a rename adjacent ids, the same
+/// shape FSEvents reports them in.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn reserve_event_ids(count: u64) -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static LAST_EVENT_ID: AtomicU64 = AtomicU64::new(0);
+    let now = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or_default();
+    let prev = LAST_EVENT_ID
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(now.max(last + 1) + count - 1)
+        })
+        .unwrap_or_default();
+    now.max(prev + 1)
+}
+
+#[cfg(target_os = "macos")]
 pub fn last_event_id_before_time(dev: dev_t, timestamp: i64) -> u64 {
     unsafe { FSEventsGetLastEventIdForDeviceBeforeTime(dev, timestamp as f64) }
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn last_event_id_before_time(_dev: dev_t, timestamp: i64) -> u64 {
+    (timestamp.max(0) as u64).saturating_mul(1_000_000)
+}
+
 /// Given a device id, an event id, and a cache mapping timestamps to last event ids before them,
 /// perform a binary search to find the timestamp corresponding to the event id.
 pub fn event_id_to_timestamp(dev: dev_t, event_id: u64, cache: &mut HashMap<i64, u64>) -> i64 {
@@ -72,6 +72,7 @@ fn optimize_expr(expr: Expr) -> Expr {
         Expr::And(parts) => optimize_and(parts),
         Expr::Or(parts) => optimize_or(parts),
         Expr::Not(inner) => Expr::Not(Box::new(optimize_expr(*inner))),
+        Expr::Modified(kind, inner) => Expr::Modified(kind, Box::new(optimize_expr(*inner))),
         Expr::Term(_) | Expr::Empty => expr,
     }
 }
@@ -128,22 +129,28 @@ fn optimize_or(parts: Vec<Expr>) -> Expr {
 /// - 0: Scope filters (`infolder:`, `parent:`) - narrow search space first
 /// - 1: Non-filter terms (words, phrases, boolean ops) - cheap string matching
 /// - 2: Generic filters (`ext:`, `type:`, `size:`, etc.) - moderate cost
-/// - 3: Tag filters (`tag:`) - expensive metadata access, runs last
+/// - 3: Tag filters (`tag:`) - expensive metadata access
+/// - 4: Duplicate filters (`dupe:`, `sizedupe:`, ...) - group whatever the
+///   other parts left, so they have to run last
+///
+/// A modified operand (`case:<...>`) keeps the priority of what it wraps.
 fn reorder_by_priority(parts: &mut Vec<Expr>) {
     if parts.len() <= 1 {
         return;
     }
 
-    let priority = |expr: &Expr| -> u8 {
+    fn priority(expr: &Expr) -> u8 {
         match expr {
-            Expr::Term(Term::Filter(filter)) => match filter.kind {
+            Expr::Term(Term::Filter(filter)) => match &filter.kind {
                 FilterKind::InFolder | FilterKind::Parent => 0,
                 FilterKind::Tag => 3,
+                kind if kind.is_duplicate() => 4,
                 _ => 2,
             },
+            Expr::Modified(_, inner) => priority(inner),
             _ => 1,
         }
-    };
+    }
 
     let mut keyed: Vec<_> = parts
         .drain(..)
@@ -204,6 +211,19 @@ pub enum Expr {
     /// assert_eq!(parts.len(), 2);
     /// ```
     Or(Vec<Expr>),
+    /// A modifier such as `case:` or `wfn:` written right in front of a group or
+    /// another filter, applying to all of it. `case:ABC` with a plain word
+    /// stays a [`Term::Filter`].
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, FilterKind, Term};
+    /// let Expr::Modified(kind, inner) = parse_query("case:<foo|bar>").unwrap().expr else { panic!() };
+    /// assert!(matches!(kind, FilterKind::CaseSensitive));
+    /// assert!(matches!(*inner, Expr::Or(_)));
+    /// let Expr::Modified(_, inner) = parse_query("case:regex:^Foo").unwrap().expr else { panic!() };
+    /// assert!(matches!(*inner, Expr::Term(Term::Regex(_))));
+    /// ```
+    Modified(FilterKind, Box<Expr>),
 }
 
 /// A leaf expression that Everything understands without further boolean
@@ -369,6 +389,34 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Child));
     /// ```
     Child,
+    /// Number of direct children (`childcount:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("childcount:>1000").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::ChildCount));
+    /// ```
+    ChildCount,
+    /// Number of direct child files (`childfilecount:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("childfilecount:0").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::ChildFileCount));
+    /// ```
+    ChildFileCount,
+    /// Number of direct child folders (`childfoldercount:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("childfoldercount:1..5").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::ChildFolderCount));
+    /// ```
+    ChildFolderCount,
+    /// Zero-byte files and folders without children (`empty:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("empty:").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Empty));
+    /// ```
+    Empty,
     /// Match file-system attributes (`attrib:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -397,6 +445,13 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Duplicate));
     /// ```
     Duplicate,
+    /// Duplicate detection by file contents (`hashdupe:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("hashdupe:").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::HashDuplicate));
+    /// ```
+    HashDuplicate,
     /// Duplicate detection ignoring extensions (`namepartdupe:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -495,7 +550,8 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::BitDepth));
     /// ```
     BitDepth,
-    /// Case-sensitive search toggle (`case:`).
+    /// Case-sensitive matching (`case:`). A modifier: it also wraps groups
+    /// and filters, see [`Expr::Modified`].
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
     /// let Expr::Term(Term::Filter(filter)) = parse_query("case:ABC").unwrap().expr else { panic!() };
@@ -516,13 +572,65 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Content));
     /// ```
     Content,
-    /// Temporarily disable whole filename matching (`nowholefilename:`).
+    /// Match the whole file name (`wfn:`, `wholefilename:`). A modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("wfn:report.pdf").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::WholeFilename));
+    /// ```
+    WholeFilename,
+    /// Temporarily disable whole filename matching (`nowholefilename:`,
+    /// `nowfn:`). A modifier.
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
     /// let Expr::Term(Term::Filter(filter)) = parse_query("nowholefilename:report").unwrap().expr else { panic!() };
     /// assert!(matches!(filter.kind, FilterKind::NoWholeFilename));
     /// ```
     NoWholeFilename,
+    /// Match whole words only (`ww:`, `wholeword:`). A modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("ww:log").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::WholeWord));
+    /// ```
+    WholeWord,
+    /// Temporarily disable whole word matching (`nowholeword:`, `noww:`).
+    /// A modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("noww:log").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NoWholeWord));
+    /// ```
+    NoWholeWord,
+    /// Match names that start with the value (`startwith:`). A modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("startwith:IMG_").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::StartWith));
+    /// ```
+    StartWith,
+    /// Match names that end with the value (`endwith:`). A modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("endwith:.tar.gz;.tgz").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::EndWith));
+    /// ```
+    EndWith,
+    /// Match accents and other diacritics exactly (`diacritics:`). A
+    /// modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("diacritics:café").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Diacritics));
+    /// ```
+    Diacritics,
+    /// Ignore accents and other diacritics (`nodiacritics:`). A modifier.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("nodiacritics:cafe").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NoDiacritics));
+    /// ```
+    NoDiacritics,
     /// User-defined macro or unrecognized filter name.
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -533,6 +641,52 @@ pub enum FilterKind {
 }
 
 impl FilterKind {
+    /// Modifiers change how the operand right after them matches instead of
+    /// filtering on their own.
+    ///
+    /// ```
+    /// use cardinal_syntax::FilterKind;
+    /// assert!(FilterKind::CaseSensitive.is_modifier());
+    /// assert!(FilterKind::WholeFilename.is_modifier());
+    /// assert!(FilterKind::WholeWord.is_modifier());
+    /// assert!(!FilterKind::Ext.is_modifier());
+    /// ```
+    pub fn is_modifier(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::CaseSensitive
+                | FilterKind::WholeFilename
+                | FilterKind::NoWholeFilename
+                | FilterKind::WholeWord
+                | FilterKind::NoWholeWord
+                | FilterKind::StartWith
+                | FilterKind::EndWith
+                | FilterKind::Diacritics
+                | FilterKind::NoDiacritics
+        )
+    }
+
+    /// Returns true for the duplicate filters, which group the nodes they are
+    /// given against each other, so their result depends on the whole set.
+    ///
+    /// ```
+    /// use cardinal_syntax::FilterKind;
+    /// assert!(FilterKind::Duplicate.is_duplicate());
+    /// assert!(FilterKind::HashDuplicate.is_duplicate());
+    /// assert!(!FilterKind::Size.is_duplicate());
+    /// ```
+    pub fn is_duplicate(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::Duplicate
+                | FilterKind::SizeDuplicate
+                | FilterKind::DateModifiedDuplicate
+                | FilterKind::NamePartDuplicate
+                | FilterKind::AttributeDuplicate
+                | FilterKind::HashDuplicate
+        )
+    }
+
     fn from_name(name: &str) -> Self {
         let lower = name.to_ascii_lowercase();
         match lower.as_str() {
@@ -553,10 +707,15 @@ impl FilterKind {
             "infolder" | "in" => FilterKind::InFolder,
             "nosubfolders" => FilterKind::NoSubfolders,
             "child" => FilterKind::Child,
+            "childcount" => FilterKind::ChildCount,
+            "childfilecount" => FilterKind::ChildFileCount,
+            "childfoldercount" => FilterKind::ChildFolderCount,
+            "empty" => FilterKind::Empty,
             "attrib" => FilterKind::Attribute,
             "attribdupe" => FilterKind::AttributeDuplicate,
             "dmdupe" => FilterKind::DateModifiedDuplicate,
             "dupe" => FilterKind::Duplicate,
+            "hashdupe" => FilterKind::HashDuplicate,
             "namepartdupe" => FilterKind::NamePartDuplicate,
             "sizedupe" => FilterKind::SizeDuplicate,
             "artist" => FilterKind::Artist,
@@ -574,7 +733,14 @@ impl FilterKind {
             "case" => FilterKind::CaseSensitive,
             "tag" | "t" => FilterKind::Tag,
             "content" => FilterKind::Content,
-            "nowholefilename" => FilterKind::NoWholeFilename,
+            "wfn" | "wholefilename" => FilterKind::WholeFilename,
+            "nowfn" | "nowholefilename" => FilterKind::NoWholeFilename,
+            "ww" | "wholeword" => FilterKind::WholeWord,
+            "noww" | "nowholeword" => FilterKind::NoWholeWord,
+            "startwith" => FilterKind::StartWith,
+            "endwith" => FilterKind::EndWith,
+            "diacritics" => FilterKind::Diacritics,
+            "nodiacritics" => FilterKind::NoDiacritics,
             _ => FilterKind::Custom(name.to_string()),
         }
     }
@@ -883,6 +1049,10 @@ impl<'a> Parser<'a> {
             '(' => self.parse_group(')'),
             '>' | ')' => Err(self.error("unexpected closing delimiter")),
             _ => {
+                if let Some(kind) = self.consume_modifier_prefix() {
+                    let operand = self.parse_primary()?;
+                    return Ok(Expr::Modified(kind, Box::new(operand)));
+                }
                 let term = self.parse_word_like()?;
                 match &term {
                     Term::Word(text) if text == "\"\"" => Ok(Expr::Empty),
@@ -1219,6 +1389,29 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // A modifier such as `case:` directly followed by a group or another
+    // filter (`case:<a|b>`, `case:regex:^A`) applies to that whole operand,
+    // so consume it here instead of parsing it as a filter with an argument.
+    fn consume_modifier_prefix(&mut self) -> Option<FilterKind> {
+        let rest = self.remaining();
+        let name = &rest[..rest.find(':')?];
+        if !is_valid_filter_name(name) {
+            return None;
+        }
+        let kind = FilterKind::from_name(name);
+        if !kind.is_modifier() {
+            return None;
+        }
+        let start = self.pos;
+        self.pos += name.len() + 1;
+        if matches!(self.peek_char(), Some('(' | '<')) || self.starts_with_filter_token() {
+            Some(kind)
+        } else {
+            self.pos = start;
+            None
+        }
+    }
+
     // Determines whether the cursor is sitting at the next `name:` token so
     // filters such as `video: size:>1gb` don't treat the second filter as the
     // first one's argument.
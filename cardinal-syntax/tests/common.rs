@@ -40,6 +40,13 @@ pub fn as_not(expr: &Expr) -> &Expr {
     }
 }
 
+pub fn as_modified(expr: &Expr) -> (&FilterKind, &Expr) {
+    match expr {
+        Expr::Modified(kind, inner) => (kind, inner),
+        other => panic!("expected Modified, got: {other:?}"),
+    }
+}
+
 pub fn as_term(expr: &Expr) -> &Term {
     match expr {
         Expr::Term(t) => t,
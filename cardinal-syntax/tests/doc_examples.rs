@@ -62,6 +62,13 @@ fn all_examples_parse() {
         Example("ABC 123"),
         Example("ABC|123"),
         Example("case:ABC"),
+        Example("wfn:report.pdf"),
+        Example("wfn:case:<Report.pdf|*.DOC>"),
+        Example("ww:log"),
+        Example("ww:<log|tmp> noww:bak"),
+        Example("startwith:IMG_ endwith:.tar.gz;.tgz"),
+        Example("nodiacritics:resume"),
+        Example("nodiacritics:<cafe|creme> diacritics:é"),
         Example("D:|E: *.mp3"),
         Example("D: *.jpg|*.png"),
         Example("!."),
@@ -101,11 +108,17 @@ fn all_examples_parse() {
         Example("infolder:/Users/demo"),
         Example("nosubfolders:/Users/demo"),
         Example("child:*.jpg"),
+        Example("childcount:>1000"),
+        Example("childfilecount:0"),
+        Example("childfoldercount:1..5"),
+        Example("empty:"),
         Example("namepartdupe:"),
         Example("sizedupe:"),
         Example("attribdupe:"),
         Example("dmdupe:"),
         Example("dupe:"),
+        Example("dupe:content"),
+        Example("hashdupe:*.jpg"),
         Example("ext:jpg;png;gif;bmp;webp"),
         Example("ext:7z;zip;tar;gz"),
         Example("ext:mp3;flac;mp4;avi;mkv"),
@@ -36,10 +36,15 @@ fn maps_known_filter_names() {
         ("infolder", FilterKind::InFolder),
         ("nosubfolders", FilterKind::NoSubfolders),
         ("child", FilterKind::Child),
+        ("childcount", FilterKind::ChildCount),
+        ("childfilecount", FilterKind::ChildFileCount),
+        ("childfoldercount", FilterKind::ChildFolderCount),
+        ("empty", FilterKind::Empty),
         ("attrib", FilterKind::Attribute),
         ("attribdupe", FilterKind::AttributeDuplicate),
         ("dmdupe", FilterKind::DateModifiedDuplicate),
         ("dupe", FilterKind::Duplicate),
+        ("hashdupe", FilterKind::HashDuplicate),
         ("namepartdupe", FilterKind::NamePartDuplicate),
         ("sizedupe", FilterKind::SizeDuplicate),
         ("artist", FilterKind::Artist),
@@ -57,6 +62,17 @@ fn maps_known_filter_names() {
         ("case", FilterKind::CaseSensitive),
         ("content", FilterKind::Content),
         ("nowholefilename", FilterKind::NoWholeFilename),
+        ("nowfn", FilterKind::NoWholeFilename),
+        ("wfn", FilterKind::WholeFilename),
+        ("wholefilename", FilterKind::WholeFilename),
+        ("ww", FilterKind::WholeWord),
+        ("wholeword", FilterKind::WholeWord),
+        ("noww", FilterKind::NoWholeWord),
+        ("nowholeword", FilterKind::NoWholeWord),
+        ("startwith", FilterKind::StartWith),
+        ("endwith", FilterKind::EndWith),
+        ("diacritics", FilterKind::Diacritics),
+        ("nodiacritics", FilterKind::NoDiacritics),
     ];
 
     for (name, expected) in cases {
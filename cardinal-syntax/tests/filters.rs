@@ -14,9 +14,15 @@ fn recognized_filter_kinds_without_arguments() {
         ("attribdupe:", FilterKind::AttributeDuplicate),
         ("dmdupe:", FilterKind::DateModifiedDuplicate),
         ("dupe:", FilterKind::Duplicate),
+        ("hashdupe:", FilterKind::HashDuplicate),
         ("namepartdupe:", FilterKind::NamePartDuplicate),
         ("sizedupe:", FilterKind::SizeDuplicate),
         ("nowholefilename:", FilterKind::NoWholeFilename),
+        ("wfn:", FilterKind::WholeFilename),
+        ("ww:", FilterKind::WholeWord),
+        ("startwith:", FilterKind::StartWith),
+        ("endwith:", FilterKind::EndWith),
+        ("nodiacritics:", FilterKind::NoDiacritics),
     ];
 
     for (q, kind) in cases {
@@ -0,0 +1,135 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+#[test]
+fn case_with_a_plain_word_stays_a_filter() {
+    let expr = parse_ok("readme case:TODO");
+    let parts = as_and(&expr);
+    word_is(&parts[0], "readme");
+    filter_is_kind(&parts[1], &FilterKind::CaseSensitive);
+    filter_arg_raw(&parts[1], "TODO");
+}
+
+#[test]
+fn case_wraps_angle_and_paren_groups() {
+    for query in ["case:<Foo|Bar>", "case:(Foo|Bar)", "CASE:<Foo|Bar>"] {
+        let expr = parse_ok(query);
+        let (kind, inner) = as_modified(&expr);
+        assert_eq!(kind, &FilterKind::CaseSensitive, "{query}");
+        let parts = as_or(inner);
+        word_is(&parts[0], "Foo");
+        word_is(&parts[1], "Bar");
+    }
+}
+
+#[test]
+fn case_wraps_regex_and_other_filters() {
+    let expr = parse_ok("case:regex:^Read");
+    let (_, inner) = as_modified(&expr);
+    regex_is(inner, "^Read");
+
+    let expr = parse_ok("case:content:Error");
+    let (_, inner) = as_modified(&expr);
+    filter_is_kind(inner, &FilterKind::Content);
+    filter_arg_raw(inner, "Error");
+}
+
+#[test]
+fn modified_operand_only_covers_the_next_term() {
+    let expr = parse_raw("case:<Foo> bar !case:(Baz)");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    let (_, inner) = as_modified(&parts[0]);
+    word_is(inner, "Foo");
+    word_is(&parts[1], "bar");
+    let (_, inner) = as_modified(as_not(&parts[2]));
+    word_is(inner, "Baz");
+}
+
+#[test]
+fn case_followed_by_whitespace_has_no_operand() {
+    let expr = parse_raw("case: <Foo|Bar>");
+    let parts = as_and(&expr);
+    filter_is_kind(&parts[0], &FilterKind::CaseSensitive);
+    filter_arg_none(&parts[0]);
+    as_or(&parts[1]);
+}
+
+#[test]
+fn optimizer_recurses_into_modified_groups_and_keeps_their_priority() {
+    let expr = parse_ok("case:ext:rs case:<(foo)> infolder:/src");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    filter_is_kind(&parts[0], &FilterKind::InFolder);
+    let (_, inner) = as_modified(&parts[1]);
+    word_is(inner, "foo");
+    let (_, inner) = as_modified(&parts[2]);
+    filter_is_kind(inner, &FilterKind::Ext);
+}
+
+#[test]
+fn whole_filename_modifiers_nest_with_case() {
+    let expr = parse_ok("wfn:case:Report.pdf");
+    let (kind, inner) = as_modified(&expr);
+    assert_eq!(kind, &FilterKind::WholeFilename);
+    filter_is_kind(inner, &FilterKind::CaseSensitive);
+    filter_arg_raw(inner, "Report.pdf");
+
+    let expr = parse_ok("case:wholefilename:<a|b> nowfn:draft");
+    let parts = as_and(&expr);
+    let (kind, inner) = as_modified(&parts[0]);
+    assert_eq!(kind, &FilterKind::CaseSensitive);
+    let (kind, inner) = as_modified(inner);
+    assert_eq!(kind, &FilterKind::WholeFilename);
+    as_or(inner);
+    filter_is_kind(&parts[1], &FilterKind::NoWholeFilename);
+    filter_arg_raw(&parts[1], "draft");
+}
+
+#[test]
+fn whole_word_modifiers_wrap_groups_and_words() {
+    let expr = parse_ok("ww:<log|tmp> noww:bak");
+    let parts = as_and(&expr);
+    let (kind, inner) = as_modified(&parts[0]);
+    assert_eq!(kind, &FilterKind::WholeWord);
+    as_or(inner);
+    filter_is_kind(&parts[1], &FilterKind::NoWholeWord);
+    filter_arg_raw(&parts[1], "bak");
+
+    let expr = parse_ok("wholeword:case:Log");
+    let (kind, inner) = as_modified(&expr);
+    assert_eq!(kind, &FilterKind::WholeWord);
+    filter_is_kind(inner, &FilterKind::CaseSensitive);
+    filter_arg_raw(inner, "Log");
+}
+
+#[test]
+fn anchor_modifiers_take_lists_and_groups() {
+    let expr = parse_ok("endwith:.tar.gz;.tgz");
+    filter_is_kind(&expr, &FilterKind::EndWith);
+    filter_arg_is_list(&expr, &[".tar.gz", ".tgz"]);
+
+    let expr = parse_ok("startwith:<IMG_|DSC> endwith:jpg");
+    let parts = as_and(&expr);
+    let (kind, inner) = as_modified(&parts[0]);
+    assert_eq!(kind, &FilterKind::StartWith);
+    as_or(inner);
+    filter_is_kind(&parts[1], &FilterKind::EndWith);
+}
+
+#[test]
+fn diacritics_modifiers_nest() {
+    let expr = parse_ok("nodiacritics:<cafe|creme> diacritics:é");
+    let parts = as_and(&expr);
+    let (kind, inner) = as_modified(&parts[0]);
+    assert_eq!(kind, &FilterKind::NoDiacritics);
+    as_or(inner);
+    filter_is_kind(&parts[1], &FilterKind::Diacritics);
+    filter_arg_raw(&parts[1], "é");
+
+    let expr = parse_ok("nodiacritics:case:Resume");
+    let (kind, inner) = as_modified(&expr);
+    assert_eq!(kind, &FilterKind::NoDiacritics);
+    filter_is_kind(inner, &FilterKind::CaseSensitive);
+}
@@ -54,6 +54,19 @@ fn tag_filters_move_to_end() {
     filter_is_kind(&parts[5], &FilterKind::Tag);
 }
 
+#[test]
+fn duplicate_filters_run_after_tags() {
+    let expr = parse_ok("sizedupe: tag:assets ext:png dupe: alpha");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 5);
+
+    word_is(&parts[0], "alpha");
+    filter_is_kind(&parts[1], &FilterKind::Ext);
+    filter_is_kind(&parts[2], &FilterKind::Tag);
+    filter_is_kind(&parts[3], &FilterKind::SizeDuplicate);
+    filter_is_kind(&parts[4], &FilterKind::Duplicate);
+}
+
 #[test]
 fn tag_filter_only() {
     let expr = parse_ok("tag:important");
@@ -535,8 +548,16 @@ fn all_filter_types_comprehensive() {
     word_is(&parts[3], "word1");
     word_is(&parts[4], "word2");
 
-    // Tags should be the final three elements
-    let tail_start = parts.len() - 3;
+    // Duplicate filters close the query, in encounter order
+    let dupe_start = parts.len() - 5;
+    filter_is_kind(&parts[dupe_start], &FilterKind::AttributeDuplicate);
+    filter_is_kind(&parts[dupe_start + 1], &FilterKind::DateModifiedDuplicate);
+    filter_is_kind(&parts[dupe_start + 2], &FilterKind::Duplicate);
+    filter_is_kind(&parts[dupe_start + 3], &FilterKind::NamePartDuplicate);
+    filter_is_kind(&parts[dupe_start + 4], &FilterKind::SizeDuplicate);
+
+    // Tags come right before them
+    let tail_start = dupe_start - 3;
     filter_is_kind(&parts[tail_start], &FilterKind::Tag);
     filter_is_kind(&parts[tail_start + 1], &FilterKind::Tag);
     filter_is_kind(&parts[tail_start + 2], &FilterKind::Tag);
@@ -20,6 +20,7 @@ fn keyword_boundaries_do_not_consume_neighbors() {
             Expr::Not(_) => panic!("unexpected NOT node from keyword boundary"),
             Expr::Or(_) => panic!("unexpected OR node from keyword boundary"),
             Expr::And(parts) => parts.iter().for_each(assert_no_keyword_nodes),
+            Expr::Modified(_, inner) => assert_no_keyword_nodes(inner),
             Expr::Term(_) | Expr::Empty => {}
         }
     }
@@ -6,7 +6,7 @@ use crate::{
 };
 use anyhow::Result;
 use base64::{Engine as _, engine::general_purpose};
-use cardinal_sdk::{EventFlag, EventWatcher, FsEvent};
+use cardinal_sdk::{EventFlag, EventWatcher, FsEvent, SourceKind};
 use crossbeam_channel::{Receiver, Sender};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
@@ -161,7 +161,7 @@ fn handle_watch_config_update(
         update_app_state(app_handle, AppLifecycleState::Updating);
         EventWatcher::spawn(
             watch_root.to_string(),
-            cache.last_event_id(),
+            cache.resume_event_id(SourceKind::native()),
             fse_latency_secs,
             cache.ignore_paths(),
             cache.include_paths(),
@@ -491,7 +491,11 @@ fn perform_rescan(
             }
         });
         // If rescan is cancelled, we have nothing to do
-        let stopped = cache.rescan_with_walk_data(&walk_data).is_none();
+        let summary = cache.rescan_with_walk_data(&walk_data);
+        if let Some(summary) = summary {
+            info!("Rescan done: {summary:?}");
+        }
+        let stopped = summary.is_none();
         walking_done.store(true, Ordering::Relaxed);
         stopped
     });
@@ -502,7 +506,7 @@ fn perform_rescan(
         update_app_state(app_handle, AppLifecycleState::Updating);
         EventWatcher::spawn(
             watch_root.to_string(),
-            cache.last_event_id(),
+            cache.resume_event_id(SourceKind::native()),
             fse_latency_secs,
             cache.ignore_paths(),
             cache.include_paths(),
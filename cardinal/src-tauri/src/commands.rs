@@ -41,11 +41,22 @@ pub struct WatchConfigUpdate {
 pub struct SearchOptionsPayload {
     #[serde(default)]
     pub case_insensitive: bool,
+    #[serde(default)]
+    pub ignore_diacritics: bool,
 }
 
 impl From<SearchOptionsPayload> for SearchOptions {
-    fn from(SearchOptionsPayload { case_insensitive }: SearchOptionsPayload) -> Self {
-        SearchOptions { case_insensitive }
+    fn from(
+        SearchOptionsPayload {
+            case_insensitive,
+            ignore_diacritics,
+        }: SearchOptionsPayload,
+    ) -> Self {
+        SearchOptions {
+            case_insensitive,
+            ignore_diacritics,
+            ..Default::default()
+        }
     }
 }
 
@@ -238,6 +249,7 @@ pub struct NodeInfoMetadata {
     pub size: i64,
     pub ctime: u32,
     pub mtime: u32,
+    pub atime: u32,
 }
 
 impl NodeInfoMetadata {
@@ -247,6 +259,7 @@ impl NodeInfoMetadata {
             size: metadata.size(),
             ctime: metadata.ctime().map(|x| x.get()).unwrap_or_default(),
             mtime: metadata.mtime().map(|x| x.get()).unwrap_or_default(),
+            atime: metadata.atime().map(|x| x.get()).unwrap_or_default(),
         }
     }
 }
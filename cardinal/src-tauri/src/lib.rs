@@ -11,7 +11,7 @@ use background::{
     BackgroundLoopChannels, IconPayload, build_search_cache, emit_status_bar_update,
     run_background_event_loop,
 };
-use cardinal_sdk::EventWatcher;
+use cardinal_sdk::{EventWatcher, SourceKind};
 use commands::{
     NodeInfoRequest, SearchJob, SearchState, WatchConfigUpdate, activate_main_window,
     close_quicklook, copy_files_to_clipboard, get_app_status, get_nodes_info, get_sorted_view,
@@ -291,7 +291,7 @@ fn run_logic_thread(
         update_app_state(app_handle, AppLifecycleState::Updating);
         EventWatcher::spawn(
             watch_root.to_string(),
-            cache.last_event_id(),
+            cache.resume_event_id(SourceKind::native()),
             FSE_LATENCY_SECS,
             cache.ignore_paths(),
             cache.include_paths(),
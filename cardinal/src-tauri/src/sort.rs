@@ -18,6 +18,7 @@ pub enum SortKeyPayload {
     Size,
     Mtime,
     Ctime,
+    Atime,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
@@ -78,6 +79,10 @@ fn metadata_numeric(meta: &SlabNodeMetadataCompact, key: SortKeyPayload) -> i64
             .ctime()
             .map(|value| value.get() as i64)
             .unwrap_or(i64::MIN),
+        SortKeyPayload::Atime => meta_ref
+            .atime()
+            .map(|value| value.get() as i64)
+            .unwrap_or(i64::MIN),
         SortKeyPayload::FullPath | SortKeyPayload::Filename => 0,
     }
 }
@@ -102,7 +107,10 @@ fn compare_entries(a: &SortEntry, b: &SortEntry, sort: &SortStatePayload) -> Std
             .cmp(&b.name_key)
             .then_with(|| type_order(&a.node).cmp(&type_order(&b.node)))
             .then_with(|| a.path_key.cmp(&b.path_key)),
-        SortKeyPayload::Size | SortKeyPayload::Mtime | SortKeyPayload::Ctime => {
+        SortKeyPayload::Size
+        | SortKeyPayload::Mtime
+        | SortKeyPayload::Ctime
+        | SortKeyPayload::Atime => {
             metadata_numeric(&a.node.metadata, sort.key)
                 .cmp(&metadata_numeric(&b.node.metadata, sort.key))
                 .then_with(|| a.name_key.cmp(&b.name_key))
@@ -142,6 +150,21 @@ mod tests {
             size,
             ctime: None,
             mtime: None,
+            atime: None,
+            mode: 0,
+            flags: 0,
+        })
+    }
+
+    fn metadata_with_atime(atime: u64) -> SlabNodeMetadataCompact {
+        SlabNodeMetadataCompact::some(NodeMetadata {
+            r#type: NodeFileType::File,
+            size: 0,
+            ctime: None,
+            mtime: None,
+            atime: std::num::NonZeroU64::new(atime),
+            mode: 0,
+            flags: 0,
         })
     }
 
@@ -196,4 +219,26 @@ mod tests {
             "directories stay ahead when size and names match, while files fall back to path order"
         );
     }
+
+    #[test]
+    fn atime_sort_lists_least_recently_accessed_first() {
+        let sort_state = SortStatePayload {
+            key: SortKeyPayload::Atime,
+            direction: SortDirectionPayload::Asc,
+        };
+        let mut entries = vec![
+            entry_with_metadata(1, "/tmp/b/recent", metadata_with_atime(2_000)),
+            entry_with_metadata(2, "/tmp/c/unknown", SlabNodeMetadataCompact::none()),
+            entry_with_metadata(0, "/tmp/a/stale", metadata_with_atime(1_000)),
+        ];
+
+        sort_entries(&mut entries, &sort_state);
+        let order: Vec<usize> = entries.iter().map(|entry| entry.slab_index.get()).collect();
+
+        assert_eq!(
+            order,
+            vec![2, 0, 1],
+            "nodes without metadata sort first, then files by access time"
+        );
+    }
 }
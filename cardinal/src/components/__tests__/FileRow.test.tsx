@@ -5,7 +5,7 @@ import { FileRow } from '../FileRow';
 
 const baseItem = {
   path: '/tmp/example.txt',
-  metadata: { type: 0, size: 1024, mtime: 0, ctime: 0 },
+  metadata: { type: 0, size: 1024, mtime: 0, ctime: 0, atime: 0 },
 };
 
 const renderRow = (props?: Partial<React.ComponentProps<typeof FileRow>>) => {
@@ -14,6 +14,7 @@ const fromNodeInfo = (node: NodeInfoResponse): SearchResultItem => ({
   size: node.size ?? node.metadata?.size,
   mtime: node.mtime ?? node.metadata?.mtime,
   ctime: node.ctime ?? node.metadata?.ctime,
+  atime: node.atime ?? node.metadata?.atime,
   icon: node.icon ?? undefined,
 });
 
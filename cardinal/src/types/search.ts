@@ -3,6 +3,7 @@ export type SearchResultMetadata = Readonly<{
   size: number;
   mtime: number;
   ctime: number;
+  atime: number;
 }>;
 
 export type SearchResultItem = Readonly<{
@@ -11,6 +12,7 @@ export type SearchResultItem = Readonly<{
   size?: number;
   mtime?: number;
   ctime?: number;
+  atime?: number;
   icon?: string;
 }>;
 
@@ -21,4 +23,5 @@ export type NodeInfoResponse = Readonly<{
   size?: number | null;
   mtime?: number | null;
   ctime?: number | null;
+  atime?: number | null;
 }>;
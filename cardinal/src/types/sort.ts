@@ -1,4 +1,4 @@
-export type SortKey = 'filename' | 'fullPath' | 'size' | 'mtime' | 'ctime';
+export type SortKey = 'filename' | 'fullPath' | 'size' | 'mtime' | 'ctime' | 'atime';
 
 export type SortDirection = 'asc' | 'desc';
 
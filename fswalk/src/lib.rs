@@ -25,8 +25,16 @@ pub struct NodeMetadata {
     pub size: u64,
     pub ctime: Option<NonZeroU64>,
     pub mtime: Option<NonZeroU64>,
+    pub atime: Option<NonZeroU64>,
+    /// Raw `st_mode`: file type and permission bits.
+    pub mode: u32,
+    /// BSD file flags (`st_flags`), such as [`UF_HIDDEN`]. Always 0 on Linux.
+    pub flags: u32,
 }
 
+/// The file flag Finder uses to hide a file (`chflags hidden`).
+pub const UF_HIDDEN: u32 = 0x8000;
+
 impl From<Metadata> for NodeMetadata {
     fn from(metadata: Metadata) -> Self {
         Self::new(&metadata)
@@ -47,11 +55,23 @@ impl NodeMetadata {
             .ok()
             .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
             .and_then(|x| NonZeroU64::new(x.as_secs()));
+        let atime = metadata
+            .accessed()
+            .ok()
+            .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+            .and_then(|x| NonZeroU64::new(x.as_secs()));
+        #[cfg(target_os = "macos")]
+        let flags = std::os::macos::fs::MetadataExt::st_flags(metadata);
+        #[cfg(not(target_os = "macos"))]
+        let flags = 0;
         Self {
             r#type,
             size,
             ctime,
             mtime,
+            atime,
+            mode: metadata.mode(),
+            flags,
         }
     }
 }
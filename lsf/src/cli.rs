@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -8,6 +8,28 @@ pub struct Cli {
     pub refresh: bool,
     #[clap(long, default_value = "/")]
     pub path: PathBuf,
+    /// Poll directory mtimes every N seconds instead of relying on change notifications.
+    #[clap(long)]
+    pub poll_interval: Option<f64>,
+    /// Maximum number of stat/read_dir calls per polling cycle.
+    #[clap(long, default_value_t = 4096)]
+    pub poll_budget: usize,
+    /// Feed the index from an event log recorded by `was record`, paced as
+    /// recorded, instead of watching the filesystem.
+    #[clap(long, value_name = "LOG", conflicts_with = "poll_interval")]
+    pub replay: Option<PathBuf>,
     #[command(flatten)]
     pub verbosity: clap_verbosity_flag::Verbosity,
+    /// Run a single command against the index instead of the interactive prompt.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print the groups of byte-identical files among the results of QUERY.
+    ///
+    /// Content hashes are saved with the cache, so later runs only reread
+    /// files that changed.
+    Dupes { query: String },
 }
@@ -1,16 +1,23 @@
 mod cli;
 
 use anyhow::{Context, Result};
-use cardinal_sdk::EventWatcher;
+use cardinal_sdk::{
+    ChangeSource, EventLogReader, EventWatcher, PollConfig, ReplaySource, SourceKind,
+};
 use clap::Parser;
-use cli::Cli;
-use crossbeam_channel::{Sender, bounded, unbounded};
+use cli::{Cli, Command};
+use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use rustyline::{DefaultEditor, error::ReadlineError};
-use search_cache::{HandleFSEError, SearchCache, SearchResultNode};
+use search_cache::{
+    HandleFSEError, QueryDelta, SearchCache, SearchOptions, SearchResultNode, SlabIndex,
+};
 use search_cancel::CancellationToken;
 use std::{
+    fs::File,
+    io::BufReader,
     path::{Path, PathBuf},
     sync::atomic::AtomicBool,
+    time::Duration,
 };
 use tracing_subscriber::EnvFilter;
 
@@ -18,6 +25,92 @@ const CACHE_PATH: &str = "target/cache.zstd";
 const IGNORE_PATH: &str = "/System/Volumes/Data"; // macOS specific ignore path
 static NEVER_STOPPED: AtomicBool = AtomicBool::new(false);
 
+/// Where index updates come from, picked from the command line.
+enum SourceChoice {
+    /// Native change notifications.
+    Native,
+    /// A directory-mtime poller, for NFS/SMB/FUSE roots.
+    Polling(PollConfig),
+    /// An event log recorded by `was record`.
+    Replay(PathBuf),
+}
+
+fn spawn_change_source(
+    cache: &SearchCache,
+    path: &Path,
+    choice: &SourceChoice,
+) -> Result<Box<dyn ChangeSource>> {
+    let source: Box<dyn ChangeSource> = match choice {
+        SourceChoice::Native => Box::new(
+            EventWatcher::spawn(
+                "/".to_string(),
+                cache.resume_event_id(SourceKind::native()),
+                0.1,
+                cache.ignore_paths(),
+                cache.include_paths(),
+            )
+            .1,
+        ),
+        SourceChoice::Polling(config) => Box::new(
+            EventWatcher::spawn_polling(
+                path.to_string_lossy().into_owned(),
+                cache.polled_dirs(),
+                *config,
+                cache.ignore_paths(),
+                cache.include_paths(),
+            )
+            .1,
+        ),
+        SourceChoice::Replay(log) => {
+            let log = File::open(log).with_context(|| format!("Failed to open {log:?}"))?;
+            let log =
+                EventLogReader::new(BufReader::new(log)).context("Failed to read event log")?;
+            Box::new(ReplaySource::spawn(log, path.to_path_buf(), true))
+        }
+    };
+    println!("Processing changes from {:?}.", source.kind());
+    Ok(source)
+}
+
+/// Print the pending result changes of every `/watch`ed query.
+fn print_watch_deltas(cache: &SearchCache, watches: &[(String, Receiver<QueryDelta>)]) {
+    let path_of = |index: SlabIndex| cache.node_path(index).unwrap_or_default();
+    for (query, deltas) in watches {
+        for delta in deltas.try_iter() {
+            for index in delta.removed {
+                println!("[{query}] - {:?}", path_of(index));
+            }
+            for index in delta.added {
+                println!("[{query}] + {:?}", path_of(index));
+            }
+        }
+    }
+}
+
+/// Print the groups of byte-identical files among the results of `query`.
+fn print_content_duplicates(cache: &mut SearchCache, query: &str) -> Result<()> {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .context("Failed to search")?
+        .nodes
+        .context("noop token never cancels")?;
+    let groups = cache
+        .content_duplicates(&nodes, CancellationToken::noop())
+        .context("noop token never cancels")?;
+    for (i, group) in groups.iter().enumerate() {
+        println!("[{i}] {} identical files", group.len());
+        for &index in group {
+            println!("    {:?}", cache.node_path(index).unwrap_or_default());
+        }
+    }
+    println!(
+        "{} groups, {} content hashes cached",
+        groups.len(),
+        cache.content_hashes().len()
+    );
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -29,6 +122,14 @@ fn main() -> Result<()> {
     }
 
     let path = cli.path;
+    let source_choice = match (cli.replay, cli.poll_interval) {
+        (Some(log), _) => SourceChoice::Replay(log),
+        (None, Some(interval)) => SourceChoice::Polling(PollConfig {
+            interval: Duration::from_secs_f64(interval),
+            io_budget: cli.poll_budget,
+        }),
+        (None, None) => SourceChoice::Native,
+    };
     let ignore_paths = vec![PathBuf::from(IGNORE_PATH)];
     let mut cache = if cli.refresh {
         println!("Walking filesystem...");
@@ -50,19 +151,23 @@ fn main() -> Result<()> {
 
     println!("Cache is: {cache:?}");
 
+    if let Some(Command::Dupes { query }) = cli.command {
+        print_content_duplicates(&mut cache, &query)?;
+        return cache
+            .flush_to_file(Path::new(CACHE_PATH))
+            .context("Failed to write cache to file");
+    }
+
     let (finish_tx, finish_rx) = bounded::<Sender<SearchCache>>(1);
     let (search_tx, search_rx) = unbounded::<String>();
     let (search_result_tx, search_result_rx) = unbounded::<Result<Vec<SearchResultNode>>>();
+    let (watch_tx, watch_rx) = unbounded::<String>();
 
+    let mut change_source = spawn_change_source(&cache, &path, &source_choice)?;
     std::thread::spawn(move || {
-        let (dev, mut event_watcher) = EventWatcher::spawn(
-            "/".to_string(),
-            cache.last_event_id(),
-            0.1,
-            cache.ignore_paths(),
-            cache.include_paths(),
-        );
-        println!("Processing changes of dev:{dev} during preparation.");
+        let mut watches = Vec::new();
+        // Flushes duplicate-filter watches whose re-run was throttled.
+        let watch_ticker = crossbeam_channel::tick(Duration::from_secs(1));
         loop {
             crossbeam_channel::select! {
                 recv(finish_rx) -> tx => {
@@ -77,14 +182,37 @@ fn main() -> Result<()> {
                         .send(files)
                         .expect("search_result_tx is closed");
                 }
-                recv(event_watcher) -> events => {
-                    let events = events.expect("event_stream is closed");
-                    if let Err(HandleFSEError::Rescan) = cache.handle_fs_events(events) {
+                recv(watch_rx) -> query => {
+                    let query = query.expect("watch_tx is closed");
+                    match cache.subscribe_query(&query, SearchOptions::default(), CancellationToken::noop()) {
+                        Ok(Some(subscription)) => {
+                            println!("Watching {query:?}: {} results", subscription.nodes.len());
+                            watches.push((query, subscription.deltas));
+                        }
+                        Ok(None) => println!("Watching {query:?} cancelled"),
+                        Err(e) => eprintln!("Failed to watch: {e:?}"),
+                    }
+                }
+                recv(watch_ticker) -> _ => {
+                    if !watches.is_empty() {
+                        cache.refresh_live_queries();
+                        print_watch_deltas(&cache, &watches);
+                    }
+                }
+                recv(change_source.events()) -> events => {
+                    let Ok(events) = events else {
+                        println!("Change source {:?} is exhausted.", change_source.kind());
+                        change_source = Box::new(EventWatcher::noop());
+                        continue;
+                    };
+                    let kind = change_source.kind();
+                    if let Err(HandleFSEError::Rescan) = cache.handle_source_events(kind, events) {
                         println!("!!!!!!!!!! Rescan triggered !!!!!!!!");
-                        // Here we clear event_watcher first as rescan may take a lot of time
-                        #[allow(unused_assignments)]
-                        {
-                            event_watcher = EventWatcher::noop();
+                        // Here we clear the live watcher first as rescan may take a lot of time.
+                        // A replay carries on where it is instead of starting over.
+                        let replaying = kind == SourceKind::Replay;
+                        if !replaying {
+                            change_source = Box::new(EventWatcher::noop());
                         }
                         let mut scan_root = PathBuf::new();
                         let mut scan_ignore_paths = Vec::new();
@@ -95,16 +223,15 @@ fn main() -> Result<()> {
                             &mut scan_include_paths,
                             CancellationToken::new_scan(),
                         );
-                        let _ = cache.rescan_with_walk_data(&walk_data);
-                        event_watcher = EventWatcher::spawn(
-                            "/".to_string(),
-                            cache.last_event_id(),
-                            0.1,
-                            cache.ignore_paths(),
-                            cache.include_paths(),
-                        )
-                        .1;
+                        if let Some(summary) = cache.rescan_with_walk_data(&walk_data) {
+                            println!("Rescan done: {summary:?}");
+                        }
+                        if !replaying {
+                            change_source = spawn_change_source(&cache, &path, &source_choice)
+                                .expect("live watchers spawn infallibly");
+                        }
                     }
+                    print_watch_deltas(&cache, &watches);
                 }
             }
         }
@@ -125,6 +252,13 @@ fn main() -> Result<()> {
 
                 let _ = rl.add_history_entry(line);
 
+                if let Some(query) = line.strip_prefix("/watch ") {
+                    watch_tx
+                        .send(query.trim().to_string())
+                        .context("watch_tx is closed")?;
+                    continue;
+                }
+
                 search_tx
                     .send(line.to_string())
                     .context("search_tx is closed")?;
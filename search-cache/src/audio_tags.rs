@@ -0,0 +1,684 @@
+use crate::{
+    SearchCache, SlabIndex,
+    file_probe::{Probe, be_uint, find_atom, mp4_atoms, read_top_level_atom},
+};
+use search_cancel::CancellationToken;
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// Files worth opening for the audio tag filters.
+const TAGGED_AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "ogg", "oga", "opus", "m4a", "m4b", "mp4", "aac",
+];
+
+/// Upper bound on what is read for a single tag block. Text fields come
+/// before embedded cover art in practice, so a truncated block still yields
+/// them.
+const MAX_TAG_BYTES: usize = 4 * 1024 * 1024;
+/// `moov` also holds the sample tables, which grow with the duration.
+const MAX_MOOV_BYTES: u64 = 64 * 1024 * 1024;
+
+/// The ID3v1 genre list, including Winamp's extensions up to 125.
+const ID3_GENRES: &[&str] = &[
+    "Blues",
+    "Classic Rock",
+    "Country",
+    "Dance",
+    "Disco",
+    "Funk",
+    "Grunge",
+    "Hip-Hop",
+    "Jazz",
+    "Metal",
+    "New Age",
+    "Oldies",
+    "Other",
+    "Pop",
+    "R&B",
+    "Rap",
+    "Reggae",
+    "Rock",
+    "Techno",
+    "Industrial",
+    "Alternative",
+    "Ska",
+    "Death Metal",
+    "Pranks",
+    "Soundtrack",
+    "Euro-Techno",
+    "Ambient",
+    "Trip-Hop",
+    "Vocal",
+    "Jazz+Funk",
+    "Fusion",
+    "Trance",
+    "Classical",
+    "Instrumental",
+    "Acid",
+    "House",
+    "Game",
+    "Sound Clip",
+    "Gospel",
+    "Noise",
+    "AlternRock",
+    "Bass",
+    "Soul",
+    "Punk",
+    "Space",
+    "Meditative",
+    "Instrumental Pop",
+    "Instrumental Rock",
+    "Ethnic",
+    "Gothic",
+    "Darkwave",
+    "Techno-Industrial",
+    "Electronic",
+    "Pop-Folk",
+    "Eurodance",
+    "Dream",
+    "Southern Rock",
+    "Comedy",
+    "Cult",
+    "Gangsta",
+    "Top 40",
+    "Christian Rap",
+    "Pop/Funk",
+    "Jungle",
+    "Native American",
+    "Cabaret",
+    "New Wave",
+    "Psychadelic",
+    "Rave",
+    "Showtunes",
+    "Trailer",
+    "Lo-Fi",
+    "Tribal",
+    "Acid Punk",
+    "Acid Jazz",
+    "Polka",
+    "Retro",
+    "Musical",
+    "Rock & Roll",
+    "Hard Rock",
+    "Folk",
+    "Folk-Rock",
+    "National Folk",
+    "Swing",
+    "Fast Fusion",
+    "Bebob",
+    "Latin",
+    "Revival",
+    "Celtic",
+    "Bluegrass",
+    "Avantgarde",
+    "Gothic Rock",
+    "Progressive Rock",
+    "Psychedelic Rock",
+    "Symphonic Rock",
+    "Slow Rock",
+    "Big Band",
+    "Chorus",
+    "Easy Listening",
+    "Acoustic",
+    "Humour",
+    "Speech",
+    "Chanson",
+    "Opera",
+    "Chamber Music",
+    "Sonata",
+    "Symphony",
+    "Booty Bass",
+    "Primus",
+    "Porn Groove",
+    "Satire",
+    "Slow Jam",
+    "Club",
+    "Tango",
+    "Samba",
+    "Folklore",
+    "Ballad",
+    "Power Ballad",
+    "Rhythmic Soul",
+    "Freestyle",
+    "Duet",
+    "Punk Rock",
+    "Drum Solo",
+    "A capella",
+    "Euro-House",
+    "Dance Hall",
+];
+
+/// The fields read by `artist:`, `album:`, `title:`, `genre:`, `year:`,
+/// `track:` and `comment:`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct AudioTags {
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub title: Option<String>,
+    pub genre: Option<String>,
+    pub comment: Option<String>,
+    pub year: Option<u32>,
+    pub track: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum AudioField {
+    Artist,
+    Album,
+    Title,
+    Genre,
+    Year,
+    Track,
+    Comment,
+}
+
+impl AudioField {
+    pub(crate) fn filter_name(self) -> &'static str {
+        match self {
+            AudioField::Artist => "artist",
+            AudioField::Album => "album",
+            AudioField::Title => "title",
+            AudioField::Genre => "genre",
+            AudioField::Year => "year",
+            AudioField::Track => "track",
+            AudioField::Comment => "comment",
+        }
+    }
+
+    pub(crate) fn is_numeric(self) -> bool {
+        matches!(self, AudioField::Year | AudioField::Track)
+    }
+
+    pub(crate) fn text(self, tags: &AudioTags) -> Option<&str> {
+        match self {
+            AudioField::Artist => tags.artist.as_deref(),
+            AudioField::Album => tags.album.as_deref(),
+            AudioField::Title => tags.title.as_deref(),
+            AudioField::Genre => tags.genre.as_deref(),
+            AudioField::Comment => tags.comment.as_deref(),
+            AudioField::Year | AudioField::Track => None,
+        }
+    }
+
+    pub(crate) fn number(self, tags: &AudioTags) -> Option<u32> {
+        match self {
+            AudioField::Year => tags.year,
+            AudioField::Track => tags.track,
+            _ => None,
+        }
+    }
+}
+
+impl SearchCache {
+    /// Keeps the audio files among `nodes` whose tags satisfy `predicate`.
+    pub(crate) fn nodes_with_audio_tags(
+        &mut self,
+        nodes: Vec<SlabIndex>,
+        predicate: impl Fn(&AudioTags) -> bool + Sync,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let probe = Probe {
+            extensions: TAGGED_AUDIO_EXTENSIONS,
+            read: read_audio_tags,
+            cache: |cache| &mut cache.audio_tags,
+        };
+        self.probe_nodes(nodes, probe, predicate, token)
+    }
+}
+
+/// Reads the tags of an MP3 (ID3v2, ID3v1), FLAC, Ogg Vorbis/Opus or MP4
+/// file, recognized by content rather than extension. Returns `None` when the
+/// file can't be read or carries no tag.
+pub(crate) fn read_audio_tags(path: &Path) -> Option<AudioTags> {
+    let mut file = File::open(path).ok()?;
+    let mut tags = AudioTags::default();
+    let mut offset = 0;
+    let mut magic = read_magic(&mut file, offset)?;
+    if magic.starts_with(b"ID3") {
+        offset = read_id3v2(&mut file, &mut tags)?;
+        magic = read_magic(&mut file, offset).unwrap_or_default();
+    }
+    if magic.starts_with(b"fLaC") {
+        read_flac(&mut file, offset + 4, &mut tags);
+    } else if magic.starts_with(b"OggS") {
+        read_ogg(&mut file, offset, &mut tags);
+    } else if &magic[4..8] == b"ftyp" {
+        read_mp4(&mut file, offset, &mut tags);
+    }
+    read_id3v1(&mut file, &mut tags);
+    (tags != AudioTags::default()).then_some(tags)
+}
+
+fn read_magic(file: &mut File, offset: u64) -> Option<[u8; 8]> {
+    let mut magic = [0u8; 8];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut magic).ok()?;
+    Some(magic)
+}
+
+/// Reads at most `len` bytes from the current position, capped at `MAX_TAG_BYTES`.
+fn read_block(file: &mut impl Read, len: u64) -> Option<Vec<u8>> {
+    let mut block = Vec::new();
+    file.take(len.min(MAX_TAG_BYTES as u64))
+        .read_to_end(&mut block)
+        .ok()?;
+    Some(block)
+}
+
+/// Fills `slot` unless an earlier tag already did.
+fn fill_text(slot: &mut Option<String>, value: &str) {
+    let value = value.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    if slot.is_none() && !value.is_empty() {
+        *slot = Some(value.to_string());
+    }
+}
+
+fn fill_number(slot: &mut Option<u32>, value: &str) {
+    if slot.is_none() {
+        *slot = leading_number(value);
+    }
+}
+
+/// `1997-05-12` is 1997 and `3/12` is 3.
+fn leading_number(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// `17`, `(17)` and `(17)Rock` all name a genre; plain names are kept as is.
+fn id3_genre(value: &str) -> String {
+    let value = value.trim();
+    let (code, refinement) = match value.strip_prefix('(') {
+        Some(rest) => rest.split_once(')').unwrap_or((rest, "")),
+        None => (value, ""),
+    };
+    if !refinement.trim().is_empty() {
+        return refinement.trim().to_string();
+    }
+    code.parse::<usize>()
+        .ok()
+        .and_then(|code| ID3_GENRES.get(code))
+        .map_or_else(|| value.to_string(), |genre| genre.to_string())
+}
+
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 7) | u32::from(byte & 0x7f))
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().expect("four bytes"))
+}
+
+/// Undoes ID3 unsynchronisation, which inserts a zero after every 0xFF.
+fn remove_unsync(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut previous = 0;
+    for &byte in data {
+        if !(previous == 0xff && byte == 0) {
+            out.push(byte);
+        }
+        previous = byte;
+    }
+    out
+}
+
+/// Parses the ID3v2 tag at the start of the file and returns where it ends.
+fn read_id3v2(file: &mut File, tags: &mut AudioTags) -> Option<u64> {
+    let mut header = [0u8; 10];
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_exact(&mut header).ok()?;
+    let major = header[3];
+    let flags = header[5];
+    let size = syncsafe(&header[6..10]);
+    let footer = if major == 4 && flags & 0x10 != 0 {
+        10
+    } else {
+        0
+    };
+    let end = 10 + u64::from(size) + footer;
+    if !(2..=4).contains(&major) {
+        return Some(end);
+    }
+    let mut body = read_block(file, u64::from(size))?;
+    if flags & 0x80 != 0 && major < 4 {
+        body = remove_unsync(&body);
+    }
+
+    let mut pos = 0;
+    if flags & 0x40 != 0 && major >= 3 {
+        let extended = body.get(0..4)?;
+        pos = if major == 3 {
+            be_uint(extended) as usize + 4
+        } else {
+            syncsafe(extended) as usize
+        };
+    }
+    let (id_len, header_len) = if major == 2 { (3, 6) } else { (4, 10) };
+    while let Some(frame) = body.get(pos..pos + header_len) {
+        if frame[0] == 0 {
+            break;
+        }
+        let size = match major {
+            2 => be_uint(&frame[3..6]),
+            3 => be_uint(&frame[4..8]),
+            _ => u64::from(syncsafe(&frame[4..8])),
+        } as usize;
+        let frame_flags = if major == 2 {
+            0
+        } else {
+            be_uint(&frame[8..10]) as u16
+        };
+        let id = &frame[..id_len];
+        pos += header_len;
+        let Some(data) = body.get(pos..pos + size) else {
+            break;
+        };
+        pos += size;
+        if let Some(data) = id3_frame_payload(major, frame_flags, data) {
+            apply_id3_frame(id, &data, tags);
+        }
+    }
+    Some(end)
+}
+
+/// Strips the per-frame extras; `None` for compressed or encrypted frames.
+fn id3_frame_payload(major: u8, flags: u16, data: &[u8]) -> Option<Vec<u8>> {
+    match major {
+        3 => {
+            if flags & 0x00c0 != 0 {
+                return None;
+            }
+            let skip = if flags & 0x0020 != 0 { 1 } else { 0 };
+            Some(data.get(skip..)?.to_vec())
+        }
+        4 => {
+            if flags & 0x000c != 0 {
+                return None;
+            }
+            let mut skip = 0;
+            if flags & 0x0040 != 0 {
+                skip += 1;
+            }
+            if flags & 0x0001 != 0 {
+                skip += 4;
+            }
+            let data = data.get(skip..)?;
+            Some(if flags & 0x0002 != 0 {
+                remove_unsync(data)
+            } else {
+                data.to_vec()
+            })
+        }
+        _ => Some(data.to_vec()),
+    }
+}
+
+fn apply_id3_frame(id: &[u8], data: &[u8], tags: &mut AudioTags) {
+    let Some((&encoding, payload)) = data.split_first() else {
+        return;
+    };
+    match id {
+        b"COMM" | b"COM" => {
+            // Language, then a terminated description, then the text.
+            let Some(payload) = payload.get(3..) else {
+                return;
+            };
+            let (description, text) = split_terminated(encoding, payload);
+            // iTunes keeps its own data in described comments.
+            if decode_id3_text(encoding, description).starts_with("iTun") {
+                return;
+            }
+            fill_text(&mut tags.comment, &decode_id3_text(encoding, text));
+        }
+        _ => {
+            let text = decode_id3_text(encoding, payload);
+            // ID3v2.4 separates multiple values with NUL.
+            let text = text.split('\0').next().unwrap_or_default();
+            match id {
+                b"TPE1" | b"TP1" => fill_text(&mut tags.artist, text),
+                b"TALB" | b"TAL" => fill_text(&mut tags.album, text),
+                b"TIT2" | b"TT2" => fill_text(&mut tags.title, text),
+                b"TCON" | b"TCO" => fill_text(&mut tags.genre, &id3_genre(text)),
+                b"TYER" | b"TYE" | b"TDRC" => fill_number(&mut tags.year, text),
+                b"TRCK" | b"TRK" => fill_number(&mut tags.track, text),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Splits at the first string terminator of `encoding`: one zero byte, or
+/// two at an even offset for UTF-16.
+fn split_terminated(encoding: u8, data: &[u8]) -> (&[u8], &[u8]) {
+    let terminator = if matches!(encoding, 1 | 2) {
+        data.chunks_exact(2)
+            .position(|pair| pair == [0, 0])
+            .map(|i| (i * 2, 2))
+    } else {
+        data.iter().position(|&byte| byte == 0).map(|i| (i, 1))
+    };
+    match terminator {
+        Some((at, len)) => (&data[..at], &data[at + len..]),
+        None => (data, &[]),
+    }
+}
+
+fn decode_id3_text(encoding: u8, data: &[u8]) -> String {
+    match encoding {
+        0 => data.iter().map(|&byte| char::from(byte)).collect(),
+        1 | 2 => {
+            let (big_endian, data) = match data {
+                [0xfe, 0xff, rest @ ..] => (true, rest),
+                [0xff, 0xfe, rest @ ..] => (false, rest),
+                _ => (encoding == 2, data),
+            };
+            let units = data.chunks_exact(2).map(|pair| {
+                if big_endian {
+                    u16::from_be_bytes([pair[0], pair[1]])
+                } else {
+                    u16::from_le_bytes([pair[0], pair[1]])
+                }
+            });
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+        _ => String::from_utf8_lossy(data).into_owned(),
+    }
+}
+
+fn read_id3v1(file: &mut File, tags: &mut AudioTags) -> Option<()> {
+    let mut tag = [0u8; 128];
+    file.seek(SeekFrom::End(-128)).ok()?;
+    file.read_exact(&mut tag).ok()?;
+    if !tag.starts_with(b"TAG") {
+        return None;
+    }
+    let latin1 = |bytes: &[u8]| decode_id3_text(0, bytes);
+    fill_text(&mut tags.title, &latin1(&tag[3..33]));
+    fill_text(&mut tags.artist, &latin1(&tag[33..63]));
+    fill_text(&mut tags.album, &latin1(&tag[63..93]));
+    fill_number(&mut tags.year, &latin1(&tag[93..97]));
+    let comment = &tag[97..127];
+    // ID3v1.1 keeps the track in the last comment byte, after a zero.
+    if comment[28] == 0 && comment[29] != 0 {
+        fill_text(&mut tags.comment, &latin1(&comment[..28]));
+        tags.track.get_or_insert(u32::from(comment[29]));
+    } else {
+        fill_text(&mut tags.comment, &latin1(comment));
+    }
+    if let Some(genre) = ID3_GENRES.get(usize::from(tag[127])) {
+        fill_text(&mut tags.genre, genre);
+    }
+    Some(())
+}
+
+/// Walks the FLAC metadata blocks starting at `offset` up to the Vorbis comment.
+fn read_flac(file: &mut File, offset: u64, tags: &mut AudioTags) -> Option<()> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    loop {
+        let mut header = [0u8; 4];
+        file.read_exact(&mut header).ok()?;
+        let len = be_uint(&header[1..4]);
+        if header[0] & 0x7f == 4 {
+            read_vorbis_comment(&read_block(file, len)?, tags);
+            return Some(());
+        }
+        if header[0] & 0x80 != 0 {
+            return None;
+        }
+        file.seek(SeekFrom::Current(len as i64)).ok()?;
+    }
+}
+
+/// Reads the second packet of the first logical stream, which holds the
+/// comments for Vorbis, Opus and FLAC alike.
+fn read_ogg(file: &mut File, offset: u64, tags: &mut AudioTags) -> Option<()> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut serial = None;
+    let mut packet = Vec::new();
+    let mut packets = 0;
+    loop {
+        let mut header = [0u8; 27];
+        reader.read_exact(&mut header).ok()?;
+        if !header.starts_with(b"OggS") {
+            return None;
+        }
+        let mut lacing = vec![0u8; usize::from(header[26])];
+        reader.read_exact(&mut lacing).ok()?;
+        let mut body = vec![0u8; lacing.iter().map(|&len| usize::from(len)).sum()];
+        reader.read_exact(&mut body).ok()?;
+        if *serial.get_or_insert(le_u32(&header[14..18])) != le_u32(&header[14..18]) {
+            continue;
+        }
+        let mut start = 0;
+        for &len in &lacing {
+            let len = usize::from(len);
+            packet.extend_from_slice(&body[start..start + len]);
+            start += len;
+            // A lacing value below 255 ends the packet.
+            if len < 255 {
+                packets += 1;
+                if packets == 2 {
+                    break;
+                }
+                packet.clear();
+            }
+        }
+        if packets == 2 || (packets == 1 && packet.len() >= MAX_TAG_BYTES) {
+            break;
+        }
+    }
+    let comment = if let Some(rest) = packet.strip_prefix(b"\x03vorbis") {
+        rest
+    } else if let Some(rest) = packet.strip_prefix(b"OpusTags") {
+        rest
+    } else if packet.first().is_some_and(|&kind| kind & 0x7f == 4) {
+        packet.get(4..)?
+    } else {
+        return None;
+    };
+    read_vorbis_comment(comment, tags);
+    Some(())
+}
+
+/// Parses a Vorbis comment block, stopping quietly where it was truncated.
+fn read_vorbis_comment(data: &[u8], tags: &mut AudioTags) {
+    fn take<'a>(rest: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        let (head, tail) = rest.split_at_checked(len)?;
+        *rest = tail;
+        Some(head)
+    }
+    let mut rest = data;
+    let Some(vendor_len) = take(&mut rest, 4).map(le_u32) else {
+        return;
+    };
+    if take(&mut rest, vendor_len as usize).is_none() {
+        return;
+    }
+    let Some(count) = take(&mut rest, 4).map(le_u32) else {
+        return;
+    };
+    for _ in 0..count {
+        let Some(len) = take(&mut rest, 4).map(le_u32) else {
+            return;
+        };
+        let Some(entry) = take(&mut rest, len as usize) else {
+            return;
+        };
+        let entry = String::from_utf8_lossy(entry);
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "ARTIST" => fill_text(&mut tags.artist, value),
+            "ALBUM" => fill_text(&mut tags.album, value),
+            "TITLE" => fill_text(&mut tags.title, value),
+            "GENRE" => fill_text(&mut tags.genre, value),
+            "DATE" | "YEAR" => fill_number(&mut tags.year, value),
+            "TRACKNUMBER" => fill_number(&mut tags.track, value),
+            "COMMENT" | "DESCRIPTION" => fill_text(&mut tags.comment, value),
+            _ => {}
+        }
+    }
+}
+
+/// Finds the top-level `moov` atom and reads the iTunes item list below it.
+fn read_mp4(file: &mut File, offset: u64, tags: &mut AudioTags) -> Option<()> {
+    let moov = read_top_level_atom(file, offset, b"moov", MAX_MOOV_BYTES)?;
+    read_mp4_moov(&moov, tags)
+}
+
+fn read_mp4_moov(moov: &[u8], tags: &mut AudioTags) -> Option<()> {
+    let meta = match find_atom(moov, b"udta").and_then(|udta| find_atom(udta, b"meta")) {
+        Some(meta) => meta,
+        None => find_atom(moov, b"meta")?,
+    };
+    // The iTunes `meta` is a full box with four bytes of version and flags,
+    // the QuickTime one starts with its children.
+    let meta = if meta.get(4..8) == Some(b"hdlr") {
+        meta
+    } else {
+        meta.get(4..)?
+    };
+    for (kind, item) in mp4_atoms(find_atom(meta, b"ilst")?) {
+        // `data` holds a type indicator and a locale before the value.
+        let Some(value) = find_atom(item, b"data").and_then(|data| data.get(8..)) else {
+            continue;
+        };
+        let text = || String::from_utf8_lossy(value);
+        match &kind {
+            b"\xa9ART" => fill_text(&mut tags.artist, &text()),
+            b"\xa9alb" => fill_text(&mut tags.album, &text()),
+            b"\xa9nam" => fill_text(&mut tags.title, &text()),
+            b"\xa9gen" => fill_text(&mut tags.genre, &text()),
+            b"\xa9cmt" => fill_text(&mut tags.comment, &text()),
+            b"\xa9day" => fill_number(&mut tags.year, &text()),
+            b"trkn" => {
+                if let Some(track) = value.get(2..4).map(be_uint).filter(|&n| n > 0) {
+                    tags.track.get_or_insert(track as u32);
+                }
+            }
+            b"gnre" => {
+                let genre = value
+                    .get(..2)
+                    .map(be_uint)
+                    .and_then(|code| ID3_GENRES.get((code as usize).checked_sub(1)?));
+                if let Some(genre) = genre {
+                    fill_text(&mut tags.genre, genre);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(())
+}
@@ -1,31 +1,42 @@
 use crate::{
-    FileNodes, NameIndex, SearchOptions, SearchResultNode, SlabIndex, SlabNode,
-    SlabNodeMetadataCompact, State, ThinSlab,
+    ContentHashCache, FileNodes, IndexChange, IndexChangeKind, NameIndex, SearchOptions,
+    SearchResultNode, SlabIndex, SlabNode, SlabNodeMetadataCompact, State, ThinSlab,
+    audio_tags::AudioTags,
+    case_fold::eq_ignore_case,
+    changes::ChangeFeed,
+    file_probe::ProbeCache,
     highlight::derive_highlight_terms,
+    image_info::ImageInfo,
+    live_query::{LiveQueries, LiveQuery, QueryDelta, QuerySubscription},
     persistent::{PersistentStorage, read_cache_from_file, write_cache_to_file},
+    query::ScopeNotFound,
     query_preprocessor::{expand_query_home_dirs, strip_query_quotes},
 };
 use anyhow::{Context, Result, anyhow};
-use cardinal_sdk::{EventFlag, FsEvent, ScanType, current_event_id};
+use cardinal_sdk::{
+    EventFlag, EventId, FsEvent, PolledDir, ResumeCursor, ScanType, SourceKind, current_event_id,
+};
 use cardinal_syntax::{Expr, Filter, FilterKind, Term, optimize_query, parse_query};
+use crossbeam_channel::Receiver;
 use fswalk::{
-    Node, NodeMetadata, WalkData, should_ignore_path, walk_it, walk_it_without_root_chain,
+    Node, NodeFileType, NodeMetadata, WalkData, should_ignore_path, walk_it,
+    walk_it_without_root_chain,
 };
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use namepool::NamePool;
 use search_cancel::CancellationToken;
 use std::{
-    ffi::OsStr,
+    ffi::{OsStr, OsString},
     io::ErrorKind,
     path::{Path, PathBuf},
     sync::{
         LazyLock,
         atomic::{AtomicBool, Ordering},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 use thin_vec::ThinVec;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use typed_num::Num;
 use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick, is_nfd_quick};
 
@@ -34,10 +45,15 @@ static NEVER_STOPPED: AtomicBool = AtomicBool::new(false);
 
 pub struct SearchCache {
     pub(crate) file_nodes: FileNodes,
-    last_event_id: u64,
+    cursor: ResumeCursor,
     rescan_count: u64,
     pub(crate) name_index: NameIndex,
+    pub(crate) content_hashes: ContentHashCache,
+    pub(crate) audio_tags: ProbeCache<AudioTags>,
+    pub(crate) image_info: ProbeCache<ImageInfo>,
     stop: &'static AtomicBool,
+    changes: ChangeFeed,
+    live_queries: LiveQueries,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -113,7 +129,7 @@ impl std::fmt::Debug for SearchCache {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SearchCache")
             .field("path", &self.file_nodes.path())
-            .field("last_event_id", &self.last_event_id)
+            .field("cursor", &self.cursor)
             .field("rescan_count", &self.rescan_count)
             .field("slab_root", &self.file_nodes.root())
             .field("slab.len()", &self.file_nodes.len())
@@ -185,13 +201,21 @@ impl SearchCache {
                      slab_root,
                      slab,
                      name_index,
-                     last_event_id,
+                     cursor,
                      rescan_count,
+                     content_hashes,
                  }| {
                     // name pool construction speed is fast enough that caching it doesn't worth it.
                     let name_index = NameIndex::construct_name_pool(name_index);
                     let slab = FileNodes::new(path, ignore_paths, include_paths, slab, slab_root);
-                    Self::new(slab, last_event_id, rescan_count, name_index, cancel)
+                    Self::new(
+                        slab,
+                        cursor,
+                        rescan_count,
+                        name_index,
+                        content_hashes,
+                        cancel,
+                    )
                 },
             )
     }
@@ -261,7 +285,7 @@ impl SearchCache {
             Some((slab_root, slab, name_index))
         }
 
-        let last_event_id = current_event_id();
+        let cursor = ResumeCursor::now();
         let (slab_root, slab, name_index) = walkfs_to_slab(walk_data)?;
         let slab = FileNodes::new(
             walk_data.root_path.to_path_buf(),
@@ -271,22 +295,35 @@ impl SearchCache {
             slab_root,
         );
         // metadata cache inits later
-        Some(Self::new(slab, last_event_id, 0, name_index, cancel))
+        Some(Self::new(
+            slab,
+            cursor,
+            0,
+            name_index,
+            ContentHashCache::default(),
+            cancel,
+        ))
     }
 
     fn new(
         slab: FileNodes,
-        last_event_id: u64,
+        cursor: ResumeCursor,
         rescan_count: u64,
         name_index: NameIndex,
+        content_hashes: ContentHashCache,
         cancel: &'static AtomicBool,
     ) -> Self {
         Self {
             file_nodes: slab,
-            last_event_id,
+            cursor,
             rescan_count,
             name_index,
+            content_hashes,
+            audio_tags: ProbeCache::default(),
+            image_info: ProbeCache::default(),
             stop: cancel,
+            changes: ChangeFeed::default(),
+            live_queries: LiveQueries::default(),
         }
     }
 
@@ -306,10 +343,15 @@ impl SearchCache {
                 ThinSlab::new(),
                 SlabIndex::new(0),
             ),
-            last_event_id: 0,
+            cursor: ResumeCursor::new(SourceKind::native(), 0),
             rescan_count: 0,
             name_index: NameIndex::default(),
+            content_hashes: ContentHashCache::default(),
+            audio_tags: ProbeCache::default(),
+            image_info: ProbeCache::default(),
             stop: cancel,
+            changes: ChangeFeed::default(),
+            live_queries: LiveQueries::default(),
         }
     }
 
@@ -417,13 +459,22 @@ impl SearchCache {
             return Ok(primary);
         };
 
-        let secondary = self.search_with_query_line_transform_base(
+        let secondary = match self.search_with_query_line_transform_base(
             &alt_line,
             base,
             options,
             cancellation_token,
             transform,
-        )?;
+        ) {
+            Ok(secondary) => secondary,
+            // A `parent:` or `in:` path usually exists in only one of the two
+            // forms, which must not fail the search.
+            Err(err) if err.is::<ScopeNotFound>() => {
+                debug!("Alternate normalization query skipped: {err}");
+                return Ok(primary);
+            }
+            Err(err) => return Err(err),
+        };
         Ok(primary.merge(secondary))
     }
 
@@ -600,13 +651,266 @@ impl SearchCache {
         Some(())
     }
 
+    /// Directories under the watch root together with the mtimes recorded for
+    /// them, used to seed `EventWatcher::spawn_polling`.
+    ///
+    /// Nodes walked without metadata count as directories when they have
+    /// children; the poller finds empty ones on its own.
+    pub fn polled_dirs(&self) -> Vec<PolledDir> {
+        let Some(root) = self.node_index_for_path(self.file_nodes.path()) else {
+            return Vec::new();
+        };
+        let mut dirs = Vec::new();
+        let mut stack = vec![(root, self.file_nodes.path().to_path_buf())];
+        while let Some((index, path)) = stack.pop() {
+            let node = &self.file_nodes[index];
+            let metadata = node.metadata.as_ref();
+            let is_dir = metadata
+                .as_ref()
+                .is_some_and(|m| m.r#type() == NodeFileType::Dir)
+                || !node.children.is_empty();
+            if !is_dir {
+                continue;
+            }
+            for &child in &node.children {
+                stack.push((child, path.join(self.file_nodes[child].name())));
+            }
+            dirs.push(PolledDir {
+                path,
+                mtime: metadata.and_then(|m| m.mtime()).map(|m| m.get()),
+            });
+        }
+        dirs
+    }
+
     fn push_node(&mut self, node: SlabNode) -> SlabIndex {
         let name = node.name();
         let index = self.file_nodes.insert(node);
         self.name_index.add_index(name, index, &self.file_nodes);
+        self.record_change(IndexChangeKind::Added, index);
         index
     }
 
+    /// Subscribe to the changes applied to the index.
+    ///
+    /// Every batch handled by `handle_fs_events` or a rescan is delivered as one
+    /// `Vec<IndexChange>` once it's fully applied; batches that change nothing
+    /// are not sent. Dropping the receiver unsubscribes.
+    pub fn subscribe_changes(&mut self) -> Receiver<Vec<IndexChange>> {
+        self.changes.subscribe()
+    }
+
+    /// Register a standing query and keep its result set up to date.
+    ///
+    /// Returns the current results plus a receiver of `QueryDelta`s. After
+    /// each applied batch only the nodes the batch touched (and their
+    /// parents) are checked against the query. Queries with a duplicate
+    /// filter (`dupe:`, `sizedupe:`, ...) are the exception: a new or changed
+    /// node can start or end a group with untouched ones, so they are re-run
+    /// over the whole index, which costs a full search. Those re-runs are
+    /// throttled to one per [`Self::set_live_query_rerun_interval`]; batches
+    /// arriving sooner are folded into the next run, which happens on a
+    /// later batch or on [`Self::refresh_live_queries`].
+    ///
+    /// Dropping the receiver unregisters the query. Returns `Ok(None)` if the
+    /// initial search was cancelled.
+    pub fn subscribe_query(
+        &mut self,
+        line: &str,
+        options: SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<Option<QuerySubscription>> {
+        let SearchOutcome { nodes, highlights } =
+            self.search_with_options(line, options, cancellation_token)?;
+        let Some(nodes) = nodes else {
+            return Ok(None);
+        };
+        let deltas =
+            self.live_queries
+                .register(line.to_string(), options, nodes.iter().copied().collect());
+        Ok(Some(QuerySubscription {
+            nodes,
+            highlights,
+            deltas,
+        }))
+    }
+
+    /// Minimum pause between two whole-index runs of one duplicate-filter
+    /// live query, one second by default. `Duration::ZERO` re-runs them on
+    /// every batch.
+    pub fn set_live_query_rerun_interval(&mut self, interval: Duration) {
+        self.live_queries.rerun_interval = interval;
+    }
+
+    /// Run the deferred whole-index live queries whose rerun interval has
+    /// passed. Call it periodically, or the last batch of a burst is only
+    /// reflected once another batch arrives.
+    pub fn refresh_live_queries(&mut self) {
+        let mut live_queries = std::mem::take(&mut self.live_queries);
+        let interval = live_queries.rerun_interval;
+        live_queries
+            .queries_mut()
+            .retain_mut(|query| !query.is_due(interval) || self.rerun_whole_index_query(query));
+        self.live_queries = live_queries;
+    }
+
+    fn tracks_changes(&self) -> bool {
+        self.changes.is_active() || self.live_queries.is_active()
+    }
+
+    /// Update live queries with the changes recorded for the applied batch, then
+    /// deliver them to change subscribers.
+    fn publish_changes(&mut self) {
+        let changes = self.changes.take_pending();
+        if changes.is_empty() {
+            return;
+        }
+        self.update_live_queries(&changes);
+        self.changes.send(&changes);
+    }
+
+    fn update_live_queries(&mut self, changes: &[IndexChange]) {
+        if !self.live_queries.is_active() {
+            return;
+        }
+        let event_id = changes
+            .iter()
+            .map(|change| change.event_id)
+            .max()
+            .unwrap_or_default();
+        // Freed indices; a live one among them was reused by a new node.
+        let mut freed = HashSet::new();
+        let mut touched = HashSet::new();
+        for change in changes {
+            if change.kind == IndexChangeKind::Removed {
+                freed.insert(change.index);
+            }
+            touched.insert(change.index);
+            // Parents gained or lost a child.
+            let old_path = match &change.kind {
+                IndexChangeKind::Moved { from } => Some(from.as_path()),
+                _ => None,
+            };
+            for path in [Some(change.path.as_path()), old_path]
+                .into_iter()
+                .flatten()
+            {
+                if let Some(parent) = path
+                    .parent()
+                    .and_then(|parent| self.node_index_for_path(parent))
+                {
+                    touched.insert(parent);
+                }
+            }
+        }
+        let mut touched: Vec<SlabIndex> = touched.into_iter().collect();
+        touched.sort_unstable();
+        let alive: Vec<SlabIndex> = touched
+            .iter()
+            .copied()
+            .filter(|&index| self.file_nodes.get(index).is_some())
+            .collect();
+
+        let mut live_queries = std::mem::take(&mut self.live_queries);
+        let interval = live_queries.rerun_interval;
+        live_queries.queries_mut().retain_mut(|query| {
+            if query.whole_index {
+                let pending = query.pending.get_or_insert_default();
+                pending.freed.extend(&freed);
+                pending.event_id = pending.event_id.max(event_id);
+                return !query.is_due(interval) || self.rerun_whole_index_query(query);
+            }
+            let matched = if alive.is_empty() {
+                HashSet::new()
+            } else {
+                self.live_query_matches(query, Some(&alive))
+                    .unwrap_or_default()
+            };
+            self.send_live_query_delta(query, &touched, &matched, &freed, event_id)
+        });
+        self.live_queries = live_queries;
+    }
+
+    /// Re-run a duplicate-filter live query over the whole index, accounting
+    /// for every batch since its last run. Returns whether the subscriber is
+    /// still listening.
+    fn rerun_whole_index_query(&mut self, query: &mut LiveQuery) -> bool {
+        let Some(pending) = query.pending.take() else {
+            return true;
+        };
+        query.last_run = Instant::now();
+        let Some(matched) = self.live_query_matches(query, None) else {
+            return true;
+        };
+        // Nodes in neither set can't have changed their result.
+        let mut candidates: Vec<SlabIndex> = query.results.union(&matched).copied().collect();
+        candidates.sort_unstable();
+        self.send_live_query_delta(
+            query,
+            &candidates,
+            &matched,
+            &pending.freed,
+            pending.event_id,
+        )
+    }
+
+    /// Compare `candidates` against `matched`, update the query's results and
+    /// send the difference. Returns whether the subscriber is still listening.
+    fn send_live_query_delta(
+        &self,
+        query: &mut LiveQuery,
+        candidates: &[SlabIndex],
+        matched: &HashSet<SlabIndex>,
+        freed: &HashSet<SlabIndex>,
+        event_id: EventId,
+    ) -> bool {
+        let mut delta = QueryDelta {
+            event_id,
+            ..QueryDelta::default()
+        };
+        for &index in candidates {
+            let reused = freed.contains(&index) && self.file_nodes.get(index).is_some();
+            let was_matching = query.results.contains(&index);
+            let is_matching = matched.contains(&index);
+            if was_matching && (!is_matching || reused) {
+                query.results.remove(&index);
+                delta.removed.push(index);
+            }
+            if is_matching && (!was_matching || reused) {
+                query.results.insert(index);
+                delta.added.push(index);
+            }
+        }
+        delta.added.is_empty() && delta.removed.is_empty() || query.send(delta)
+    }
+
+    /// Runs a live query over `base`, or the whole index for `None`.
+    fn live_query_matches(
+        &mut self,
+        query: &LiveQuery,
+        base: Option<&Vec<SlabIndex>>,
+    ) -> Option<HashSet<SlabIndex>> {
+        match self.search_with_options_base(
+            &query.line,
+            base,
+            query.options,
+            CancellationToken::noop(),
+        ) {
+            Ok(outcome) => Some(outcome.nodes.unwrap_or_default().into_iter().collect()),
+            Err(err) => {
+                warn!("Live query {:?} failed: {err:?}", query.line);
+                None
+            }
+        }
+    }
+
+    fn record_change(&mut self, kind: IndexChangeKind, index: SlabIndex) {
+        if self.tracks_changes() {
+            let path = self.file_nodes.node_path(index).unwrap_or_default();
+            self.changes.record(kind, index, path);
+        }
+    }
+
     /// Removes a node by path and its children recursively.
     fn remove_node_path(&mut self, path: &Path) -> Option<SlabIndex> {
         let Ok(path) = path.strip_prefix("/") else {
@@ -628,6 +932,121 @@ impl SearchCache {
         Some(current)
     }
 
+    /// Move the subtree at `from` to `to` in place, keeping every `SlabIndex`.
+    ///
+    /// Returns `false` when the filesystem doesn't look like `from` was renamed to
+    /// `to` (source still present, destination missing or ignored, or a cached
+    /// file whose size or mtime differs), so the caller can rescan both paths.
+    fn move_node_path(&mut self, from: &Path, to: &Path) -> bool {
+        if to.starts_with(from) || !to.starts_with(self.file_nodes.path()) || self.should_ignore(to)
+        {
+            return false;
+        }
+        if from.symlink_metadata().err().map(|e| e.kind()) != Some(ErrorKind::NotFound) {
+            return false;
+        }
+        let (Some(parent), Some(name)) = (to.parent(), to.file_name()) else {
+            return false;
+        };
+        let Ok(metadata) = to.symlink_metadata() else {
+            return false;
+        };
+        let Some(index) = self.node_index_for_path(from) else {
+            return false;
+        };
+        let metadata = SlabNodeMetadataCompact::some(NodeMetadata::from(metadata));
+        if let (Some(old), Some(new)) =
+            (self.file_nodes[index].metadata.as_ref(), metadata.as_ref())
+        {
+            // A rename never changes a file's size or mtime.
+            let same_file = old.r#type() == new.r#type()
+                && (new.r#type() != NodeFileType::File
+                    || (old.size() == new.size() && old.mtime() == new.mtime()));
+            if !same_file {
+                return false;
+            }
+        }
+
+        let new_parent = self.create_node_chain(parent);
+        if let Some(&replaced) = self.file_nodes[new_parent]
+            .children
+            .iter()
+            .find(|&&x| OsStr::new(self.file_nodes[x].name()) == name)
+        {
+            self.remove_node(replaced);
+        }
+
+        // Full paths order the name index, so every entry of the subtree is
+        // taken out before the move and put back at its new position after it.
+        let mut subtree = vec![index];
+        let mut i = 0;
+        while let Some(&current) = subtree.get(i) {
+            subtree.extend_from_slice(&self.file_nodes[current].children);
+            i += 1;
+        }
+        for &node in &subtree {
+            let removed = self
+                .name_index
+                .remove_index(self.file_nodes[node].name(), node);
+            assert!(removed, "inconsistent name index and node");
+        }
+        let old_paths: Vec<PathBuf> = if self.tracks_changes() {
+            subtree
+                .iter()
+                .map(|&node| self.file_nodes.node_path(node).unwrap_or_default())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if let Some(old_parent) = self.file_nodes[index].parent() {
+            self.file_nodes[old_parent].children.retain(|&x| x != index);
+        }
+        let name = NAME_POOL.push(name.to_string_lossy().as_ref());
+        let node = &mut self.file_nodes[index];
+        node.set_name_and_parent(name, Some(new_parent));
+        node.metadata = metadata;
+        self.file_nodes[new_parent].add_children(index);
+        for &node in &subtree {
+            self.name_index
+                .add_index(self.file_nodes[node].name(), node, &self.file_nodes);
+        }
+        for (&node, from) in subtree.iter().zip(old_paths) {
+            self.record_change(IndexChangeKind::Moved { from }, node);
+        }
+        true
+    }
+
+    /// Apply paired rename events as in-place moves and return the events that
+    /// still have to be rescanned.
+    fn apply_renames(&mut self, mut events: Vec<FsEvent>) -> Vec<FsEvent> {
+        let mut moved = vec![false; events.len()];
+        let mut i = 0;
+        while i + 1 < events.len() {
+            let (from, to) = (&events[i], &events[i + 1]);
+            self.changes.set_event_id(to.id);
+            if !is_rename_pair(from, to) || !self.move_node_path(&from.path, &to.path) {
+                i += 1;
+                continue;
+            }
+            info!("Node moved: {:?} -> {:?}", from.path, to.path);
+            let (from, to) = (from.path.clone(), to.path.clone());
+            // Earlier changes under the old location now live under the new one.
+            for event in &mut events[..i] {
+                if let Ok(rest) = event.path.strip_prefix(&from) {
+                    event.path = to.join(rest);
+                }
+            }
+            moved[i] = true;
+            moved[i + 1] = true;
+            i += 2;
+        }
+        events
+            .into_iter()
+            .zip(moved)
+            .filter_map(|(event, moved)| (!moved).then_some(event))
+            .collect()
+    }
+
     // Create node chain of specific path
     fn create_node_chain(&mut self, path: &Path) -> SlabIndex {
         let path = path
@@ -689,14 +1108,11 @@ impl SearchCache {
         );
         // Ensure node of the path parent is existed
         let parent = self.create_node_chain(parent);
-        // Remove node(if exists) and do a full rescan
-        if let Some(&old_node) = self.file_nodes[parent]
+        let existing = self.file_nodes[parent]
             .children
             .iter()
-            .find(|&&x| path.file_name() == Some(OsStr::new(self.file_nodes[x].name())))
-        {
-            self.remove_node(old_node);
-        }
+            .copied()
+            .find(|&x| path.file_name() == Some(OsStr::new(self.file_nodes[x].name())));
         // For incremental data, we need metadata
         let walk_data = WalkData::new(
             path,
@@ -705,12 +1121,32 @@ impl SearchCache {
             true,
             || self.stop.load(Ordering::Relaxed),
         );
-        walk_it_without_root_chain(&walk_data).map(|node| {
-            let node = self.create_node_slab_update_name_index_and_name_pool(Some(parent), &node);
-            // Push the newly created node to the parent's children
-            self.file_nodes[parent].add_children(node);
-            node
-        })
+        let Some(node) = walk_it_without_root_chain(&walk_data) else {
+            if let Some(existing) = existing {
+                self.remove_node(existing);
+            }
+            return None;
+        };
+        let mut summary = RescanSummary::default();
+        match existing {
+            // Reconcile the existing subtree so unchanged nodes keep their `SlabIndex`.
+            Some(existing) => {
+                let known = self.file_nodes[existing].metadata.is_some();
+                self.reconcile_node(existing, &node, true, &mut summary);
+                // The event names this node, so report it even if its metadata
+                // had never been fetched before.
+                if !known && self.file_nodes[existing].metadata.is_some() {
+                    self.record_change(IndexChangeKind::MetadataChanged, existing);
+                }
+                Some(existing)
+            }
+            None => {
+                let node = self.insert_walked_node(parent, &node, true, &mut summary);
+                // Push the newly created node to the parent's children
+                self.file_nodes[parent].add_children(node);
+                Some(node)
+            }
+        }
     }
 
     // `Self::scan_path_nonrecursive`function returns index of the constructed node.
@@ -742,38 +1178,213 @@ impl SearchCache {
         })
     }
 
-    pub fn rescan_with_walk_data<F>(&mut self, walk_data: &WalkData<'_, F>) -> Option<()>
+    /// Walk the tree described by `walk_data` again and reconcile it with the slab.
+    ///
+    /// Nodes that still exist keep their `SlabIndex`, vanished ones are freed and
+    /// new ones inserted, so indices held by the UI or clients stay valid.
+    /// If cancelled during walking, None is returned and the cache is untouched.
+    pub fn rescan_with_walk_data<F>(&mut self, walk_data: &WalkData<'_, F>) -> Option<RescanSummary>
     where
         F: Fn() -> bool + Send + Sync,
     {
-        let Some(new_cache) = Self::walk_fs_with_walk_data(walk_data, self.stop) else {
+        if self.is_noop() {
+            // Nothing to preserve, the ordered bulk construction is much cheaper.
+            let Some(new_cache) = Self::walk_fs_with_walk_data(walk_data, self.stop) else {
+                info!("Rescan cancelled.");
+                return None;
+            };
+            let changes = std::mem::take(&mut self.changes);
+            let live_queries = std::mem::take(&mut self.live_queries);
+            *self = new_cache;
+            self.changes = changes;
+            self.live_queries = live_queries;
+            if self.tracks_changes() {
+                self.changes.set_event_id(self.cursor.event_id());
+                let indices: Vec<SlabIndex> =
+                    self.file_nodes.iter().map(|(index, _)| index).collect();
+                for index in indices {
+                    self.record_change(IndexChangeKind::Added, index);
+                }
+                self.publish_changes();
+            }
+            return Some(RescanSummary {
+                added: self.file_nodes.len(),
+                ..RescanSummary::default()
+            });
+        }
+        let cursor = ResumeCursor::now();
+        let visit_time = Instant::now();
+        let Some(node) = walk_it(walk_data) else {
             info!("Rescan cancelled.");
             return None;
         };
-        *self = new_cache;
-        Some(())
+        info!(
+            "Walk data: {:?}, time: {:?}",
+            walk_data,
+            visit_time.elapsed()
+        );
+
+        let reconcile_time = Instant::now();
+        let mut summary = RescanSummary::default();
+        self.changes.set_event_id(cursor.event_id());
+        self.reconcile_node(self.file_nodes.root(), &node, false, &mut summary);
+        self.file_nodes.set_scope(
+            walk_data.root_path.to_path_buf(),
+            walk_data.ignore_directories.to_vec(),
+            walk_data.include_paths.to_vec(),
+        );
+        self.publish_changes();
+        self.cursor = cursor;
+        self.rescan_count = 0;
+        info!(
+            "Rescan reconciled: {:?}, time: {:?}",
+            summary,
+            reconcile_time.elapsed()
+        );
+        Some(summary)
     }
 
-    pub fn rescan(&mut self) {
-        // Remove all memory consuming cache early for memory consumption in Self::walk_fs_new.
-        let Some(new_cache) = Self::walk_fs_with_walk_data(
-            &WalkData::new(
-                self.file_nodes.path(),
-                self.file_nodes.ignore_paths(),
-                self.file_nodes.include_paths(),
-                false,
-                || self.stop.load(Ordering::Relaxed),
-            ),
-            self.stop,
-        ) else {
-            info!("Rescan cancelled.");
+    pub fn rescan(&mut self) -> Option<RescanSummary> {
+        let mut root = PathBuf::new();
+        let mut ignore_paths = Vec::new();
+        let mut include_paths = Vec::new();
+        let walk_data = self.walk_data(
+            &mut root,
+            &mut ignore_paths,
+            &mut include_paths,
+            CancellationToken::noop(),
+        );
+        self.rescan_with_walk_data(&walk_data)
+    }
+
+    /// Bring the subtree at `index` in line with the freshly walked `node`.
+    ///
+    /// Children are matched by name: matches are reconciled recursively, slab
+    /// children missing from `node` are removed and new ones inserted.
+    /// `metadata_fetched` tells whether the walk fetched metadata.
+    fn reconcile_node(
+        &mut self,
+        index: SlabIndex,
+        node: &Node,
+        metadata_fetched: bool,
+        summary: &mut RescanSummary,
+    ) {
+        let metadata = walked_metadata(node, metadata_fetched);
+        if let (Some(old), Some(new)) =
+            (self.file_nodes[index].metadata.as_ref(), metadata.as_ref())
+            && (old.r#type() != new.r#type()
+                || old.size() != new.size()
+                || old.ctime() != new.ctime()
+                || old.mtime() != new.mtime()
+                || old.permissions() != new.permissions()
+                || old.has_hidden_flag() != new.has_hidden_flag())
+        {
+            summary.modified += 1;
+            self.file_nodes[index].metadata = metadata;
+            self.record_change(IndexChangeKind::MetadataChanged, index);
+        } else {
+            // Metadata the walk didn't fetch may be stale; let it be fetched lazily again.
+            self.file_nodes[index].metadata = metadata;
+        }
+
+        let mut existing: HashMap<&'static str, SlabIndex> = self.file_nodes[index]
+            .children
+            .iter()
+            .map(|&child| (self.file_nodes[child].name(), child))
+            .collect();
+        let matched: Vec<Option<SlabIndex>> = node
+            .children
+            .iter()
+            .map(|child| existing.remove(&*child.name))
+            .collect();
+        for (_, vanished) in existing {
+            summary.removed += self.remove_node(vanished);
+        }
+        let children = node
+            .children
+            .iter()
+            .zip(matched)
+            .map(|(child, matched)| match matched {
+                Some(matched) => {
+                    self.reconcile_node(matched, child, metadata_fetched, summary);
+                    matched
+                }
+                None => self.insert_walked_node(index, child, metadata_fetched, summary),
+            })
+            .collect::<ThinVec<_>>();
+        self.file_nodes[index].children = children;
+    }
+
+    /// Insert a walked subtree that has no counterpart in the slab yet.
+    fn insert_walked_node(
+        &mut self,
+        parent: SlabIndex,
+        node: &Node,
+        metadata_fetched: bool,
+        summary: &mut RescanSummary,
+    ) -> SlabIndex {
+        let metadata = walked_metadata(node, metadata_fetched);
+        let name = NAME_POOL.push(&node.name);
+        let index = self.push_node(SlabNode::new(Some(parent), name, metadata));
+        summary.added += 1;
+        self.file_nodes[index].children = node
+            .children
+            .iter()
+            .map(|child| self.insert_walked_node(index, child, metadata_fetched, summary))
+            .collect();
+        index
+    }
+
+    /// Reconcile the direct entries of the watch root after the root directory
+    /// itself changed, instead of re-walking everything below it.
+    ///
+    /// Vanished entries are removed and new ones scanned; entries that are still
+    /// there are left to their own events.
+    fn rescan_root_entries(&mut self) {
+        let root_path = self.file_nodes.path().to_path_buf();
+        let Some(root) = self.node_index_for_path(&root_path) else {
+            return;
+        };
+        if let Ok(metadata) = root_path.symlink_metadata() {
+            let metadata = SlabNodeMetadataCompact::some(NodeMetadata::from(metadata));
+            let changed = match (self.file_nodes[root].metadata.as_ref(), metadata.as_ref()) {
+                (Some(old), Some(new)) => old.mtime() != new.mtime() || old.ctime() != new.ctime(),
+                _ => true,
+            };
+            self.file_nodes[root].metadata = metadata;
+            if changed {
+                self.record_change(IndexChangeKind::MetadataChanged, root);
+            }
+        }
+        let Ok(entries) = std::fs::read_dir(&root_path) else {
             return;
         };
-        *self = new_cache;
+        let names: HashSet<OsString> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.file_name()))
+            .collect();
+        let vanished: Vec<SlabIndex> = self.file_nodes[root]
+            .children
+            .iter()
+            .copied()
+            .filter(|&child| !names.contains(OsStr::new(self.file_nodes[child].name())))
+            .collect();
+        for child in vanished {
+            self.remove_node(child);
+        }
+        for name in names {
+            let known = self.file_nodes[root]
+                .children
+                .iter()
+                .any(|&child| OsStr::new(self.file_nodes[child].name()) == name);
+            if !known {
+                self.scan_path_recursive(&root_path.join(name));
+            }
+        }
     }
 
-    /// Removes a node and its children recursively by index.
-    fn remove_node(&mut self, index: SlabIndex) {
+    /// Removes a node and its children recursively by index, returning how many
+    /// nodes were removed.
+    fn remove_node(&mut self, index: SlabIndex) -> usize {
         fn remove_single_node(cache: &mut SearchCache, index: SlabIndex) {
             if let Some(node) = cache.file_nodes.try_remove(index) {
                 let removed = cache.name_index.remove_index(node.name(), index);
@@ -781,24 +1392,37 @@ impl SearchCache {
             }
         }
 
+        // Record while the parent chain is intact so paths can still be built.
+        if self.tracks_changes() {
+            let mut stack = vec![index];
+            while let Some(current) = stack.pop() {
+                stack.extend_from_slice(&self.file_nodes[current].children);
+                self.record_change(IndexChangeKind::Removed, current);
+            }
+        }
         // Remove parent reference, make whole subtree unreachable.
         if let Some(parent) = self.file_nodes[index].parent() {
             self.file_nodes[parent].children.retain(|&x| x != index);
         }
+        let mut removed = 0;
         let mut stack = vec![index];
         while let Some(current) = stack.pop() {
             stack.extend_from_slice(&self.file_nodes[current].children);
             remove_single_node(self, current);
+            removed += 1;
         }
+        removed
     }
 
     pub fn flush_snapshot_to_file(&mut self, cache_path: &Path) -> Result<()> {
+        self.prune_content_hashes();
         let name_index = self.name_index.as_persistent();
         let slab = self.file_nodes.take_slab();
+        let content_hashes = std::mem::take(&mut self.content_hashes);
 
         let storage = PersistentStorage {
             version: Num,
-            last_event_id: self.last_event_id,
+            cursor: self.cursor,
             rescan_count: self.rescan_count,
             path: self.file_nodes.path().to_path_buf(),
             ignore_paths: self.file_nodes.ignore_paths().clone(),
@@ -806,24 +1430,36 @@ impl SearchCache {
             slab_root: self.file_nodes.root(),
             name_index,
             slab,
+            content_hashes,
         };
 
         let flush_result =
             write_cache_to_file(cache_path, &storage).context("Write cache to file failed.");
 
-        let PersistentStorage { slab, .. } = storage;
+        let PersistentStorage {
+            slab,
+            content_hashes,
+            ..
+        } = storage;
         self.file_nodes.put_slab(slab);
+        self.content_hashes = content_hashes;
 
         flush_result
     }
 
-    pub fn flush_to_file(self, cache_path: &Path) -> Result<()> {
+    pub fn flush_to_file(mut self, cache_path: &Path) -> Result<()> {
+        self.prune_content_hashes();
         let Self {
             file_nodes,
-            last_event_id,
+            cursor,
             rescan_count,
             name_index,
+            content_hashes,
+            audio_tags: _,
+            image_info: _,
             stop: _,
+            changes: _,
+            live_queries: _,
         } = self;
         let (path, ignore_paths, include_paths, slab_root, slab) = file_nodes.into_parts();
         let name_index = name_index.into_persistent();
@@ -837,24 +1473,50 @@ impl SearchCache {
                 slab_root,
                 slab,
                 name_index,
-                last_event_id,
+                cursor,
                 rescan_count,
+                content_hashes,
             },
         )
         .context("Write cache to file failed.")
     }
 
-    fn update_last_event_id(&mut self, event_id: u64) {
-        if event_id <= self.last_event_id {
-            debug!("last_event_id {} |< {event_id}", self.last_event_id);
+    /// Forgets the content hashes of files that left the index.
+    fn prune_content_hashes(&mut self) {
+        let mut content_hashes = std::mem::take(&mut self.content_hashes);
+        content_hashes.retain(|path| self.node_index_for_path(path).is_some());
+        self.content_hashes = content_hashes;
+    }
+
+    fn advance_cursor(&mut self, kind: SourceKind, event_id: EventId) {
+        let previous = self.cursor;
+        if self.cursor.advance(kind, event_id) {
+            debug!("cursor {previous:?} => {:?}", self.cursor);
         } else {
-            debug!("last_event_id {} => {event_id}", self.last_event_id);
-            self.last_event_id = event_id;
+            debug!("cursor {previous:?} |< {event_id}");
         }
     }
 
-    pub fn last_event_id(&mut self) -> u64 {
-        self.last_event_id
+    /// Where this index left off; persisted with the cache.
+    pub fn resume_cursor(&self) -> ResumeCursor {
+        self.cursor
+    }
+
+    /// The event id a `kind` source should be started from to pick up where
+    /// this index left off.
+    ///
+    /// A cursor issued by another backend can't be resumed; the source then
+    /// starts from now and changes in between are only seen by the next rescan.
+    pub fn resume_event_id(&self, kind: SourceKind) -> EventId {
+        self.cursor.since(kind).unwrap_or_else(|| {
+            info!("Cannot resume {:?} from {:?}", kind, self.cursor);
+            current_event_id()
+        })
+    }
+
+    /// Content hashes kept for [`SearchCache::content_duplicates`].
+    pub fn content_hashes(&self) -> &ContentHashCache {
+        &self.content_hashes
     }
 
     pub fn rescan_count(&self) -> u64 {
@@ -916,7 +1578,19 @@ impl SearchCache {
             .collect()
     }
 
+    /// Apply a batch from this platform's native watcher, see
+    /// [`Self::handle_source_events`].
     pub fn handle_fs_events(&mut self, events: Vec<FsEvent>) -> Result<(), HandleFSEError> {
+        self.handle_source_events(SourceKind::native(), events)
+    }
+
+    /// Apply a batch reported by a `kind` [`ChangeSource`](cardinal_sdk::ChangeSource)
+    /// and move the resume cursor past it.
+    pub fn handle_source_events(
+        &mut self,
+        kind: SourceKind,
+        events: Vec<FsEvent>,
+    ) -> Result<(), HandleFSEError> {
         let max_event_id = events.iter().map(|e| e.id).max();
         // If rescan needed, early exit.
         if events.iter().any(|event| {
@@ -933,23 +1607,57 @@ impl SearchCache {
             self.rescan_count = self.rescan_count.saturating_add(1);
             return Err(HandleFSEError::Rescan);
         }
-        for scan_path in scan_paths(events) {
+        let events = self.apply_renames(events);
+        let root = self.file_nodes.path().to_path_buf();
+        // Only recursive scans of the root or above need a full rebuild (see
+        // `FsEvent::should_rescan`); other events on them are recovered here.
+        let (root_events, events): (Vec<_>, Vec<_>) = events
+            .into_iter()
+            .partition(|event| root.starts_with(&event.path));
+        if let Some(root_event_id) = root_events
+            .iter()
+            .filter(|event| event.path == root && event.flag.scan_type() != ScanType::Nop)
+            .map(|event| event.id)
+            .max()
+        {
+            info!("Rescanning root entries: {root:?}");
+            self.changes.set_event_id(root_event_id);
+            self.rescan_root_entries();
+        }
+        for (scan_path, event_id) in scan_paths(events) {
             info!("Scanning path: {scan_path:?}");
+            self.changes.set_event_id(event_id);
             let folder = self.scan_path_recursive(&scan_path);
             if folder.is_some() {
                 info!("Node changed: {folder:?}");
             }
         }
+        self.publish_changes();
         if let Some(max_event_id) = max_event_id {
-            self.update_last_event_id(max_event_id);
+            self.advance_cursor(kind, max_event_id);
         }
         Ok(())
     }
 }
 
+/// Whether two neighbouring events are the old and new halves of one rename.
+///
+/// FSEvents reports a rename as two `ItemRenamed` events with consecutive ids,
+/// and the inotify backend numbers `IN_MOVED_FROM`/`IN_MOVED_TO` pairs the same
+/// way. Folder rescans requested through `MustScanSubDirs` are never paired.
+fn is_rename_pair(from: &FsEvent, to: &FsEvent) -> bool {
+    [from, to].iter().all(|event| {
+        event.flag.contains(EventFlag::ItemRenamed)
+            && !event.flag.contains(EventFlag::MustScanSubDirs)
+    }) && from.id.checked_add(1) == Some(to.id)
+        && from.path != to.path
+}
+
 fn path_segment_matches(name: &str, segment: &OsStr, case_insensitive: bool) -> bool {
     if case_insensitive {
-        segment.eq_ignore_ascii_case(name)
+        segment
+            .to_str()
+            .is_some_and(|segment| eq_ignore_case(segment, name))
     } else {
         OsStr::new(name) == segment
     }
@@ -993,8 +1701,8 @@ fn path_segment_matches(name: &str, segment: &OsStr, case_insensitive: bool) ->
 ///
 /// Result:
 /// - Local benchmarks skipped rescans for 173,034 events out of 415,449.
-fn scan_paths(events: Vec<FsEvent>) -> Vec<PathBuf> {
-    let mut candidates: Vec<(PathBuf, usize)> = events
+fn scan_paths(events: Vec<FsEvent>) -> Vec<(PathBuf, EventId)> {
+    let mut candidates: Vec<(PathBuf, usize, EventId)> = events
         .into_iter()
         .filter(|event| {
             // Sometimes there are ridiculous events assuming dir as file, so we always scan them as folder
@@ -1006,23 +1714,32 @@ fn scan_paths(events: Vec<FsEvent>) -> Vec<PathBuf> {
         .map(|event| {
             let path = event.path;
             let depth = path_depth(&path);
-            (path, depth)
+            (path, depth, event.id)
         })
         .collect();
 
-    candidates.sort_unstable_by(|(path_a, depth_a), (path_b, depth_b)| {
+    candidates.sort_unstable_by(|(path_a, depth_a, _), (path_b, depth_b, _)| {
         depth_a.cmp(depth_b).then_with(|| path_a.cmp(path_b))
     });
-    candidates.dedup_by(|(path_a, _), (path_b, _)| path_a == path_b);
+    candidates.dedup_by(|(path_a, _, id_a), (path_b, _, id_b)| {
+        let duplicated = path_a == path_b;
+        if duplicated {
+            *id_b = (*id_b).max(*id_a);
+        }
+        duplicated
+    });
 
-    let mut selected = Vec::with_capacity(candidates.len());
-    let mut selected_set = HashSet::with_capacity(candidates.len());
-    for (path, _) in candidates {
-        if has_selected_ancestor(&path, &selected_set) {
+    // Each selected path carries the latest event id it covers.
+    let mut selected: Vec<(PathBuf, EventId)> = Vec::with_capacity(candidates.len());
+    let mut selected_map = HashMap::with_capacity(candidates.len());
+    for (path, _, id) in candidates {
+        if let Some(ancestor) = selected_ancestor(&path, &selected_map) {
+            let covered_id = &mut selected[ancestor].1;
+            *covered_id = (*covered_id).max(id);
             continue;
         }
-        selected_set.insert(path.clone());
-        selected.push(path);
+        selected_map.insert(path.clone(), selected.len());
+        selected.push((path, id));
     }
     selected
 }
@@ -1031,26 +1748,39 @@ fn path_depth(path: &Path) -> usize {
     path.components().count()
 }
 
-fn has_selected_ancestor(path: &Path, selected: &HashSet<PathBuf>) -> bool {
+/// Position of the selected path covering `path` (itself or an ancestor), if any.
+fn selected_ancestor(path: &Path, selected: &HashMap<PathBuf, usize>) -> Option<usize> {
     if selected.is_empty() {
-        return false;
+        return None;
     }
-    if selected.contains(path) {
-        return true;
+    if let Some(&position) = selected.get(path) {
+        return Some(position);
     }
     let mut ancestor = path.to_path_buf();
     while ancestor.pop() {
-        if selected.contains(&ancestor) {
-            return true;
+        if let Some(&position) = selected.get(&ancestor) {
+            return Some(position);
         }
     }
-    false
+    None
+}
+
+/// What `SearchCache::rescan_with_walk_data` changed, counted in nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RescanSummary {
+    /// Nodes that didn't exist in the slab before.
+    pub added: usize,
+    /// Nodes freed because the walk no longer found them.
+    pub removed: usize,
+    /// Kept nodes whose walked metadata differs from the recorded one.
+    pub modified: usize,
 }
 
 /// Error type for `SearchCache::handle_fs_event`.
 #[derive(Debug)]
 pub enum HandleFSEError {
-    /// Full rescan is required.
+    /// Full rescan is required: the root changed, or events covering the whole
+    /// root were lost. Lost events below the root are rescanned in place.
     Rescan,
 }
 
@@ -1081,30 +1811,13 @@ fn construct_node_slab_name_index(
     index
 }
 
-impl SearchCache {
-    /// ATTENTION: This function doesn't remove existing node, you should remove it
-    /// before creating the new subtree, or the old subtree nodes will be dangling.
-    ///
-    /// ATTENTION1: This function should only called with Node fetched with metadata.
-    fn create_node_slab_update_name_index_and_name_pool(
-        &mut self,
-        parent: Option<SlabIndex>,
-        node: &Node,
-    ) -> SlabIndex {
-        let metadata = match node.metadata {
-            Some(metadata) => SlabNodeMetadataCompact::some(metadata),
-            // This function should only be called with Node fetched with metadata
-            None => SlabNodeMetadataCompact::unaccessible(),
-        };
-        let name = NAME_POOL.push(&node.name);
-        let slab_node = SlabNode::new(parent, name, metadata);
-        let index = self.push_node(slab_node);
-        self.file_nodes[index].children = node
-            .children
-            .iter()
-            .map(|node| self.create_node_slab_update_name_index_and_name_pool(Some(index), node))
-            .collect::<ThinVec<_>>();
-        index
+/// Slab metadata for a walked node. A walk that fetches metadata only lacks it
+/// when fetching failed, which is remembered as unaccessible.
+fn walked_metadata(node: &Node, metadata_fetched: bool) -> SlabNodeMetadataCompact {
+    match node.metadata {
+        Some(metadata) => SlabNodeMetadataCompact::some(metadata),
+        None if metadata_fetched => SlabNodeMetadataCompact::unaccessible(),
+        None => SlabNodeMetadataCompact::none(),
     }
 }
 
@@ -2022,6 +2735,142 @@ mod tests {
         );
     }
 
+    /// Results of `query` restricted to `base` must be exactly the unrestricted
+    /// results that lie in `base`. With `ordered`, they must also come back in
+    /// the same order; filters over a base have always kept the base order.
+    fn assert_base_search_matches_full_search(
+        cache: &mut SearchCache,
+        query: &str,
+        base: &Vec<SlabIndex>,
+        options: SearchOptions,
+        ordered: bool,
+    ) {
+        let members: HashSet<SlabIndex> = base.iter().copied().collect();
+        let mut expected =
+            guard_indices(cache.search_with_options(query, options, CancellationToken::noop()));
+        expected.retain(|index| members.contains(index));
+        let mut restricted = guard_indices(cache.search_with_options_base(
+            query,
+            Some(base),
+            options,
+            CancellationToken::noop(),
+        ));
+        if !ordered {
+            expected.sort_unstable();
+            restricted.sort_unstable();
+        }
+        assert_eq!(restricted, expected, "query {query:?}");
+    }
+
+    /// Single name and regex terms, which a base answers from node names alone.
+    const BASE_NAME_QUERIES: &[&str] = &[
+        "report",
+        "report.md",
+        "REPORT",
+        "\"notes.txt\"",
+        "/Nested",
+        "Docs/",
+        "*.md",
+        "regex:^re.*d$",
+        "ww:report",
+        "nodiacritics:cafe",
+        "missing-name",
+    ];
+
+    fn base_equivalence_queries(root: &Path) -> Vec<String> {
+        let mut queries: Vec<String> = BASE_NAME_QUERIES
+            .iter()
+            .chain(&[
+                "Docs/*.md",
+                "Work/**/nested.md",
+                "ext:md",
+                "report | notes",
+                "md !archive",
+                "!md",
+            ])
+            .map(|query| query.to_string())
+            .collect();
+        queries.push(format!(
+            r#"infolder:"{}""#,
+            root.join("Work/Docs").display()
+        ));
+        queries.push(format!(
+            r#"parent:"{}" md"#,
+            root.join("Work/Docs").display()
+        ));
+        queries
+    }
+
+    #[test]
+    fn scoped_searches_match_full_search_within_scope() {
+        let (_temp_dir, root, _) = build_base_filter_fixture("base_equivalence_scope");
+        // Repeated names inside one scope, plus one only diacritics-folding finds.
+        fs::write(root.join("Work/Docs/Nested/café.md"), b"").unwrap();
+        fs::write(root.join("Work/Docs/Nested/report.md"), b"").unwrap();
+        let mut cache = SearchCache::walk_fs(&root);
+
+        for directory in ["Work/Docs", "Docs", "Nested"] {
+            let scope = guard_indices(cache.search_directory_scope(
+                directory,
+                SearchOptions::default(),
+                CancellationToken::noop(),
+            ));
+            assert!(!scope.is_empty());
+            for query in base_equivalence_queries(&root) {
+                for options in [
+                    SearchOptions::default(),
+                    SearchOptions {
+                        case_insensitive: true,
+                        ..Default::default()
+                    },
+                ] {
+                    assert_base_search_matches_full_search(
+                        &mut cache, &query, &scope, options, false,
+                    );
+                }
+            }
+            for query in BASE_NAME_QUERIES {
+                assert_base_search_matches_full_search(
+                    &mut cache,
+                    query,
+                    &scope,
+                    SearchOptions::default(),
+                    true,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_base_searches_match_full_search_within_base() {
+        let (_temp_dir, root, mut cache) = build_base_filter_fixture("base_equivalence_sparse");
+        let all = guard_indices(cache.search_with_options(
+            "",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        ));
+        // Every other node, so a base need not be a whole subtree.
+        let sparse: Vec<SlabIndex> = all.iter().copied().step_by(2).collect();
+        for query in base_equivalence_queries(&root) {
+            assert_base_search_matches_full_search(
+                &mut cache,
+                &query,
+                &sparse,
+                SearchOptions::default(),
+                false,
+            );
+        }
+        for query in base_equivalence_queries(&root) {
+            assert_base_search_matches_full_search(
+                &mut cache,
+                &query,
+                &Vec::new(),
+                SearchOptions::default(),
+                false,
+            );
+        }
+    }
+
     #[test]
     fn overlapping_directory_scope_matches_do_not_duplicate_results() {
         let temp_dir = TempDir::new("directory_query_overlapping_scopes").unwrap();
@@ -2051,6 +2900,7 @@ mod tests {
             Some("report.md"),
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         );
@@ -2062,6 +2912,7 @@ mod tests {
             Some("report.md"),
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         );
@@ -2787,7 +3638,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemCreated,
         }];
 
@@ -2811,7 +3662,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemCreated,
         }];
 
@@ -2837,7 +3688,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id.saturating_sub(1),
+            id: cache.cursor.event_id().saturating_sub(1),
             flag: EventFlag::ItemCreated,
         }];
 
@@ -2884,6 +3735,7 @@ mod tests {
             "bar !foo",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             token,
         );
@@ -2901,6 +3753,7 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir);
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let indices =
             guard_indices(cache.search_with_options("alpha.txt", opts, CancellationToken::noop()));
@@ -2911,6 +3764,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let miss =
             guard_indices(cache.search_with_options("gamma.txt", opts, CancellationToken::noop()));
@@ -2930,6 +3784,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices =
             guard_indices(cache.search_with_options("alpha*.md", opts, CancellationToken::noop()));
@@ -2939,6 +3794,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let indices =
             guard_indices(cache.search_with_options("alpha*.md", opts, CancellationToken::noop()));
@@ -2959,6 +3815,7 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir);
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices = guard_indices(cache.search_with_options(
             "content:memchr",
@@ -2971,6 +3828,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let insensitive = guard_indices(cache.search_with_options(
             "content:MEMCHR",
@@ -2995,6 +3853,7 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir);
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices = guard_indices(cache.search_with_options(
             "content:XYZ",
@@ -3020,6 +3879,7 @@ mod tests {
             "content:a",
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -3029,6 +3889,7 @@ mod tests {
             "content:a",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -3039,6 +3900,7 @@ mod tests {
             "content:A",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -3049,6 +3911,7 @@ mod tests {
             "content:z",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -3073,6 +3936,7 @@ mod tests {
             "content:XYZ",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -3103,6 +3967,7 @@ mod tests {
             &query,
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -3140,6 +4005,7 @@ mod tests {
             "file_a",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             token,
         );
@@ -3227,6 +4093,7 @@ mod tests {
                 "B\u{00FC}ro",
                 SearchOptions {
                     case_insensitive: false,
+                    ..Default::default()
                 },
                 CancellationToken::noop(),
             )
@@ -3277,7 +4144,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemRemoved,
         }];
 
@@ -3295,7 +4162,7 @@ mod tests {
         let instant = std::time::Instant::now();
         let root = Path::new("/Library/Developer/CoreSimulator");
         let mut cache = SearchCache::walk_fs(root);
-        let mut event_id = cache.last_event_id + 1;
+        let mut event_id = cache.cursor.event_id() + 1;
         println!(
             "Cache size: {}, process time: {:?}",
             cache.file_nodes.len(),
@@ -3314,7 +4181,7 @@ mod tests {
             event_id += 1;
             println!(
                 "Event id: {}, process time: {:?}",
-                cache.last_event_id,
+                cache.cursor.event_id(),
                 instant.elapsed()
             );
         }
@@ -3333,7 +4200,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemRemoved,
         }];
 
@@ -3359,12 +4226,12 @@ mod tests {
         let mock_events = vec![
             FsEvent {
                 path: temp_path.join("new_file.txt"),
-                id: cache.last_event_id + 1,
+                id: cache.cursor.event_id() + 1,
                 flag: EventFlag::ItemCreated,
             },
             FsEvent {
                 path: temp_path.join("new_file.txt"),
-                id: cache.last_event_id + 1,
+                id: cache.cursor.event_id() + 1,
                 flag: EventFlag::ItemRemoved,
             },
         ];
@@ -3393,7 +4260,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.to_path_buf(),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::RootChanged,
         }];
 
@@ -3423,7 +4290,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.to_path_buf(),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::RootChanged,
         }];
 
@@ -3435,7 +4302,7 @@ mod tests {
     }
 
     #[test]
-    fn test_handle_fs_event_rescan_by_modify() {
+    fn test_handle_fs_event_root_modify_rescans_root_entries() {
         let temp_dir = TempDir::new("test_events").expect("Failed to create temp directory");
         let temp_path = temp_dir.path();
         let mut cache = SearchCache::walk_fs(temp_dir.path());
@@ -3451,14 +4318,18 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.to_path_buf(),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemModified,
         }];
 
-        cache.handle_fs_events(mock_events).unwrap_err();
+        let rescan_count = cache.rescan_count();
+        cache.handle_fs_events(mock_events).unwrap();
 
-        assert_eq!(cache.file_nodes.len(), 1 + depth(temp_path));
-        assert_eq!(cache.name_index.len(), 1 + depth(temp_path));
+        // Only the root's direct entries are reconciled, no full rescan.
+        assert_eq!(cache.rescan_count(), rescan_count);
+        assert_eq!(cache.file_nodes.len(), 7 + depth(temp_path));
+        assert_eq!(cache.name_index.len(), 7 + depth(temp_path));
+        assert_eq!(cache.search("good.rs").unwrap().len(), 1);
     }
 
     #[test]
@@ -3489,7 +4360,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("src"),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemRemoved | EventFlag::ItemIsDir,
         }];
 
@@ -3532,7 +4403,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("src/foo"),
-            id: cache.last_event_id + 1,
+            id: cache.cursor.event_id() + 1,
             flag: EventFlag::ItemRemoved | EventFlag::ItemIsDir,
         }];
 
@@ -3609,7 +4480,7 @@ mod tests {
         fs::File::create(root_path.join("subdir1/file2.txt")).expect("Failed to create file1.txt");
 
         let mut cache = SearchCache::walk_fs(root_path);
-        let mut last_event_id = cache.last_event_id();
+        let mut last_event_id = cache.resume_cursor().event_id();
 
         let new_file_path = root_path.join("event_file.txt");
         fs::write(&new_file_path, b"heck").expect("Failed to create event_file.txt");
@@ -4146,7 +5017,7 @@ mod tests {
         // Simulate an event for a new file
         let event_file_path = root_path.join("event_added_file.txt");
         fs::write(&event_file_path, "content123").unwrap(); // content of size 10
-        let last_event_id = cache.last_event_id();
+        let last_event_id = cache.resume_cursor().event_id();
         let event = FsEvent {
             path: event_file_path.clone(),
             id: last_event_id + 1,
@@ -4168,7 +5039,7 @@ mod tests {
         let file_in_event_dir_path = event_dir_path.join("inner_event.dat");
         fs::write(&file_in_event_dir_path, "data").unwrap(); // content of size 4
 
-        let last_event_id_2 = cache.last_event_id();
+        let last_event_id_2 = cache.resume_cursor().event_id();
         let event_dir = FsEvent {
             path: event_dir_path.clone(), // Event is for the directory
             id: last_event_id_2 + 1,
@@ -4193,6 +5064,13 @@ mod tests {
     }
 
     // --- scan_paths focused tests ---
+    fn scanned_paths(events: Vec<FsEvent>) -> Vec<PathBuf> {
+        scan_paths(events)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
     #[test]
     fn test_scan_paths_empty() {
         assert!(scan_paths(vec![]).is_empty());
@@ -4241,7 +5119,7 @@ mod tests {
                 flag: EventFlag::ItemRemoved | EventFlag::ItemIsFile,
             },
         ];
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         assert_eq!(out.len(), 1);
         assert_eq!(out[0], PathBuf::from("/tmp/a/b"));
     }
@@ -4265,7 +5143,7 @@ mod tests {
                 flag: EventFlag::ItemModified | EventFlag::ItemIsDir,
             },
         ];
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         // Expect the ancestor /t/a to absorb the whole subtree.
         assert_eq!(out, vec![PathBuf::from("/t/a")]);
     }
@@ -4289,7 +5167,7 @@ mod tests {
                 flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
             },
         ];
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         assert_eq!(out, vec![PathBuf::from("/t/a")]);
     }
 
@@ -4312,7 +5190,7 @@ mod tests {
                 flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
             },
         ];
-        let mut out = scan_paths(events);
+        let mut out = scanned_paths(events);
         out.sort();
         assert_eq!(
             out,
@@ -4338,7 +5216,7 @@ mod tests {
                 flag: EventFlag::ItemCreated | EventFlag::ItemIsDir,
             },
         ];
-        let mut out = scan_paths(events);
+        let mut out = scanned_paths(events);
         out.sort();
         assert_eq!(
             out,
@@ -4361,7 +5239,7 @@ mod tests {
                 flag: EventFlag::ItemCreated | EventFlag::ItemIsDir,
             },
         ];
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         assert_eq!(out, vec![PathBuf::from("/mix/dir/sub")]);
     }
 
@@ -4389,7 +5267,7 @@ mod tests {
                 flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
             },
         ];
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         assert_eq!(
             out,
             vec![
@@ -4414,7 +5292,7 @@ mod tests {
                 flag: EventFlag::ItemCreated | EventFlag::ItemIsDir,
             },
         ];
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         assert_eq!(out, vec![PathBuf::from("/")]);
     }
 
@@ -4437,7 +5315,37 @@ mod tests {
             id: 99,
             flag: EventFlag::ItemModified | EventFlag::ItemIsDir,
         });
-        let out = scan_paths(events);
+        let out = scanned_paths(events);
         assert_eq!(out, vec![PathBuf::from("/long")]);
     }
+
+    #[test]
+    fn test_scan_paths_carries_latest_covered_event_id() {
+        let events = vec![
+            FsEvent {
+                path: PathBuf::from("/t/a/b"),
+                id: 7,
+                flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+            },
+            FsEvent {
+                path: PathBuf::from("/t/a"),
+                id: 3,
+                flag: EventFlag::ItemModified | EventFlag::ItemIsDir,
+            },
+            FsEvent {
+                path: PathBuf::from("/t/a"),
+                id: 5,
+                flag: EventFlag::ItemModified | EventFlag::ItemIsDir,
+            },
+            FsEvent {
+                path: PathBuf::from("/t/c"),
+                id: 4,
+                flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+            },
+        ];
+        assert_eq!(
+            scan_paths(events),
+            vec![(PathBuf::from("/t/a"), 7), (PathBuf::from("/t/c"), 4)]
+        );
+    }
 }
@@ -0,0 +1,183 @@
+//! Unicode simple case folding, the same equivalence the `regex` crate uses
+//! for `(?i)`. Name matching goes through case-insensitive regexes, so every
+//! other case-insensitive comparison (path segments, content, tags) folds
+//! with these helpers to agree with it.
+
+/// Characters whose simple case folding differs from their lowercase
+/// mapping.
+const FOLD_EXCEPTIONS: &[(char, char)] = &[
+    ('\u{00B5}', '\u{03BC}'), // µ → μ
+    ('\u{017F}', 's'),        // ſ → s
+    ('\u{0345}', '\u{03B9}'), // combining ypogegrammeni → ι
+    ('\u{03C2}', '\u{03C3}'), // ς → σ
+    ('\u{03D0}', '\u{03B2}'), // ϐ → β
+    ('\u{03D1}', '\u{03B8}'), // ϑ → θ
+    ('\u{03D5}', '\u{03C6}'), // ϕ → φ
+    ('\u{03D6}', '\u{03C0}'), // ϖ → π
+    ('\u{03F0}', '\u{03BA}'), // ϰ → κ
+    ('\u{03F1}', '\u{03C1}'), // ϱ → ρ
+    ('\u{03F5}', '\u{03B5}'), // ϵ → ε
+    ('\u{1C80}', '\u{0432}'), // rounded ve → в
+    ('\u{1C81}', '\u{0434}'), // long-legged de → д
+    ('\u{1C82}', '\u{043E}'), // narrow o → о
+    ('\u{1C83}', '\u{0441}'), // wide es → с
+    ('\u{1C84}', '\u{0442}'), // tall te → т
+    ('\u{1C85}', '\u{0442}'), // three-legged te → т
+    ('\u{1C86}', '\u{044A}'), // tall hard sign → ъ
+    ('\u{1C87}', '\u{0463}'), // tall yat → ѣ
+    ('\u{1C88}', '\u{A64B}'), // unblended uk → ꙋ
+    ('\u{1E9B}', '\u{1E61}'), // ẛ → ṡ
+    ('\u{1FBE}', '\u{03B9}'), // prosgegrammeni → ι
+    ('\u{1FD3}', '\u{0390}'), // ΐ (compatibility) → ΐ
+    ('\u{1FE3}', '\u{03B0}'), // ΰ (compatibility) → ΰ
+    ('\u{FB05}', '\u{FB06}'), // ﬅ → ﬆ
+];
+
+/// Folds one character. Characters without a single-character folding,
+/// such as `İ` (only Turkic or full folding applies) or `ß` (full folding
+/// gives `ss`), are kept as they are.
+pub(crate) fn fold_char(ch: char) -> char {
+    if ch.is_ascii() {
+        return ch.to_ascii_lowercase();
+    }
+    if let Ok(index) = FOLD_EXCEPTIONS.binary_search_by_key(&ch, |&(from, _)| from) {
+        return FOLD_EXCEPTIONS[index].1;
+    }
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => ch,
+    }
+}
+
+pub(crate) fn fold_case(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_ascii_lowercase();
+    }
+    text.chars().map(fold_char).collect()
+}
+
+pub(crate) fn eq_ignore_case(left: &str, right: &str) -> bool {
+    left.chars().map(fold_char).eq(right.chars().map(fold_char))
+}
+
+/// Appends the folded form of `bytes` to `out` for streaming content search.
+/// Invalid UTF-8 is copied through unchanged, while an incomplete sequence
+/// at the end is left for the next call. Returns how many bytes were used.
+pub(crate) fn fold_utf8(mut bytes: &[u8], out: &mut Vec<u8>) -> usize {
+    let len = bytes.len();
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                push_folded(text, out);
+                return len;
+            }
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                push_folded(std::str::from_utf8(valid).expect("validated prefix"), out);
+                let Some(invalid_len) = err.error_len() else {
+                    return len - rest.len();
+                };
+                out.extend_from_slice(&rest[..invalid_len]);
+                bytes = &rest[invalid_len..];
+            }
+        }
+    }
+}
+
+fn push_folded(text: &str, out: &mut Vec<u8>) {
+    if text.is_ascii() {
+        out.extend(text.bytes().map(|byte| byte.to_ascii_lowercase()));
+        return;
+    }
+    let mut buf = [0u8; 4];
+    for ch in text.chars() {
+        out.extend_from_slice(fold_char(ch).encode_utf8(&mut buf).as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::RegexBuilder;
+
+    fn case_insensitive_regex(ch: char) -> regex::Regex {
+        RegexBuilder::new(&format!("^{}$", regex::escape(&ch.to_string())))
+            .case_insensitive(true)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn exceptions_are_sorted() {
+        assert!(FOLD_EXCEPTIONS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn folding_agrees_with_regex_case_insensitivity() {
+        let ranges = [
+            '\0'..='\u{058F}',
+            '\u{10A0}'..='\u{10FF}',
+            '\u{13A0}'..='\u{13FF}',
+            '\u{1C80}'..='\u{1C88}',
+            '\u{1C90}'..='\u{1CBF}',
+            '\u{1E00}'..='\u{1FFF}',
+            '\u{2126}'..='\u{2184}',
+            '\u{2C00}'..='\u{2D2F}',
+            '\u{A640}'..='\u{A7AF}',
+            '\u{AB70}'..='\u{ABBF}',
+            '\u{FB00}'..='\u{FB06}',
+            '\u{FF21}'..='\u{FF5A}',
+            '\u{10400}'..='\u{1044F}',
+        ];
+        for ch in ranges.into_iter().flatten() {
+            let mut others: Vec<char> = ch.to_uppercase().chain(ch.to_lowercase()).collect();
+            others.retain(|&other| other != ch);
+            if others.is_empty() {
+                // Uncased characters fold to themselves.
+                assert_eq!(fold_char(ch), ch);
+                continue;
+            }
+            let regex = case_insensitive_regex(ch);
+            let folded = fold_char(ch);
+            assert!(
+                regex.is_match(&folded.to_string()),
+                "{ch:?} folds to {folded:?}"
+            );
+            for other in others {
+                assert_eq!(
+                    regex.is_match(&other.to_string()),
+                    folded == fold_char(other),
+                    "{ch:?} vs {other:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn turkish_i_and_sharp_s_only_fold_simply() {
+        assert!(eq_ignore_case("I", "i"));
+        assert!(!eq_ignore_case("ı", "i"));
+        assert!(!eq_ignore_case("ı", "I"));
+        assert!(!eq_ignore_case("İ", "i"));
+        assert!(eq_ignore_case("STRAẞE", "straße"));
+        assert!(!eq_ignore_case("STRASSE", "straße"));
+        assert!(eq_ignore_case("ÄRGER", "ärger"));
+        assert!(eq_ignore_case("ΟΔΥΣΣΕΥΣ", "οδυσσευς"));
+        assert!(!eq_ignore_case("ärger", "ärge"));
+    }
+
+    #[test]
+    fn fold_utf8_keeps_invalid_bytes_and_defers_split_chars() {
+        let mut out = Vec::new();
+        assert_eq!(fold_utf8(b"AB\xFFC", &mut out), 4);
+        assert_eq!(out, b"ab\xFFc");
+
+        let text = "xÄ".as_bytes();
+        let mut out = Vec::new();
+        assert_eq!(fold_utf8(&text[..2], &mut out), 1);
+        assert_eq!(out, b"x");
+        assert_eq!(fold_utf8(&text[1..], &mut out), 2);
+        assert_eq!(out, "xä".as_bytes());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::SlabIndex;
+use cardinal_sdk::EventId;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use std::path::PathBuf;
+
+/// What happened to a node of the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexChangeKind {
+    Added,
+    Removed,
+    MetadataChanged,
+    /// The node was renamed or moved in place and kept its `SlabIndex`.
+    Moved {
+        from: PathBuf,
+    },
+}
+
+/// One change applied to the index, as delivered to subscribers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexChange {
+    pub kind: IndexChangeKind,
+    pub index: SlabIndex,
+    /// Path of the node; for removals, the path it had before being removed.
+    pub path: PathBuf,
+    /// Id of the event that caused the change. Rescans use the event id they
+    /// were started at.
+    pub event_id: EventId,
+}
+
+/// Collects index changes while a batch is applied and fans them out to
+/// subscribers once the batch is done.
+///
+/// Nothing is recorded while nobody is subscribed, either here or through a
+/// live query.
+#[derive(Default)]
+pub(crate) struct ChangeFeed {
+    subscribers: Vec<Sender<Vec<IndexChange>>>,
+    pending: Vec<IndexChange>,
+    event_id: EventId,
+}
+
+impl ChangeFeed {
+    pub(crate) fn subscribe(&mut self) -> Receiver<Vec<IndexChange>> {
+        let (sender, receiver) = unbounded();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        !self.subscribers.is_empty()
+    }
+
+    /// Attribute the changes recorded from now on to `event_id`.
+    pub(crate) fn set_event_id(&mut self, event_id: EventId) {
+        self.event_id = event_id;
+    }
+
+    pub(crate) fn record(&mut self, kind: IndexChangeKind, index: SlabIndex, path: PathBuf) {
+        self.pending.push(IndexChange {
+            kind,
+            index,
+            path,
+            event_id: self.event_id,
+        });
+    }
+
+    /// Take the changes recorded since the last batch.
+    pub(crate) fn take_pending(&mut self) -> Vec<IndexChange> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Send `changes` as one batch, dropping subscribers that hung up.
+    pub(crate) fn send(&mut self, changes: &[IndexChange]) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(changes.to_vec()).is_ok());
+    }
+}
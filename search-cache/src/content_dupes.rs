@@ -0,0 +1,263 @@
+use crate::{SearchCache, SlabIndex};
+use fswalk::NodeFileType;
+use hashbrown::HashMap;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use search_cancel::CancellationToken;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    hash::Hash,
+    io::{Read, Seek, SeekFrom},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Bytes hashed from each end of a file before committing to a full read.
+const EDGE_BYTES: u64 = 64 * 1024;
+const HASH_BUFFER_BYTES: usize = 256 * 1024;
+
+/// Content hashes of files, keyed by path and only trusted while the size
+/// and mtime recorded next to them still match the file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContentHashCache {
+    entries: HashMap<PathBuf, ContentHashEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct ContentHashEntry {
+    size: u64,
+    mtime: u32,
+    /// Hash of the first and last `EDGE_BYTES`.
+    edges: Option<u128>,
+    full: Option<u128>,
+}
+
+impl ContentHashCache {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops the entries whose path is rejected by `keep`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        self.entries.retain(|path, _| keep(path));
+    }
+
+    fn get(&self, candidate: &Candidate, stage: HashStage) -> Option<u128> {
+        let entry = self.entries.get(&candidate.path)?;
+        if entry.size != candidate.size || entry.mtime != candidate.mtime {
+            return None;
+        }
+        match stage {
+            HashStage::Edges => entry.edges,
+            HashStage::Full => entry.full,
+        }
+    }
+
+    fn insert(&mut self, candidate: &Candidate, stage: HashStage, hash: u128) {
+        let entry = self
+            .entries
+            .entry_ref(&candidate.path)
+            .or_insert(ContentHashEntry {
+                size: candidate.size,
+                mtime: candidate.mtime,
+                edges: None,
+                full: None,
+            });
+        if entry.size != candidate.size || entry.mtime != candidate.mtime {
+            *entry = ContentHashEntry {
+                size: candidate.size,
+                mtime: candidate.mtime,
+                edges: None,
+                full: None,
+            };
+        }
+        match stage {
+            HashStage::Edges => {
+                entry.edges = Some(hash);
+                // Short files are read whole for the edge hash.
+                if candidate.size <= 2 * EDGE_BYTES {
+                    entry.full = Some(hash);
+                }
+            }
+            HashStage::Full => entry.full = Some(hash),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashStage {
+    Edges,
+    Full,
+}
+
+struct Candidate {
+    /// Position in the input, so groups keep the order of their first member.
+    order: usize,
+    index: SlabIndex,
+    path: PathBuf,
+    size: u64,
+    mtime: u32,
+}
+
+impl SearchCache {
+    /// Groups the regular files among `nodes` whose contents are byte-identical.
+    ///
+    /// Files are narrowed down by size, then by a hash of their first and
+    /// last 64 KiB, and only the survivors are hashed in full. Hashes are
+    /// kept in the cache keyed by (path, size, mtime) and persisted with it,
+    /// so asking again after a flush rereads only the files that changed.
+    /// Groups are ordered by their first member in `nodes`; returns `None`
+    /// when cancelled.
+    pub fn content_duplicates(
+        &mut self,
+        nodes: &[SlabIndex],
+        token: CancellationToken,
+    ) -> Option<Vec<Vec<SlabIndex>>> {
+        let mut candidates = Vec::new();
+        for (order, &index) in nodes.iter().enumerate() {
+            token.is_cancelled_sparse(order)?;
+            if self.node_file_type(index) != NodeFileType::File {
+                continue;
+            }
+            let metadata = self.ensure_metadata(index);
+            let Some(metadata) = metadata.as_ref() else {
+                continue;
+            };
+            let Some(path) = self.node_path(index) else {
+                continue;
+            };
+            candidates.push(Candidate {
+                order,
+                index,
+                path,
+                size: metadata.size().max(0) as u64,
+                mtime: metadata.mtime().map_or(0, NonZeroU32::get),
+            });
+        }
+
+        let groups = split_groups(vec![candidates], |candidate| Some(candidate.size));
+        let groups = self.split_by_hash(groups, HashStage::Edges, token)?;
+        let mut groups = self.split_by_hash(groups, HashStage::Full, token)?;
+        groups.sort_by_key(|group| group[0].order);
+        Some(
+            groups
+                .into_iter()
+                .map(|group| group.into_iter().map(|candidate| candidate.index).collect())
+                .collect(),
+        )
+    }
+
+    fn split_by_hash(
+        &mut self,
+        groups: Vec<Vec<Candidate>>,
+        stage: HashStage,
+        token: CancellationToken,
+    ) -> Option<Vec<Vec<Candidate>>> {
+        // The edge hash of a short file already covers all of it.
+        let (verified, groups): (Vec<_>, Vec<_>) = groups
+            .into_iter()
+            .partition(|group| stage == HashStage::Full && group[0].size <= 2 * EDGE_BYTES);
+
+        let mut hashes: Vec<Option<u128>> = groups
+            .iter()
+            .flatten()
+            .map(|candidate| self.content_hashes.get(candidate, stage))
+            .collect();
+        let misses: Vec<(usize, &Candidate)> = groups
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(i, _)| hashes[*i].is_none())
+            .collect();
+        let computed: Vec<(usize, Option<u128>)> = misses
+            .par_iter()
+            .map(|&(i, candidate)| (i, hash_file(candidate, stage, token)))
+            .collect();
+        token.is_cancelled()?;
+        for (i, hash) in computed {
+            hashes[i] = hash;
+        }
+
+        let mut hashes = hashes.into_iter();
+        let groups: Vec<Vec<(Candidate, Option<u128>)>> = groups
+            .into_iter()
+            .map(|group| group.into_iter().zip(hashes.by_ref()).collect())
+            .collect();
+        for (candidate, hash) in groups.iter().flatten() {
+            if let Some(hash) = hash {
+                self.content_hashes.insert(candidate, stage, *hash);
+            }
+        }
+
+        let mut result = verified;
+        for group in groups {
+            let split = split_groups(vec![group], |(_, hash)| *hash);
+            result.extend(
+                split
+                    .into_iter()
+                    .map(|group| group.into_iter().map(|(candidate, _)| candidate).collect()),
+            );
+        }
+        Some(result)
+    }
+}
+
+/// Splits every group by `key`, keeping sub-groups of at least two members in
+/// first-seen order. Items without a key are dropped.
+fn split_groups<T, K: Hash + Eq>(
+    groups: Vec<Vec<T>>,
+    mut key: impl FnMut(&T) -> Option<K>,
+) -> Vec<Vec<T>> {
+    let mut result = Vec::new();
+    for group in groups {
+        let mut bucket_of: HashMap<K, usize> = HashMap::new();
+        let mut buckets: Vec<Vec<T>> = Vec::new();
+        for item in group {
+            let Some(key) = key(&item) else {
+                continue;
+            };
+            let bucket = *bucket_of.entry(key).or_insert_with(|| {
+                buckets.push(Vec::new());
+                buckets.len() - 1
+            });
+            buckets[bucket].push(item);
+        }
+        result.extend(buckets.into_iter().filter(|bucket| bucket.len() > 1));
+    }
+    result
+}
+
+/// `None` when the file can't be read as recorded or the token is cancelled.
+fn hash_file(candidate: &Candidate, stage: HashStage, token: CancellationToken) -> Option<u128> {
+    token.is_cancelled()?;
+    let mut file = File::open(&candidate.path).ok()?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_BYTES];
+    if stage == HashStage::Edges && candidate.size > 2 * EDGE_BYTES {
+        let edge = &mut buffer[..EDGE_BYTES as usize];
+        file.read_exact(edge).ok()?;
+        hasher.update(edge);
+        file.seek(SeekFrom::End(-(EDGE_BYTES as i64))).ok()?;
+        file.read_exact(edge).ok()?;
+        hasher.update(edge);
+        return Some(hasher.digest128());
+    }
+    let mut total = 0u64;
+    loop {
+        token.is_cancelled()?;
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(_) => return None,
+        };
+        hasher.update(&buffer[..read]);
+        total += read as u64;
+    }
+    // A file that changed size since it was indexed can't be compared.
+    (total == candidate.size).then(|| hasher.digest128())
+}
@@ -0,0 +1,43 @@
+use std::borrow::Cow;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+/// Drops accents and other combining marks after NFD decomposition, so
+/// `Café` and `Cafe\u{301}` both read `Cafe`. The result is recomposed to NFC,
+/// which keeps scripts such as Hangul whole. Letters without a decomposition,
+/// like `ø` or `đ`, are kept.
+pub(crate) fn strip_diacritics(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let stripped: String = text
+        .nfd()
+        .filter(|&ch| !is_combining_mark(ch))
+        .nfc()
+        .collect();
+    if stripped == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(stripped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_marks_in_either_normalization_form() {
+        assert_eq!(strip_diacritics("Café"), "Cafe");
+        assert_eq!(strip_diacritics("Cafe\u{301}"), "Cafe");
+        assert_eq!(strip_diacritics("Tiếng Việt"), "Tieng Viet");
+        assert_eq!(strip_diacritics("Über"), "Uber");
+    }
+
+    #[test]
+    fn leaves_other_text_borrowed() {
+        assert!(matches!(strip_diacritics("report"), Cow::Borrowed(_)));
+        assert!(matches!(strip_diacritics("ørsted"), Cow::Borrowed(_)));
+        assert!(matches!(strip_diacritics("한국어"), Cow::Borrowed(_)));
+        assert_eq!(strip_diacritics("đường"), "đuong");
+    }
+}
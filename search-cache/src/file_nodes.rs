@@ -62,6 +62,17 @@ impl FileNodes {
         &self.include_paths
     }
 
+    pub(crate) fn set_scope(
+        &mut self,
+        path: PathBuf,
+        ignore_paths: Vec<PathBuf>,
+        include_paths: Vec<PathBuf>,
+    ) {
+        self.path = path;
+        self.ignore_paths = ignore_paths;
+        self.include_paths = include_paths;
+    }
+
     pub(crate) fn take_slab(&mut self) -> ThinSlab<SlabNode> {
         std::mem::take(&mut self.slab)
     }
@@ -0,0 +1,186 @@
+use crate::{SearchCache, SlabIndex};
+use fswalk::NodeFileType;
+use hashbrown::HashMap;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use search_cancel::CancellationToken;
+use std::{
+    io::{Read, Seek, SeekFrom},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+/// Facts read from inside files, like audio tags or image headers, trusted
+/// while the file's mtime is unchanged. Kept in memory only.
+#[derive(Debug)]
+pub(crate) struct ProbeCache<T> {
+    entries: HashMap<PathBuf, ProbeEntry<T>>,
+}
+
+impl<T> Default for ProbeCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ProbeEntry<T> {
+    mtime: u32,
+    /// `None` for files the probe couldn't make sense of.
+    value: Option<T>,
+}
+
+impl<T> ProbeCache<T> {
+    fn get(&self, path: &Path, mtime: u32) -> Option<Option<&T>> {
+        let entry = self.entries.get(path)?;
+        (entry.mtime == mtime).then_some(entry.value.as_ref())
+    }
+}
+
+/// A reader for one kind of embedded data, such as `read_audio_tags`.
+pub(crate) struct Probe<T> {
+    /// Only files with these extensions are opened.
+    pub extensions: &'static [&'static str],
+    pub read: fn(&Path) -> Option<T>,
+    pub cache: fn(&mut SearchCache) -> &mut ProbeCache<T>,
+}
+
+impl SearchCache {
+    /// Keeps the files among `nodes` whose probed value satisfies `predicate`.
+    /// Files are read in parallel, and only when the cache has nothing for
+    /// their current mtime.
+    pub(crate) fn probe_nodes<T: Send + Sync>(
+        &mut self,
+        nodes: Vec<SlabIndex>,
+        probe: Probe<T>,
+        predicate: impl Fn(&T) -> bool + Sync,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let mut candidates = Vec::new();
+        for (i, index) in nodes.into_iter().enumerate() {
+            token.is_cancelled_sparse(i)?;
+            let node = &self.file_nodes[index];
+            if node.file_type_hint() != NodeFileType::File
+                || !has_extension(node.name(), probe.extensions)
+            {
+                continue;
+            }
+            let metadata = self.ensure_metadata(index);
+            let Some(metadata) = metadata.as_ref() else {
+                continue;
+            };
+            let Some(path) = self.node_path(index) else {
+                continue;
+            };
+            let mtime = metadata.mtime().map_or(0, NonZeroU32::get);
+            candidates.push((index, path, mtime));
+        }
+
+        let cache = (probe.cache)(self);
+        let results: Vec<_> = candidates
+            .into_par_iter()
+            .map(|(index, path, mtime)| {
+                token.is_cancelled()?;
+                if let Some(value) = cache.get(&path, mtime) {
+                    return Some((index, value.is_some_and(&predicate), None));
+                }
+                let value = (probe.read)(&path);
+                let matched = value.as_ref().is_some_and(&predicate);
+                Some((index, matched, Some((path, ProbeEntry { mtime, value }))))
+            })
+            .collect::<Option<_>>()?;
+
+        let mut matched = Vec::new();
+        for (index, is_match, entry) in results {
+            if let Some((path, entry)) = entry {
+                cache.entries.insert(path, entry);
+            }
+            if is_match {
+                matched.push(index);
+            }
+        }
+        Some(matched)
+    }
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, ext)| {
+        extensions
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
+
+pub(crate) fn be_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | u64::from(byte))
+}
+
+/// Returns the body of the first top-level ISO base media box of `kind` at or
+/// after `offset`, or `None` when it is missing or larger than `max_len`.
+/// MP4 and HEIF files are both laid out this way.
+pub(crate) fn read_top_level_atom(
+    file: &mut (impl Read + Seek),
+    offset: u64,
+    kind: &[u8; 4],
+    max_len: u64,
+) -> Option<Vec<u8>> {
+    let file_len = file.seek(SeekFrom::End(0)).ok()?;
+    let mut pos = offset;
+    while pos + 8 <= file_len {
+        let mut header = [0u8; 16];
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        file.read_exact(&mut header[..8]).ok()?;
+        let (header_len, size) = match be_uint(&header[..4]) {
+            0 => (8, file_len - pos),
+            1 => {
+                file.read_exact(&mut header[8..]).ok()?;
+                (16, be_uint(&header[8..]))
+            }
+            size => (8, size),
+        };
+        // `pos + 8 <= file_len`, so the subtraction can't underflow. A box
+        // running past the end would otherwise let a 64-bit size wrap `pos`.
+        if size < header_len || size > file_len - pos {
+            return None;
+        }
+        if &header[4..8] == kind {
+            let body_len = size - header_len;
+            if body_len > max_len {
+                return None;
+            }
+            let mut body = vec![0u8; body_len as usize];
+            file.read_exact(&mut body).ok()?;
+            return Some(body);
+        }
+        pos = pos.checked_add(size)?;
+    }
+    None
+}
+
+pub(crate) fn find_atom<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    mp4_atoms(data)
+        .into_iter()
+        .find_map(|(found, body)| (&found == kind).then_some(body))
+}
+
+/// Splits `data` into its child atoms, stopping at the first malformed one.
+pub(crate) fn mp4_atoms(mut data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut atoms = Vec::new();
+    while data.len() >= 8 {
+        let (header_len, size) = match be_uint(&data[..4]) {
+            0 => (8, data.len()),
+            1 if data.len() >= 16 => (16, be_uint(&data[8..16]) as usize),
+            size => (8, size as usize),
+        };
+        if size < header_len || size > data.len() {
+            break;
+        }
+        let kind = data[4..8].try_into().expect("four bytes");
+        atoms.push((kind, &data[header_len..size]));
+        data = &data[size..];
+    }
+    atoms
+}
@@ -19,7 +19,7 @@ impl HighlightCollector {
         match expr {
             Expr::Empty => {}
             Expr::Term(term) => self.collect_term(term),
-            Expr::Not(inner) => self.collect_expr(inner),
+            Expr::Not(inner) | Expr::Modified(_, inner) => self.collect_expr(inner),
             Expr::And(parts) | Expr::Or(parts) => {
                 for part in parts {
                     self.collect_expr(part);
@@ -0,0 +1,411 @@
+use crate::{
+    SearchCache, SlabIndex,
+    file_probe::{Probe, be_uint, find_atom, mp4_atoms, read_top_level_atom},
+};
+use search_cancel::CancellationToken;
+use std::{
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// Files worth opening for the image dimension filters.
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "jpe", "jfif", "gif", "bmp", "dib", "webp", "tif", "tiff", "heic",
+    "heif", "hif", "avif",
+];
+/// HEIF keeps its item properties in `meta`, next to the item locations.
+const MAX_META_BYTES: u64 = 4 * 1024 * 1024;
+/// A TIFF directory with more entries than this is treated as corrupt.
+const MAX_IFD_ENTRIES: u16 = 1024;
+const HEIF_BRANDS: &[&[u8; 4]] = &[
+    b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"hevm", b"hevs", b"mif1", b"msf1",
+    b"avif", b"avis",
+];
+
+/// What `width:`, `height:`, `dimensions:`, `orientation:` and `bitdepth:`
+/// look at. Dimensions are as displayed, after the EXIF, TIFF or HEIF
+/// rotation is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Bits per pixel, summed over the channels.
+    pub bit_depth: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Orientation {
+    Landscape,
+    Portrait,
+    Square,
+}
+
+impl ImageInfo {
+    /// `quarter_turn` swaps the stored dimensions for images shown rotated
+    /// by 90 or 270 degrees.
+    fn new(width: u32, height: u32, bit_depth: Option<u32>, quarter_turn: bool) -> Option<Self> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let (width, height) = if quarter_turn {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Some(Self {
+            width,
+            height,
+            bit_depth: bit_depth.filter(|&bits| bits > 0),
+        })
+    }
+
+    pub(crate) fn orientation(&self) -> Orientation {
+        match self.width.cmp(&self.height) {
+            std::cmp::Ordering::Greater => Orientation::Landscape,
+            std::cmp::Ordering::Less => Orientation::Portrait,
+            std::cmp::Ordering::Equal => Orientation::Square,
+        }
+    }
+}
+
+impl SearchCache {
+    /// Keeps the images among `nodes` whose header satisfies `predicate`.
+    pub(crate) fn nodes_with_image_info(
+        &mut self,
+        nodes: Vec<SlabIndex>,
+        predicate: impl Fn(&ImageInfo) -> bool + Sync,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let probe = Probe {
+            extensions: IMAGE_EXTENSIONS,
+            read: read_image_info,
+            cache: |cache| &mut cache.image_info,
+        };
+        self.probe_nodes(nodes, probe, predicate, token)
+    }
+}
+
+/// Reads the dimensions of a PNG, JPEG, GIF, BMP, WebP, TIFF or HEIF/AVIF
+/// image from its header, recognized by content rather than extension.
+pub(crate) fn read_image_info(path: &Path) -> Option<ImageInfo> {
+    let mut file = BufReader::new(File::open(path).ok()?);
+    let mut head = Vec::with_capacity(32);
+    file.by_ref().take(32).read_to_end(&mut head).ok()?;
+    if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+        read_png(&head)
+    } else if head.starts_with(b"\xff\xd8\xff") {
+        read_jpeg(&mut file)
+    } else if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+        read_gif(&head)
+    } else if head.starts_with(b"BM") {
+        read_bmp(&head)
+    } else if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+        read_webp(&head)
+    } else if head.starts_with(b"II*\0") || head.starts_with(b"MM\0*") {
+        let tiff = read_tiff(&mut file, 0)?;
+        ImageInfo::new(
+            tiff.width?,
+            tiff.height?,
+            tiff.bit_depth(),
+            tiff.quarter_turn(),
+        )
+    } else if head.get(4..8) == Some(b"ftyp")
+        && HEIF_BRANDS
+            .iter()
+            .any(|brand| head.get(8..12) == Some(*brand))
+    {
+        read_heif(&mut file)
+    } else {
+        None
+    }
+}
+
+fn be_u16(bytes: &[u8]) -> u32 {
+    u32::from(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn le_u16(bytes: &[u8]) -> u32 {
+    u32::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn le_u24(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().expect("four bytes"))
+}
+
+fn read_png(head: &[u8]) -> Option<ImageInfo> {
+    let ihdr = head.get(12..26)?;
+    if &ihdr[..4] != b"IHDR" {
+        return None;
+    }
+    let channels = match ihdr[13] {
+        0 | 3 => 1,
+        4 => 2,
+        2 => 3,
+        6 => 4,
+        _ => return None,
+    };
+    ImageInfo::new(
+        be_uint(&ihdr[4..8]) as u32,
+        be_uint(&ihdr[8..12]) as u32,
+        Some(u32::from(ihdr[12]) * channels),
+        false,
+    )
+}
+
+fn read_gif(head: &[u8]) -> Option<ImageInfo> {
+    let screen = head.get(6..11)?;
+    let packed = screen[4];
+    let bit_depth = if packed & 0x80 != 0 {
+        u32::from(packed & 0x07) + 1
+    } else {
+        8
+    };
+    ImageInfo::new(
+        le_u16(&screen[0..2]),
+        le_u16(&screen[2..4]),
+        Some(bit_depth),
+        false,
+    )
+}
+
+fn read_bmp(head: &[u8]) -> Option<ImageInfo> {
+    let header_len = le_u32(head.get(14..18)?);
+    if header_len == 12 {
+        // BITMAPCOREHEADER, with 16-bit dimensions.
+        let core = head.get(18..26)?;
+        return ImageInfo::new(
+            le_u16(&core[0..2]),
+            le_u16(&core[2..4]),
+            Some(le_u16(&core[6..8])),
+            false,
+        );
+    }
+    let info = head.get(18..30)?;
+    // A negative height marks a top-down bitmap.
+    ImageInfo::new(
+        le_u32(&info[0..4]),
+        (le_u32(&info[4..8]) as i32).unsigned_abs(),
+        Some(le_u16(&info[10..12])),
+        false,
+    )
+}
+
+fn read_webp(head: &[u8]) -> Option<ImageInfo> {
+    match head.get(12..16)? {
+        b"VP8 " => {
+            let frame = head.get(23..30)?;
+            if frame[..3] != [0x9d, 0x01, 0x2a] {
+                return None;
+            }
+            ImageInfo::new(
+                le_u16(&frame[3..5]) & 0x3fff,
+                le_u16(&frame[5..7]) & 0x3fff,
+                Some(24),
+                false,
+            )
+        }
+        b"VP8L" => {
+            if head.get(20) != Some(&0x2f) {
+                return None;
+            }
+            let bits = le_u32(head.get(21..25)?);
+            let alpha = bits >> 28 & 1 != 0;
+            ImageInfo::new(
+                (bits & 0x3fff) + 1,
+                (bits >> 14 & 0x3fff) + 1,
+                Some(if alpha { 32 } else { 24 }),
+                false,
+            )
+        }
+        b"VP8X" => {
+            let flags = *head.get(20)?;
+            let canvas = head.get(24..30)?;
+            ImageInfo::new(
+                le_u24(&canvas[0..3]) + 1,
+                le_u24(&canvas[3..6]) + 1,
+                Some(if flags & 0x10 != 0 { 32 } else { 24 }),
+                false,
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Walks the JPEG segments up to the first start-of-frame, picking up the
+/// EXIF orientation on the way.
+fn read_jpeg(file: &mut (impl Read + Seek)) -> Option<ImageInfo> {
+    file.seek(SeekFrom::Start(2)).ok()?;
+    let mut quarter_turn = false;
+    loop {
+        let mut byte = [0u8; 1];
+        file.read_exact(&mut byte).ok()?;
+        if byte[0] != 0xff {
+            return None;
+        }
+        // Any number of 0xFF fill bytes may precede the marker.
+        while byte[0] == 0xff {
+            file.read_exact(&mut byte).ok()?;
+        }
+        let marker = byte[0];
+        match marker {
+            0x01 | 0xd0..=0xd7 => continue,
+            0xd9 | 0xda => return None,
+            _ => {}
+        }
+        let mut len = [0u8; 2];
+        file.read_exact(&mut len).ok()?;
+        let body_len = be_u16(&len).checked_sub(2)?;
+        match marker {
+            // Every SOFn except DHT (C4), JPG (C8) and DAC (CC).
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                let mut frame = [0u8; 6];
+                file.read_exact(&mut frame).ok()?;
+                return ImageInfo::new(
+                    be_u16(&frame[3..5]),
+                    be_u16(&frame[1..3]),
+                    Some(u32::from(frame[0]) * u32::from(frame[5])),
+                    quarter_turn,
+                );
+            }
+            0xe1 => {
+                let mut body = vec![0u8; body_len as usize];
+                file.read_exact(&mut body).ok()?;
+                if let Some(exif) = body.strip_prefix(b"Exif\0\0")
+                    && let Some(tiff) = read_tiff(&mut Cursor::new(exif), 0)
+                {
+                    quarter_turn = tiff.quarter_turn();
+                }
+            }
+            _ => {
+                file.seek(SeekFrom::Current(i64::from(body_len))).ok()?;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TiffInfo {
+    width: Option<u32>,
+    height: Option<u32>,
+    bits_per_sample: Option<u32>,
+    samples_per_pixel: Option<u32>,
+    orientation: Option<u32>,
+}
+
+impl TiffInfo {
+    fn bit_depth(&self) -> Option<u32> {
+        Some(self.bits_per_sample? * self.samples_per_pixel.unwrap_or(1))
+    }
+
+    /// Orientations 5 to 8 are transposed or rotated by a quarter turn.
+    fn quarter_turn(&self) -> bool {
+        matches!(self.orientation, Some(5..=8))
+    }
+}
+
+/// Reads the tags of the first image file directory of a TIFF structure
+/// starting at `base`, which is also how EXIF is laid out.
+fn read_tiff(file: &mut (impl Read + Seek), base: u64) -> Option<TiffInfo> {
+    let mut header = [0u8; 8];
+    file.seek(SeekFrom::Start(base)).ok()?;
+    file.read_exact(&mut header).ok()?;
+    let little = match &header[..2] {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let u16_at = |bytes: &[u8]| {
+        if little { le_u16(bytes) } else { be_u16(bytes) }
+    };
+    let u32_at = |bytes: &[u8]| {
+        if little {
+            le_u32(bytes)
+        } else {
+            be_uint(&bytes[..4]) as u32
+        }
+    };
+    if u16_at(&header[2..4]) != 42 {
+        return None;
+    }
+
+    file.seek(SeekFrom::Start(base + u64::from(u32_at(&header[4..8]))))
+        .ok()?;
+    let mut count = [0u8; 2];
+    file.read_exact(&mut count).ok()?;
+    let count = u16_at(&count) as u16;
+    if count > MAX_IFD_ENTRIES {
+        return None;
+    }
+    let mut entries = vec![0u8; usize::from(count) * 12];
+    file.read_exact(&mut entries).ok()?;
+
+    let mut info = TiffInfo::default();
+    let mut bits_offset = None;
+    for entry in entries.chunks_exact(12) {
+        let tag = u16_at(&entry[0..2]);
+        let kind = u16_at(&entry[2..4]);
+        let values = u32_at(&entry[4..8]);
+        // Values that fit in four bytes are stored in place, left-justified.
+        let value = match kind {
+            3 => u16_at(&entry[8..10]),
+            4 => u32_at(&entry[8..12]),
+            _ => continue,
+        };
+        match tag {
+            256 => info.width = Some(value),
+            257 => info.height = Some(value),
+            // One count per sample; the first stands for all of them.
+            258 if kind == 3 && values > 2 => bits_offset = Some(u32_at(&entry[8..12])),
+            258 => info.bits_per_sample = Some(value),
+            274 => info.orientation = Some(value),
+            277 => info.samples_per_pixel = Some(value),
+            _ => {}
+        }
+    }
+    if let Some(offset) = bits_offset {
+        let mut bits = [0u8; 2];
+        file.seek(SeekFrom::Start(base + u64::from(offset))).ok()?;
+        file.read_exact(&mut bits).ok()?;
+        info.bits_per_sample = Some(u16_at(&bits));
+    }
+    Some(info)
+}
+
+/// Reads the item properties of a HEIF or AVIF file. The largest `ispe`
+/// belongs to the primary image; the others describe tiles and thumbnails.
+fn read_heif(file: &mut (impl Read + Seek)) -> Option<ImageInfo> {
+    let meta = read_top_level_atom(file, 0, b"meta", MAX_META_BYTES)?;
+    // `meta` is a full box with four bytes of version and flags.
+    let ipco = find_atom(find_atom(meta.get(4..)?, b"iprp")?, b"ipco")?;
+    let mut size: Option<(u32, u32)> = None;
+    let mut rotation = None;
+    let mut bit_depth = None;
+    for (kind, body) in mp4_atoms(ipco) {
+        match &kind {
+            b"ispe" if body.len() >= 12 => {
+                let width = be_uint(&body[4..8]) as u32;
+                let height = be_uint(&body[8..12]) as u32;
+                if size.is_none_or(|(w, h)| {
+                    u64::from(width) * u64::from(height) > u64::from(w) * u64::from(h)
+                }) {
+                    size = Some((width, height));
+                }
+            }
+            b"irot" if rotation.is_none() => rotation = body.first().map(|angle| angle & 0x03),
+            b"pixi" if bit_depth.is_none() => {
+                let channels = usize::from(*body.get(4)?);
+                bit_depth = body
+                    .get(5..5 + channels)
+                    .map(|bits| bits.iter().map(|&bits| u32::from(bits)).sum());
+            }
+            _ => {}
+        }
+    }
+    let (width, height) = size?;
+    ImageInfo::new(width, height, bit_depth, rotation.unwrap_or(0) % 2 == 1)
+}
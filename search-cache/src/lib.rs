@@ -1,23 +1,36 @@
 #![feature(str_from_raw_parts)]
+mod audio_tags;
 mod cache;
+mod case_fold;
+mod changes;
+mod content_dupes;
+mod diacritics;
 mod file_nodes;
+mod file_probe;
 mod highlight;
+mod image_info;
+mod live_query;
 mod metadata_cache;
 mod name_index;
 mod persistent;
 mod query;
 mod query_preprocessor;
+mod replay;
 mod segment;
 mod slab;
 mod slab_node;
 mod type_and_size;
 
 pub use cache::*;
+pub use changes::*;
+pub use content_dupes::*;
 pub use file_nodes::*;
 pub use fswalk::WalkData;
+pub use live_query::*;
 pub use metadata_cache::*;
 pub use name_index::*;
 pub use persistent::*;
+pub use replay::*;
 pub use segment::*;
 pub use slab::*;
 pub use slab_node::*;
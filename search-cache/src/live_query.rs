@@ -0,0 +1,121 @@
+use crate::{SearchOptions, SlabIndex};
+use cardinal_sdk::EventId;
+use cardinal_syntax::{Expr, Term, parse_query};
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use hashbrown::HashSet;
+use std::time::{Duration, Instant};
+
+/// Default minimum pause between two whole-index runs of one live query.
+const WHOLE_INDEX_RERUN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How one applied batch changed the result set of a live query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryDelta {
+    /// Nodes that started matching, sorted by `SlabIndex`.
+    pub added: Vec<SlabIndex>,
+    /// Nodes that stopped matching or were removed, sorted by `SlabIndex`.
+    pub removed: Vec<SlabIndex>,
+    /// Latest event id of the batch that caused the delta.
+    pub event_id: EventId,
+}
+
+/// A registered standing query: its current results and the delta stream.
+///
+/// Dropping `deltas` unregisters the query.
+#[derive(Debug)]
+pub struct QuerySubscription {
+    pub nodes: Vec<SlabIndex>,
+    pub highlights: Vec<String>,
+    pub deltas: Receiver<QueryDelta>,
+}
+
+pub(crate) struct LiveQuery {
+    pub(crate) line: String,
+    pub(crate) options: SearchOptions,
+    pub(crate) results: HashSet<SlabIndex>,
+    /// Whether the query has to be re-run over the whole index, because a
+    /// change can alter the result for nodes the batch didn't touch.
+    pub(crate) whole_index: bool,
+    /// When the query last ran over the whole index.
+    pub(crate) last_run: Instant,
+    /// Batches applied since the last whole-index run, not yet accounted for.
+    pub(crate) pending: Option<PendingRerun>,
+    sender: Sender<QueryDelta>,
+}
+
+/// What a deferred whole-index run still has to account for.
+#[derive(Default)]
+pub(crate) struct PendingRerun {
+    /// Indices freed since the last run; a live one among them was reused.
+    pub(crate) freed: HashSet<SlabIndex>,
+    /// Latest event id of the deferred batches.
+    pub(crate) event_id: EventId,
+}
+
+impl LiveQuery {
+    /// Whether a deferred whole-index run may happen now.
+    pub(crate) fn is_due(&self, interval: Duration) -> bool {
+        self.pending.is_some() && self.last_run.elapsed() >= interval
+    }
+
+    /// Deliver `delta`, returning whether the subscriber is still listening.
+    pub(crate) fn send(&self, delta: QueryDelta) -> bool {
+        self.sender.send(delta).is_ok()
+    }
+}
+
+pub(crate) struct LiveQueries {
+    queries: Vec<LiveQuery>,
+    pub(crate) rerun_interval: Duration,
+}
+
+impl Default for LiveQueries {
+    fn default() -> Self {
+        Self {
+            queries: Vec::new(),
+            rerun_interval: WHOLE_INDEX_RERUN_INTERVAL,
+        }
+    }
+}
+
+impl LiveQueries {
+    pub(crate) fn register(
+        &mut self,
+        line: String,
+        options: SearchOptions,
+        results: HashSet<SlabIndex>,
+    ) -> Receiver<QueryDelta> {
+        let (sender, receiver) = unbounded();
+        let whole_index = parse_query(&line).is_ok_and(|query| groups_nodes(&query.expr));
+        self.queries.push(LiveQuery {
+            line,
+            options,
+            results,
+            whole_index,
+            last_run: Instant::now(),
+            pending: None,
+            sender,
+        });
+        receiver
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        !self.queries.is_empty()
+    }
+
+    pub(crate) fn queries_mut(&mut self) -> &mut Vec<LiveQuery> {
+        &mut self.queries
+    }
+}
+
+/// Whether `expr` holds a duplicate filter, whose matches depend on the other
+/// nodes it's evaluated with.
+fn groups_nodes(expr: &Expr) -> bool {
+    match expr {
+        Expr::Empty => false,
+        Expr::Term(Term::Filter(filter)) => filter.kind.is_duplicate(),
+        Expr::Term(_) => false,
+        Expr::Not(inner) | Expr::Modified(_, inner) => groups_nodes(inner),
+        Expr::And(parts) | Expr::Or(parts) => parts.iter().any(groups_nodes),
+    }
+}
@@ -1,8 +1,8 @@
-use crate::{FileNodes, NAME_POOL, SlabIndex};
+use crate::{FileNodes, NAME_POOL, SlabIndex, diacritics::strip_diacritics};
 use itertools::Itertools;
 use search_cancel::CancellationToken;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, time::Instant};
+use std::{borrow::Cow, collections::BTreeMap, time::Instant};
 use thin_vec::ThinVec;
 use tracing::info;
 
@@ -65,6 +65,10 @@ impl SortedSlabIndices {
 #[derive(Clone, Default)]
 pub struct NameIndex {
     map: BTreeMap<&'static str, SortedSlabIndices>,
+    /// The indexed names that read differently once diacritics are stripped,
+    /// with their stripped form. Diacritic-insensitive searches only need to
+    /// re-check these instead of stripping the whole name pool.
+    accented: BTreeMap<&'static str, Box<str>>,
 }
 
 impl NameIndex {
@@ -99,6 +103,24 @@ impl NameIndex {
         self.map.get_mut(name)
     }
 
+    pub(crate) fn is_accented(&self, name: &str) -> bool {
+        self.accented.contains_key(name)
+    }
+
+    /// Indexed names with diacritics, paired with their stripped form.
+    pub(crate) fn accented_names(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.accented
+            .iter()
+            .map(|(name, stripped)| (*name, &**stripped))
+    }
+
+    fn insert_name(&mut self, name: &'static str, indices: SortedSlabIndices) {
+        if let Cow::Owned(stripped) = strip_diacritics(name) {
+            self.accented.insert(name, stripped.into_boxed_str());
+        }
+        self.map.insert(name, indices);
+    }
+
     /// # Safety
     ///
     /// The index must be inserted with it's full path ordered.
@@ -109,7 +131,7 @@ impl NameIndex {
             }
         } else {
             let interned = NAME_POOL.push(name);
-            self.map.insert(interned, SortedSlabIndices::new(index));
+            self.insert_name(interned, SortedSlabIndices::new(index));
         }
     }
 
@@ -118,7 +140,7 @@ impl NameIndex {
             existing.insert(index, slab);
         } else {
             let interned = NAME_POOL.push(name);
-            self.map.insert(interned, SortedSlabIndices::new(index));
+            self.insert_name(interned, SortedSlabIndices::new(index));
         }
     }
 
@@ -128,12 +150,13 @@ impl NameIndex {
         };
         let removed = indices.remove(index);
         if indices.is_empty() {
-            self.map.remove(name);
+            self.remove(name);
         }
         removed
     }
 
     pub fn remove(&mut self, name: &str) -> Option<SortedSlabIndices> {
+        self.accented.remove(name);
         self.map.remove(name)
     }
 
@@ -153,16 +176,16 @@ impl NameIndex {
 
     pub fn construct_name_pool(data: BTreeMap<Box<str>, SortedSlabIndices>) -> Self {
         let name_pool_time = Instant::now();
-        let mut map = BTreeMap::new();
+        let mut index = Self::default();
         for (name, indices) in data {
             let interned = NAME_POOL.push(&name);
-            map.insert(interned, indices);
+            index.insert_name(interned, indices);
         }
         info!(
             "Name pool construction time: {:?}, count: {}",
             name_pool_time.elapsed(),
             NAME_POOL.len(),
         );
-        Self { map }
+        index
     }
 }
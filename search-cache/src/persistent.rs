@@ -1,5 +1,6 @@
-use crate::{SlabIndex, SlabNode, ThinSlab, name_index::SortedSlabIndices};
+use crate::{ContentHashCache, SlabIndex, SlabNode, ThinSlab, name_index::SortedSlabIndices};
 use anyhow::{Context, Result};
+use cardinal_sdk::ResumeCursor;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
@@ -12,13 +13,13 @@ use std::{
 use tracing::info;
 use typed_num::Num;
 
-const LSF_VERSION: i64 = 6;
+const LSF_VERSION: i64 = 10;
 
 #[derive(Serialize, Deserialize)]
 pub struct PersistentStorage {
     pub version: Num<LSF_VERSION>,
-    /// The last event id of the cache.
-    pub last_event_id: u64,
+    /// Where the change source feeding the cache left off.
+    pub cursor: ResumeCursor,
     /// Root file path of the cache
     pub path: PathBuf,
     /// Ignore paths
@@ -31,6 +32,8 @@ pub struct PersistentStorage {
     pub name_index: BTreeMap<Box<str>, SortedSlabIndices>,
     /// The number of rescans emitted before this snapshot.
     pub rescan_count: u64,
+    /// Hashes computed for content duplicate detection.
+    pub content_hashes: ContentHashCache,
 }
 
 pub fn read_cache_from_file(path: &Path) -> Result<PersistentStorage> {
@@ -1,6 +1,12 @@
 use crate::{
     SearchCache, SearchOptions, SegmentKind, SegmentMatcher, SegmentMatcherConcrete, SlabIndex,
-    SlabNodeMetadataCompact, build_segment_matchers, cache::NAME_POOL,
+    SlabNodeMetadataCompact,
+    audio_tags::AudioField,
+    build_segment_matchers,
+    cache::NAME_POOL,
+    case_fold::{fold_case, fold_utf8},
+    image_info::{ImageInfo, Orientation},
+    term_segments,
 };
 use anyhow::{Result, anyhow, bail};
 use cardinal_syntax::{
@@ -8,14 +14,13 @@ use cardinal_syntax::{
 };
 use file_tags::{read_tags_from_path, search_tags_using_mdfind};
 use fswalk::NodeFileType;
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use jiff::{Timestamp, civil::Date, tz::TimeZone};
 use memchr::arch::all::rabinkarp;
-use query_segmentation::query_segmentation;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use regex::RegexBuilder;
 use search_cancel::CancellationToken;
-use std::{collections::BTreeSet, fs::File, io::Read, path::Path};
+use std::{borrow::Cow, collections::BTreeSet, fmt, fs::File, io::Read, path::Path};
 
 pub(crate) const CONTENT_BUFFER_BYTES: usize = 64 * 1024;
 
@@ -38,6 +43,10 @@ impl SearchCache {
             Expr::Not(inner) => self.evaluate_not(inner, base, options, token),
             Expr::And(parts) => self.evaluate_and(parts, base.cloned(), options, token),
             Expr::Or(parts) => self.evaluate_or(parts, base, options, token),
+            Expr::Modified(kind, inner) => {
+                let options = modified_options(kind, options)?;
+                self.evaluate_expr(inner, base, options, token)
+            }
         }
     }
 
@@ -148,6 +157,17 @@ impl SearchCache {
         options: SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
+        if let Some(base) = base {
+            let segments = term_segments(text, options);
+            if segments.is_empty() {
+                bail!("Unprocessable term: {text:?}");
+            }
+            let matchers = build_segment_matchers(&segments, options)
+                .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+            if let [SegmentMatcher::Concrete(matcher)] = matchers.as_slice() {
+                return Ok(self.match_base_names(base, matcher, token));
+            }
+        }
         let Some(mut nodes) = self.evaluate_phrase(text, options, token)? else {
             return Ok(None);
         };
@@ -165,7 +185,7 @@ impl SearchCache {
         options: SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let segments = query_segmentation(text);
+        let segments = term_segments(text, options);
         if segments.is_empty() {
             bail!("Unprocessable term: {text:?}");
         }
@@ -250,7 +270,23 @@ impl SearchCache {
         matcher: &SegmentMatcherConcrete,
         token: CancellationToken,
     ) -> Option<Vec<SlabIndex>> {
-        let names: BTreeSet<_> = match matcher {
+        let names = self.matching_names(matcher, token)?;
+        let mut nodes = Vec::with_capacity(names.len());
+        for (i, name) in names.iter().enumerate() {
+            token.is_cancelled_sparse(i)?;
+            if let Some(indices) = self.name_index.get(name) {
+                nodes.extend(indices.iter().copied());
+            }
+        }
+        Some(nodes)
+    }
+
+    fn matching_names(
+        &self,
+        matcher: &SegmentMatcherConcrete,
+        token: CancellationToken,
+    ) -> Option<BTreeSet<&'static str>> {
+        match matcher {
             SegmentMatcherConcrete::Plain { kind, needle } => match kind {
                 SegmentKind::Substr => NAME_POOL.search_substr(needle, token),
                 SegmentKind::Prefix => NAME_POOL.search_prefix(needle, token),
@@ -258,15 +294,29 @@ impl SearchCache {
                 SegmentKind::Exact => NAME_POOL.search_exact(needle, token),
             },
             SegmentMatcherConcrete::Regex { regex } => NAME_POOL.search_regex(regex, token),
-        }?;
-        let mut nodes = Vec::with_capacity(names.len());
-        for (i, name) in names.iter().enumerate() {
-            token.is_cancelled_sparse(i)?;
-            if let Some(indices) = self.name_index.get(name) {
-                nodes.extend(indices.iter().copied());
+            SegmentMatcherConcrete::WholeWord { regex } => {
+                NAME_POOL.search_regex(regex, token).map(|names| {
+                    names
+                        .into_iter()
+                        .filter(|name| matcher.matches(name))
+                        .collect()
+                })
+            }
+            // Names without diacritics read the same stripped, so the regular
+            // pool search covers them and only the accented names are
+            // compared in stripped form.
+            SegmentMatcherConcrete::IgnoreDiacritics(inner) => {
+                let mut names = self.matching_names(inner, token)?;
+                names.retain(|name| !self.name_index.is_accented(name));
+                for (i, (name, stripped)) in self.name_index.accented_names().enumerate() {
+                    token.is_cancelled_sparse(i)?;
+                    if inner.matches(stripped) {
+                        names.insert(name);
+                    }
+                }
+                Some(names)
             }
         }
-        Some(nodes)
     }
 
     fn match_direct_child_segments(
@@ -363,17 +413,32 @@ impl SearchCache {
         Some(matches.into_iter().map(|(_, index)| index).collect())
     }
 
+    /// Single-segment terms inside a known base only need to look at the
+    /// names of the base nodes, which is much cheaper than a name pool scan
+    /// when the base is small (directory scopes, live query updates).
+    ///
+    /// Matches come back ordered by name like a name pool scan would return
+    /// them; nodes sharing a name keep their base order.
+    fn match_base_names(
+        &self,
+        base: &[SlabIndex],
+        matcher: &SegmentMatcherConcrete,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let mut nodes = filter_nodes(base.to_vec(), token, |index| {
+            matcher.matches(self.file_nodes[index].name())
+        })?;
+        nodes.sort_by_key(|&index| self.file_nodes[index].name());
+        Some(nodes)
+    }
+
     fn evaluate_regex(
         &self,
         pattern: &str,
         options: SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let mut builder = RegexBuilder::new(pattern);
-        builder.case_insensitive(options.case_insensitive);
-        let regex = builder
-            .build()
-            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        let regex = build_regex(pattern, options)?;
         let matcher = SegmentMatcher::Concrete(SegmentMatcherConcrete::Regex { regex });
         Ok(self.execute_matchers(std::slice::from_ref(&matcher), token))
     }
@@ -385,6 +450,12 @@ impl SearchCache {
         options: SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
+        if let Some(base) = base {
+            let matcher = SegmentMatcherConcrete::Regex {
+                regex: build_regex(pattern, options)?,
+            };
+            return Ok(self.match_base_names(base, &matcher, token));
+        }
         let Some(mut nodes) = self.evaluate_regex(pattern, options, token)? else {
             return Ok(None);
         };
@@ -446,6 +517,76 @@ impl SearchCache {
                     .ok_or_else(|| anyhow!("nosubfolders: requires a folder path"))?;
                 self.evaluate_nosubfolders_filter(argument, base, options, token)
             }
+            FilterKind::Child => {
+                let argument = filter
+                    .argument
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("child: requires a name or pattern"))?;
+                self.evaluate_child_filter(argument, base, options, token)
+            }
+            FilterKind::ChildCount => {
+                self.evaluate_child_count_filter(ChildScope::All, filter, base, token)
+            }
+            FilterKind::ChildFileCount => {
+                self.evaluate_child_count_filter(ChildScope::Files, filter, base, token)
+            }
+            FilterKind::ChildFolderCount => {
+                self.evaluate_child_count_filter(ChildScope::Folders, filter, base, token)
+            }
+            FilterKind::Attribute => {
+                let argument = filter
+                    .argument
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("attrib: requires attribute letters"))?;
+                self.evaluate_attribute_filter(argument, base, token)
+            }
+            FilterKind::Duplicate if is_content_keyword(filter.argument.as_ref()) => {
+                self.evaluate_content_duplicate_filter(base, None, options, token)
+            }
+            FilterKind::Duplicate => self.evaluate_duplicate_filter(
+                DuplicateKey::Name,
+                base,
+                filter.argument.as_ref(),
+                options,
+                token,
+            ),
+            FilterKind::HashDuplicate => self.evaluate_content_duplicate_filter(
+                base,
+                filter.argument.as_ref(),
+                options,
+                token,
+            ),
+            FilterKind::NamePartDuplicate => self.evaluate_duplicate_filter(
+                DuplicateKey::NamePart,
+                base,
+                filter.argument.as_ref(),
+                options,
+                token,
+            ),
+            FilterKind::SizeDuplicate => self.evaluate_duplicate_filter(
+                DuplicateKey::Size,
+                base,
+                filter.argument.as_ref(),
+                options,
+                token,
+            ),
+            FilterKind::DateModifiedDuplicate => self.evaluate_duplicate_filter(
+                DuplicateKey::DateModified,
+                base,
+                filter.argument.as_ref(),
+                options,
+                token,
+            ),
+            FilterKind::AttributeDuplicate => self.evaluate_duplicate_filter(
+                DuplicateKey::Attributes,
+                base,
+                filter.argument.as_ref(),
+                options,
+                token,
+            ),
+            FilterKind::Empty => {
+                self.evaluate_empty_filter(base, filter.argument.as_ref(), options, token)
+            }
             FilterKind::Type => {
                 let argument = filter
                     .argument
@@ -486,6 +627,13 @@ impl SearchCache {
                     .ok_or_else(|| anyhow!("dc: requires a date or range"))?;
                 self.evaluate_date_filter(DateField::Created, argument, base, token)
             }
+            FilterKind::DateAccessed => {
+                let argument = filter
+                    .argument
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("da: requires a date or range"))?;
+                self.evaluate_date_filter(DateField::Accessed, argument, base, token)
+            }
             FilterKind::Content => {
                 let argument = filter
                     .argument
@@ -500,6 +648,63 @@ impl SearchCache {
                     .ok_or_else(|| anyhow!("tag: requires a value"))?;
                 self.evaluate_tag_filter(argument, base, options, token)
             }
+            FilterKind::Artist => {
+                self.evaluate_audio_filter(AudioField::Artist, filter, base, options, token)
+            }
+            FilterKind::Album => {
+                self.evaluate_audio_filter(AudioField::Album, filter, base, options, token)
+            }
+            FilterKind::Title => {
+                self.evaluate_audio_filter(AudioField::Title, filter, base, options, token)
+            }
+            FilterKind::Genre => {
+                self.evaluate_audio_filter(AudioField::Genre, filter, base, options, token)
+            }
+            FilterKind::Year => {
+                self.evaluate_audio_filter(AudioField::Year, filter, base, options, token)
+            }
+            FilterKind::Track => {
+                self.evaluate_audio_filter(AudioField::Track, filter, base, options, token)
+            }
+            FilterKind::Comment => {
+                self.evaluate_audio_filter(AudioField::Comment, filter, base, options, token)
+            }
+            FilterKind::CaseSensitive
+            | FilterKind::WholeFilename
+            | FilterKind::NoWholeFilename
+            | FilterKind::WholeWord
+            | FilterKind::NoWholeWord
+            | FilterKind::StartWith
+            | FilterKind::EndWith
+            | FilterKind::Diacritics
+            | FilterKind::NoDiacritics => {
+                let argument = filter
+                    .argument
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("{}: requires a value", modifier_name(&filter.kind)))?;
+                let options = modified_options(&filter.kind, options)?;
+                match &argument.kind {
+                    // `endwith:.tar.gz;.tgz` matches either value.
+                    ArgumentKind::List(values) => {
+                        let words: Vec<Expr> = values
+                            .iter()
+                            .map(|value| Expr::Term(Term::Word(value.clone())))
+                            .collect();
+                        self.evaluate_or(&words, base.as_ref(), options, token)
+                    }
+                    _ => self.evaluate_term(
+                        &Term::Word(argument.raw.clone()),
+                        base.as_ref(),
+                        options,
+                        token,
+                    ),
+                }
+            }
+            FilterKind::Width
+            | FilterKind::Height
+            | FilterKind::Dimensions
+            | FilterKind::Orientation
+            | FilterKind::BitDepth => self.evaluate_image_filter(filter, base, token),
             _ => bail!("Filter {:?} is not supported yet", filter.kind),
         }
     }
@@ -511,6 +716,23 @@ impl SearchCache {
         argument: Option<&FilterArgument>,
         options: SearchOptions,
         token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(nodes) = self.nodes_matching_argument(base, argument, options, token)? else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(nodes, token, |index| {
+            self.file_nodes[index].file_type_hint() == file_type
+        }))
+    }
+
+    /// The base (or every node) narrowed to names matching the optional
+    /// argument, for filters like `folder:Projects`.
+    fn nodes_matching_argument(
+        &self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let (mut nodes, argument_applied) = match (base, argument) {
             (Some(nodes), _) => (nodes, false),
@@ -532,10 +754,7 @@ impl SearchCache {
                 return Ok(None);
             }
         }
-
-        Ok(filter_nodes(nodes, token, |index| {
-            self.file_nodes[index].file_type_hint() == file_type
-        }))
+        Ok(Some(nodes))
     }
 
     fn evaluate_extension_filter(
@@ -572,10 +791,7 @@ impl SearchCache {
         let Some(target) =
             self.node_index_for_path_with_case(Path::new(&argument.raw), options.case_insensitive)
         else {
-            bail!(
-                "Parent filter {:?} is not found in file system",
-                argument.raw
-            );
+            return Err(ScopeNotFound::new("Parent", argument).into());
         };
         let children = self.file_nodes[target].children.to_vec();
         if let Some(mut nodes) = base {
@@ -598,22 +814,26 @@ impl SearchCache {
         let Some(target) =
             self.node_index_for_path_with_case(Path::new(&argument.raw), options.case_insensitive)
         else {
-            bail!(
-                "Parent filter {:?} is not found in file system",
-                argument.raw
-            );
-        };
-        let Some(children) = self.all_subnodes(target, token) else {
-            return Ok(None);
+            return Err(ScopeNotFound::new("Parent", argument).into());
         };
-        if let Some(mut nodes) = base {
-            if intersect_in_place(&mut nodes, &children, token).is_none() {
-                return Ok(None);
+        if let Some(nodes) = base {
+            // Walking up from each base node is cheaper than collecting the whole subtree.
+            return Ok(filter_nodes(nodes, token, |index| {
+                self.is_strict_descendant(index, target)
+            }));
+        }
+        Ok(self.all_subnodes(target, token))
+    }
+
+    fn is_strict_descendant(&self, index: SlabIndex, ancestor: SlabIndex) -> bool {
+        let mut current = self.file_nodes[index].parent();
+        while let Some(parent) = current {
+            if parent == ancestor {
+                return true;
             }
-            Ok(Some(nodes))
-        } else {
-            Ok(Some(children))
+            current = self.file_nodes[parent].parent();
         }
+        false
     }
 
     fn evaluate_nosubfolders_filter(
@@ -626,10 +846,7 @@ impl SearchCache {
         let Some(target) =
             self.node_index_for_path_with_case(Path::new(&argument.raw), options.case_insensitive)
         else {
-            bail!(
-                "nosubfolders filter {:?} is not found in file system",
-                argument.raw
-            );
+            return Err(ScopeNotFound::new("nosubfolders", argument).into());
         };
         if self.file_nodes[target].file_type_hint() != NodeFileType::Dir {
             bail!("nosubfolders path {:?} is not a folder", argument.raw);
@@ -649,6 +866,286 @@ impl SearchCache {
         }
     }
 
+    /// Keeps the folders that have at least one direct child matching the
+    /// argument, which is segmented and matched like a plain search term.
+    fn evaluate_child_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let segments = term_segments(&argument.raw, options);
+        if segments.is_empty() {
+            bail!("child: requires a name or pattern");
+        }
+        let matchers = build_segment_matchers(&segments, options)
+            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+
+        if let Some(base) = base {
+            // Checking the children of a small base beats a name pool scan.
+            if let [SegmentMatcher::Concrete(matcher)] = matchers.as_slice() {
+                return Ok(filter_nodes(base, token, |index| {
+                    self.file_nodes[index]
+                        .children
+                        .iter()
+                        .any(|&child| matcher.matches(self.file_nodes[child].name()))
+                }));
+            }
+            let Some(parents) = self.parents_of_matches(&matchers, token) else {
+                return Ok(None);
+            };
+            let mut nodes = base;
+            if intersect_in_place(&mut nodes, &parents, token).is_none() {
+                return Ok(None);
+            }
+            return Ok(Some(nodes));
+        }
+        Ok(self.parents_of_matches(&matchers, token))
+    }
+
+    fn parents_of_matches(
+        &self,
+        matchers: &[SegmentMatcher],
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let matches = self.execute_matchers(matchers, token)?;
+        let mut seen = HashSet::new();
+        let mut parents = Vec::new();
+        for (i, index) in matches.into_iter().enumerate() {
+            token.is_cancelled_sparse(i)?;
+            if let Some(parent) = self.file_nodes[index].parent()
+                && seen.insert(parent)
+            {
+                parents.push(parent);
+            }
+        }
+        Some(parents)
+    }
+
+    fn evaluate_child_count_filter(
+        &self,
+        scope: ChildScope,
+        filter: &Filter,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let name = scope.filter_name();
+        let argument = filter
+            .argument
+            .as_ref()
+            .ok_or_else(|| anyhow!("{name}: requires a count or range"))?;
+        let predicate = CountPredicate::parse(name, argument)?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(nodes, token, |index| {
+            let node = &self.file_nodes[index];
+            if node.file_type_hint() != NodeFileType::Dir {
+                return false;
+            }
+            let count = node
+                .children
+                .iter()
+                .filter(|&&child| scope.counts(self.file_nodes[child].file_type_hint()))
+                .count();
+            predicate.matches(count as u64)
+        }))
+    }
+
+    /// Zero-byte files and folders without children.
+    fn evaluate_empty_filter(
+        &mut self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(nodes) = self.nodes_matching_argument(base, argument, options, token)? else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(nodes, token, |index| {
+            let node = &self.file_nodes[index];
+            match node.file_type_hint() {
+                NodeFileType::Dir => node.children.is_empty(),
+                NodeFileType::File => self
+                    .ensure_metadata(index)
+                    .as_ref()
+                    .is_some_and(|meta| meta.size() == 0),
+                _ => false,
+            }
+        }))
+    }
+
+    fn evaluate_attribute_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let attributes = parse_attributes(&argument.raw)?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(nodes, token, |index| {
+            attributes
+                .iter()
+                .all(|&attribute| self.node_has_attribute(index, attribute))
+        }))
+    }
+
+    fn node_has_attribute(&mut self, index: SlabIndex, attribute: Attribute) -> bool {
+        match attribute {
+            Attribute::Directory => self.file_nodes[index].file_type_hint() == NodeFileType::Dir,
+            Attribute::Hidden => {
+                // Only macOS reports `UF_HIDDEN`, so elsewhere the name is all there is.
+                self.file_nodes[index].name().starts_with('.')
+                    || (cfg!(target_os = "macos")
+                        && self
+                            .ensure_metadata(index)
+                            .as_ref()
+                            .is_some_and(|meta| meta.has_hidden_flag()))
+            }
+            Attribute::Symlink => self.node_file_type(index) == NodeFileType::Symlink,
+            // Symlink permissions are meaningless, they are always 0o777 on Linux.
+            Attribute::ReadOnly => {
+                self.node_file_type(index) != NodeFileType::Symlink
+                    && self
+                        .node_permissions(index)
+                        .is_some_and(|mode| mode & 0o222 == 0)
+            }
+            Attribute::Executable => {
+                self.node_file_type(index) == NodeFileType::File
+                    && self
+                        .node_permissions(index)
+                        .is_some_and(|mode| mode & 0o111 != 0)
+            }
+            Attribute::Setuid => self
+                .node_permissions(index)
+                .is_some_and(|mode| mode & 0o4000 != 0),
+            Attribute::Setgid => self
+                .node_permissions(index)
+                .is_some_and(|mode| mode & 0o2000 != 0),
+            Attribute::Sticky => self
+                .node_permissions(index)
+                .is_some_and(|mode| mode & 0o1000 != 0),
+        }
+    }
+
+    /// Walks without metadata only tell folders apart, so anything else
+    /// needs an lstat to tell files from symlinks.
+    pub(crate) fn node_file_type(&mut self, index: SlabIndex) -> NodeFileType {
+        let hint = self.file_nodes[index].file_type_hint();
+        if hint == NodeFileType::Dir {
+            return hint;
+        }
+        self.ensure_metadata(index)
+            .as_ref()
+            .map_or(hint, |meta| meta.r#type())
+    }
+
+    fn node_permissions(&mut self, index: SlabIndex) -> Option<u32> {
+        let metadata = self.ensure_metadata(index);
+        Some(metadata.as_ref()?.permissions())
+    }
+
+    /// Keeps the nodes sharing their key with at least one other node,
+    /// with every group of duplicates listed contiguously.
+    fn evaluate_duplicate_filter(
+        &mut self,
+        key: DuplicateKey,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(nodes) = self.nodes_matching_argument(base, argument, options, token)? else {
+            return Ok(None);
+        };
+        let mut group_of: HashMap<DuplicateValue, usize> = HashMap::new();
+        let mut groups: Vec<Vec<SlabIndex>> = Vec::new();
+        for (i, index) in nodes.into_iter().enumerate() {
+            if token.is_cancelled_sparse(i).is_none() {
+                return Ok(None);
+            }
+            let Some(value) = self.duplicate_value(index, key, options) else {
+                continue;
+            };
+            let group = *group_of.entry(value).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(index);
+        }
+        Ok(Some(
+            groups
+                .into_iter()
+                .filter(|group| group.len() > 1)
+                .flatten()
+                .collect(),
+        ))
+    }
+
+    /// Keeps the files whose contents are identical to at least one other
+    /// file, one group after another. See [`SearchCache::content_duplicates`].
+    fn evaluate_content_duplicate_filter(
+        &mut self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(nodes) = self.nodes_matching_argument(base, argument, options, token)? else {
+            return Ok(None);
+        };
+        Ok(self
+            .content_duplicates(&nodes, token)
+            .map(|groups| groups.into_iter().flatten().collect()))
+    }
+
+    /// `None` leaves the node out, e.g. folders for `sizedupe:` or nodes
+    /// whose metadata can't be read.
+    fn duplicate_value(
+        &mut self,
+        index: SlabIndex,
+        key: DuplicateKey,
+        options: SearchOptions,
+    ) -> Option<DuplicateValue> {
+        let fold = |name: &'static str| {
+            if options.case_insensitive {
+                Cow::Owned(fold_case(name))
+            } else {
+                Cow::Borrowed(name)
+            }
+        };
+        let name = self.file_nodes[index].name();
+        match key {
+            DuplicateKey::Name => Some(DuplicateValue::Text(fold(name))),
+            DuplicateKey::NamePart => Some(DuplicateValue::Text(fold(name_part(name)))),
+            DuplicateKey::Size => {
+                if self.node_file_type(index) != NodeFileType::File {
+                    return None;
+                }
+                let metadata = self.ensure_metadata(index);
+                Some(DuplicateValue::Number(metadata.as_ref()?.size()))
+            }
+            DuplicateKey::DateModified => {
+                let mtime = self.node_timestamp(index, DateField::Modified)?;
+                Some(DuplicateValue::Number(mtime))
+            }
+            DuplicateKey::Attributes => {
+                let file_type = self.node_file_type(index);
+                let permissions = self.node_permissions(index)?;
+                let hidden = self.node_has_attribute(index, Attribute::Hidden);
+                Some(DuplicateValue::Attributes {
+                    file_type: file_type as u8,
+                    permissions,
+                    hidden,
+                })
+            }
+        }
+    }
+
     fn evaluate_named_type_filter(
         &self,
         raw: &str,
@@ -790,7 +1287,7 @@ impl SearchCache {
     ) -> Result<Option<Vec<SlabIndex>>> {
         let ghost;
         let needle = if options.case_insensitive {
-            ghost = argument.raw.to_ascii_lowercase().into_bytes();
+            ghost = fold_case(&argument.raw).into_bytes();
             &ghost
         } else {
             argument.raw.as_bytes()
@@ -852,7 +1349,7 @@ impl SearchCache {
         let needles = if options.case_insensitive {
             raw_needles
                 .into_iter()
-                .map(|value| value.to_ascii_lowercase())
+                .map(|value| fold_case(&value))
                 .collect()
         } else {
             raw_needles
@@ -895,7 +1392,91 @@ impl SearchCache {
         Ok(token.is_cancelled().map(|()| matched_indices))
     }
 
-    /// user need to ensure that needle is lowercased when case_insensitive is set
+    /// Matches the tags of audio files: a substring of the text fields, or
+    /// a number, comparison or range for `year:` and `track:`.
+    fn evaluate_audio_filter(
+        &mut self,
+        field: AudioField,
+        filter: &Filter,
+        base: Option<Vec<SlabIndex>>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let name = field.filter_name();
+        let argument = filter
+            .argument
+            .as_ref()
+            .filter(|argument| !argument.raw.trim().is_empty())
+            .ok_or_else(|| anyhow!("{name}: requires a value"))?;
+        if field.is_numeric() {
+            let predicate = CountPredicate::parse(name, argument)?;
+            let Some(nodes) = self.nodes_from_base(base, token) else {
+                return Ok(None);
+            };
+            return Ok(self.nodes_with_audio_tags(
+                nodes,
+                |tags| {
+                    field
+                        .number(tags)
+                        .is_some_and(|value| predicate.matches(u64::from(value)))
+                },
+                token,
+            ));
+        }
+
+        let needles: Vec<String> = match &argument.kind {
+            ArgumentKind::Bare | ArgumentKind::Phrase => vec![argument.raw.trim().to_string()],
+            ArgumentKind::List(values) => values
+                .iter()
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect(),
+            ArgumentKind::Range(_) | ArgumentKind::Comparison(_) => {
+                bail!("{name}: does not support ranges or comparisons");
+            }
+        };
+        let needles: Vec<String> = if options.case_insensitive {
+            needles.iter().map(|needle| fold_case(needle)).collect()
+        } else {
+            needles
+        };
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        Ok(self.nodes_with_audio_tags(
+            nodes,
+            |tags| {
+                field.text(tags).is_some_and(|text| {
+                    let text = if options.case_insensitive {
+                        Cow::Owned(fold_case(text))
+                    } else {
+                        Cow::Borrowed(text)
+                    };
+                    needles.iter().any(|needle| text.contains(needle.as_str()))
+                })
+            },
+            token,
+        ))
+    }
+
+    /// Matches the dimensions, orientation or bit depth read from image
+    /// headers.
+    fn evaluate_image_filter(
+        &mut self,
+        filter: &Filter,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let predicate = ImagePredicate::parse(filter)?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        Ok(self.nodes_with_image_info(nodes, |info| predicate.matches(info), token))
+    }
+
+    /// The caller folds `needle` with `fold_case` when `case_insensitive` is
+    /// set.
     fn node_content_matches(
         &self,
         path: &Path,
@@ -909,47 +1490,27 @@ impl SearchCache {
             return Some(false);
         };
 
+        if case_insensitive {
+            return content_matches_folded(&mut file, needle, token);
+        }
+
         if needle.len() == 1 {
             let needle = needle[0];
             let mut buffer = vec![0u8; CONTENT_BUFFER_BYTES];
-            if case_insensitive {
-                let lowercase_target = needle.to_ascii_lowercase();
-                let uppercase_target = needle.to_ascii_uppercase();
-                loop {
-                    token.is_cancelled()?;
-                    let read = match file.read(&mut buffer) {
-                        Ok(0) => break,
-                        Ok(count) => count,
-                        Err(_) => return Some(false),
-                    };
-                    if buffer[..read]
-                        .iter()
-                        .any(|&c| c == lowercase_target || c == uppercase_target)
-                    {
-                        return Some(true);
-                    }
-                }
-            } else {
-                loop {
-                    token.is_cancelled()?;
-                    let read = match file.read(&mut buffer) {
-                        Ok(0) => break,
-                        Ok(count) => count,
-                        Err(_) => return Some(false),
-                    };
-                    if buffer[..read].contains(&needle) {
-                        return Some(true);
-                    }
+            loop {
+                token.is_cancelled()?;
+                let read = match file.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(count) => count,
+                    Err(_) => return Some(false),
+                };
+                if buffer[..read].contains(&needle) {
+                    return Some(true);
                 }
-            };
-
+            }
             return Some(false);
         }
 
-        // ensure needle is lowercased if case_insensitive is set
-        if case_insensitive {
-            debug_assert_eq!(needle, needle.to_ascii_lowercase());
-        }
         let overlap = needle.len().saturating_sub(1);
         let finder = rabinkarp::Finder::new(needle);
         let mut buffer = vec![0u8; CONTENT_BUFFER_BYTES + overlap];
@@ -968,10 +1529,6 @@ impl SearchCache {
             let chunk_len = carry_len + read;
             let chunk = &mut buffer[..chunk_len];
 
-            if case_insensitive {
-                chunk[carry_len..].make_ascii_lowercase();
-            }
-
             if finder.find(chunk, needle).is_some() {
                 return Some(true);
             }
@@ -996,7 +1553,12 @@ impl SearchCache {
     ) -> Option<bool> {
         token.is_cancelled()?;
 
-        let tags = read_tags_from_path(path, case_insensitive)?;
+        let mut tags = read_tags_from_path(path, false)?;
+        if case_insensitive {
+            for tag in &mut tags {
+                *tag = fold_case(tag);
+            }
+        }
         let matched = tags
             .iter()
             .any(|tag| needles.iter().any(|needle| tag.contains(needle)));
@@ -1031,11 +1593,12 @@ impl SearchCache {
         match field {
             DateField::Modified => meta.mtime(),
             DateField::Created => meta.ctime(),
+            DateField::Accessed => meta.atime(),
         }
         .map(|value| value.get() as i64)
     }
 
-    fn ensure_metadata(&mut self, index: SlabIndex) -> SlabNodeMetadataCompact {
+    pub(crate) fn ensure_metadata(&mut self, index: SlabIndex) -> SlabNodeMetadataCompact {
         let current = self.file_nodes[index].metadata;
         if current.is_some() {
             return current;
@@ -1076,7 +1639,7 @@ fn normalize_extension(raw: &str) -> Option<String> {
     if trimmed.is_empty() {
         None
     } else {
-        Some(trimmed.to_ascii_lowercase())
+        Some(fold_case(trimmed))
     }
 }
 
@@ -1085,7 +1648,52 @@ fn extension_of(name: &str) -> Option<String> {
     if pos + 1 >= name.len() {
         return None;
     }
-    Some(name[pos + 1..].to_ascii_lowercase())
+    Some(fold_case(&name[pos + 1..]))
+}
+
+/// Case-insensitive content search: the file is folded chunk by chunk and
+/// searched for the already folded `needle`. Folding can change the length
+/// of the text, so the overlap between chunks is kept in folded bytes.
+fn content_matches_folded(
+    file: &mut File,
+    needle: &[u8],
+    token: CancellationToken,
+) -> Option<bool> {
+    let overlap = needle.len().saturating_sub(1);
+    let finder = rabinkarp::Finder::new(needle);
+    let mut buffer = vec![0u8; CONTENT_BUFFER_BYTES];
+    let mut folded = Vec::with_capacity(CONTENT_BUFFER_BYTES + overlap);
+    // Bytes of a character split by the previous read.
+    let mut pending = 0usize;
+
+    loop {
+        token.is_cancelled()?;
+
+        let Ok(read) = file.read(&mut buffer[pending..]) else {
+            return Some(false);
+        };
+        let chunk = &buffer[..pending + read];
+        if read == 0 {
+            if pending == 0 {
+                break;
+            }
+            // A character cut off by the end of the file is kept as raw bytes.
+            folded.extend_from_slice(chunk);
+            return Some(finder.find(&folded, needle).is_some());
+        }
+        let used = fold_utf8(chunk, &mut folded);
+
+        if finder.find(&folded, needle).is_some() {
+            return Some(true);
+        }
+
+        let keep = overlap.min(folded.len());
+        folded.drain(..folded.len() - keep);
+        buffer.copy_within(used..pending + read, 0);
+        pending = pending + read - used;
+    }
+
+    Some(false)
 }
 
 fn dedup_indices_in_place(indices: &mut Vec<SlabIndex>) {
@@ -1170,6 +1778,7 @@ const EXECUTABLE_EXTENSIONS: &[&str] = &[
 enum DateField {
     Modified,
     Created,
+    Accessed,
 }
 
 struct DateContext {
@@ -1448,6 +2057,382 @@ fn parse_absolute_date(raw: &str) -> Option<Date> {
     None
 }
 
+/// What `dupe:` and its siblings group nodes by.
+#[derive(Clone, Copy)]
+enum DuplicateKey {
+    Name,
+    NamePart,
+    Size,
+    DateModified,
+    Attributes,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum DuplicateValue {
+    Text(Cow<'static, str>),
+    Number(i64),
+    Attributes {
+        file_type: u8,
+        permissions: u32,
+        hidden: bool,
+    },
+}
+
+/// `dupe:content` compares contents rather than names; quote it
+/// (`dupe:"content"`) to look for duplicates of files named `content`.
+fn is_content_keyword(argument: Option<&FilterArgument>) -> bool {
+    argument.is_some_and(|argument| {
+        matches!(argument.kind, ArgumentKind::Bare) && argument.raw.eq_ignore_ascii_case("content")
+    })
+}
+
+/// The name without its last extension; dotfiles like `.bashrc` keep their name.
+fn name_part(name: &'static str) -> &'static str {
+    match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => name,
+    }
+}
+
+/// Unix attributes understood by `attrib:`, one letter each.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Attribute {
+    Hidden,
+    ReadOnly,
+    Executable,
+    Symlink,
+    Directory,
+    Setuid,
+    Setgid,
+    Sticky,
+}
+
+impl Attribute {
+    const LETTERS: &str = "h, r, x, l, d, u, g, t";
+
+    fn from_letter(letter: char) -> Option<Self> {
+        match letter.to_ascii_lowercase() {
+            'h' => Some(Attribute::Hidden),
+            'r' => Some(Attribute::ReadOnly),
+            'x' => Some(Attribute::Executable),
+            'l' => Some(Attribute::Symlink),
+            'd' => Some(Attribute::Directory),
+            'u' => Some(Attribute::Setuid),
+            'g' => Some(Attribute::Setgid),
+            't' => Some(Attribute::Sticky),
+            _ => None,
+        }
+    }
+
+    /// Only folders are known without metadata, the others may need an lstat.
+    fn needs_metadata(self) -> bool {
+        self != Attribute::Directory
+    }
+}
+
+/// Parses `attrib:` letters; a node has to carry all of them.
+fn parse_attributes(raw: &str) -> Result<Vec<Attribute>> {
+    let mut attributes = Vec::new();
+    for letter in raw.trim().chars() {
+        let Some(attribute) = Attribute::from_letter(letter) else {
+            bail!(
+                "attrib: unknown attribute {letter:?}, expected any of {}",
+                Attribute::LETTERS
+            );
+        };
+        if !attributes.contains(&attribute) {
+            attributes.push(attribute);
+        }
+    }
+    if attributes.is_empty() {
+        bail!("attrib: requires attribute letters");
+    }
+    // Check the attributes that need no metadata first, so `all` can stop early.
+    attributes.sort_by_key(|attribute| attribute.needs_metadata());
+    Ok(attributes)
+}
+
+/// The options a modifier such as `case:` evaluates its operand with.
+fn modified_options(kind: &FilterKind, mut options: SearchOptions) -> Result<SearchOptions> {
+    match kind {
+        FilterKind::CaseSensitive => options.case_insensitive = false,
+        FilterKind::WholeFilename => options.whole_filename = true,
+        FilterKind::NoWholeFilename => options.whole_filename = false,
+        FilterKind::WholeWord => options.whole_word = true,
+        FilterKind::NoWholeWord => options.whole_word = false,
+        FilterKind::StartWith => options.start_with = true,
+        FilterKind::EndWith => options.end_with = true,
+        FilterKind::Diacritics => options.ignore_diacritics = false,
+        FilterKind::NoDiacritics => options.ignore_diacritics = true,
+        _ => bail!("{kind:?} is not a modifier"),
+    }
+    Ok(options)
+}
+
+fn modifier_name(kind: &FilterKind) -> &'static str {
+    match kind {
+        FilterKind::CaseSensitive => "case",
+        FilterKind::WholeFilename => "wfn",
+        FilterKind::NoWholeFilename => "nowholefilename",
+        FilterKind::WholeWord => "ww",
+        FilterKind::NoWholeWord => "nowholeword",
+        FilterKind::StartWith => "startwith",
+        FilterKind::EndWith => "endwith",
+        FilterKind::Diacritics => "diacritics",
+        FilterKind::NoDiacritics => "nodiacritics",
+        _ => "modifier",
+    }
+}
+
+/// The folder named by `parent:`, `infolder:` or `nosubfolders:` isn't
+/// indexed.
+#[derive(Debug)]
+pub(crate) struct ScopeNotFound {
+    filter: &'static str,
+    path: String,
+}
+
+impl ScopeNotFound {
+    fn new(filter: &'static str, argument: &FilterArgument) -> Self {
+        Self {
+            filter,
+            path: argument.raw.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ScopeNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} filter {:?} is not found in file system",
+            self.filter, self.path
+        )
+    }
+}
+
+impl std::error::Error for ScopeNotFound {}
+
+#[derive(Clone, Copy)]
+enum ChildScope {
+    All,
+    Files,
+    Folders,
+}
+
+impl ChildScope {
+    fn filter_name(self) -> &'static str {
+        match self {
+            ChildScope::All => "childcount",
+            ChildScope::Files => "childfilecount",
+            ChildScope::Folders => "childfoldercount",
+        }
+    }
+
+    fn counts(self, file_type: NodeFileType) -> bool {
+        match self {
+            ChildScope::All => true,
+            ChildScope::Files => file_type != NodeFileType::Dir,
+            ChildScope::Folders => file_type == NodeFileType::Dir,
+        }
+    }
+}
+
+/// Plain integer comparisons and `..` ranges, e.g. `childcount:>1000` or
+/// `year:1997..2000`.
+enum CountPredicate {
+    Comparison { op: ComparisonOp, value: u64 },
+    Range { min: Option<u64>, max: Option<u64> },
+}
+
+impl CountPredicate {
+    fn parse(name: &str, argument: &FilterArgument) -> Result<Self> {
+        let number = |raw: &str| {
+            raw.trim()
+                .parse::<u64>()
+                .map_err(|_| anyhow!("{name}: {raw:?} is not a whole number"))
+        };
+        match &argument.kind {
+            ArgumentKind::Comparison(comp) => Ok(CountPredicate::Comparison {
+                op: comp.op,
+                value: number(&comp.value)?,
+            }),
+            ArgumentKind::Range(range) => {
+                if range.separator != RangeSeparator::Dots {
+                    bail!("{name}: only .. ranges are supported");
+                }
+                let min = range.start.as_deref().map(number).transpose()?;
+                let max = range.end.as_deref().map(number).transpose()?;
+                if let (Some(min), Some(max)) = (min, max)
+                    && min > max
+                {
+                    bail!("{name}: range start must be less than or equal to the end");
+                }
+                Ok(CountPredicate::Range { min, max })
+            }
+            ArgumentKind::List(_) => bail!("{name}: lists are not supported"),
+            ArgumentKind::Bare | ArgumentKind::Phrase => Ok(CountPredicate::Comparison {
+                op: ComparisonOp::Eq,
+                value: number(&argument.raw)?,
+            }),
+        }
+    }
+
+    fn matches(&self, count: u64) -> bool {
+        match *self {
+            CountPredicate::Comparison { op, value } => match op {
+                ComparisonOp::Lt => count < value,
+                ComparisonOp::Lte => count <= value,
+                ComparisonOp::Gt => count > value,
+                ComparisonOp::Gte => count >= value,
+                ComparisonOp::Eq => count == value,
+                ComparisonOp::Ne => count != value,
+            },
+            CountPredicate::Range { min, max } => {
+                min.is_none_or(|min| count >= min) && max.is_none_or(|max| count <= max)
+            }
+        }
+    }
+}
+
+enum ImagePredicate {
+    Width(CountPredicate),
+    Height(CountPredicate),
+    BitDepth(CountPredicate),
+    Dimensions(DimensionsPredicate),
+    Orientation(Vec<Orientation>),
+}
+
+impl ImagePredicate {
+    fn parse(filter: &Filter) -> Result<Self> {
+        let name = match filter.kind {
+            FilterKind::Width => "width",
+            FilterKind::Height => "height",
+            FilterKind::Dimensions => "dimensions",
+            FilterKind::Orientation => "orientation",
+            _ => "bitdepth",
+        };
+        let argument = filter
+            .argument
+            .as_ref()
+            .filter(|argument| !argument.raw.trim().is_empty())
+            .ok_or_else(|| anyhow!("{name}: requires a value"))?;
+        Ok(match filter.kind {
+            FilterKind::Width => ImagePredicate::Width(CountPredicate::parse(name, argument)?),
+            FilterKind::Height => ImagePredicate::Height(CountPredicate::parse(name, argument)?),
+            FilterKind::Dimensions => {
+                ImagePredicate::Dimensions(DimensionsPredicate::parse(argument)?)
+            }
+            FilterKind::Orientation => {
+                let values: Vec<&str> = match &argument.kind {
+                    ArgumentKind::Bare | ArgumentKind::Phrase => vec![argument.raw.as_str()],
+                    ArgumentKind::List(values) => values.iter().map(String::as_str).collect(),
+                    ArgumentKind::Range(_) | ArgumentKind::Comparison(_) => {
+                        bail!("orientation: does not support ranges or comparisons");
+                    }
+                };
+                let orientations = values
+                    .into_iter()
+                    .map(|value| match value.trim().to_ascii_lowercase().as_str() {
+                        "landscape" | "horizontal" => Ok(Orientation::Landscape),
+                        "portrait" | "vertical" => Ok(Orientation::Portrait),
+                        "square" => Ok(Orientation::Square),
+                        _ => Err(anyhow!(
+                            "orientation: unknown value {value:?}, expected landscape, portrait or square"
+                        )),
+                    })
+                    .collect::<Result<_>>()?;
+                ImagePredicate::Orientation(orientations)
+            }
+            _ => ImagePredicate::BitDepth(CountPredicate::parse(name, argument)?),
+        })
+    }
+
+    fn matches(&self, info: &ImageInfo) -> bool {
+        match self {
+            ImagePredicate::Width(predicate) => predicate.matches(u64::from(info.width)),
+            ImagePredicate::Height(predicate) => predicate.matches(u64::from(info.height)),
+            ImagePredicate::BitDepth(predicate) => info
+                .bit_depth
+                .is_some_and(|bits| predicate.matches(u64::from(bits))),
+            ImagePredicate::Dimensions(predicate) => predicate.matches(info.width, info.height),
+            ImagePredicate::Orientation(orientations) => orientations.contains(&info.orientation()),
+        }
+    }
+}
+
+/// `WxH` sizes for `dimensions:`, compared side by side: `>=1920x1080` needs
+/// both the width and the height to be large enough.
+struct DimensionsPredicate {
+    width: CountPredicate,
+    height: CountPredicate,
+    /// `!=WxH` matches every other size, including ones sharing a side.
+    negated: bool,
+}
+
+impl DimensionsPredicate {
+    fn parse(argument: &FilterArgument) -> Result<Self> {
+        let size = |raw: &str| -> Result<(u64, u64)> {
+            let invalid = || anyhow!("dimensions: {raw:?} is not a size like 1920x1080");
+            let (width, height) = raw.trim().split_once(['x', 'X', '×']).ok_or_else(invalid)?;
+            let width = width.trim().parse().map_err(|_| invalid())?;
+            let height = height.trim().parse().map_err(|_| invalid())?;
+            Ok((width, height))
+        };
+        let sides = |op: ComparisonOp, (width, height): (u64, u64)| {
+            (
+                CountPredicate::Comparison { op, value: width },
+                CountPredicate::Comparison { op, value: height },
+            )
+        };
+        let mut negated = false;
+        let (width, height) = match &argument.kind {
+            ArgumentKind::Comparison(comp) if comp.op == ComparisonOp::Ne => {
+                negated = true;
+                sides(ComparisonOp::Eq, size(&comp.value)?)
+            }
+            ArgumentKind::Comparison(comp) => sides(comp.op, size(&comp.value)?),
+            ArgumentKind::Range(range) => {
+                if range.separator != RangeSeparator::Dots {
+                    bail!("dimensions: only .. ranges are supported");
+                }
+                let min = range.start.as_deref().map(size).transpose()?;
+                let max = range.end.as_deref().map(size).transpose()?;
+                if let (Some(min), Some(max)) = (min, max)
+                    && (min.0 > max.0 || min.1 > max.1)
+                {
+                    bail!("dimensions: range start must be less than or equal to the end");
+                }
+                (
+                    CountPredicate::Range {
+                        min: min.map(|min| min.0),
+                        max: max.map(|max| max.0),
+                    },
+                    CountPredicate::Range {
+                        min: min.map(|min| min.1),
+                        max: max.map(|max| max.1),
+                    },
+                )
+            }
+            ArgumentKind::List(_) => bail!("dimensions: lists are not supported"),
+            ArgumentKind::Bare | ArgumentKind::Phrase => {
+                sides(ComparisonOp::Eq, size(&argument.raw)?)
+            }
+        };
+        Ok(Self {
+            width,
+            height,
+            negated,
+        })
+    }
+
+    fn matches(&self, width: u32, height: u32) -> bool {
+        let matched =
+            self.width.matches(u64::from(width)) && self.height.matches(u64::from(height));
+        matched != self.negated
+    }
+}
+
 struct SizePredicate {
     kind: SizePredicateKind,
 }
@@ -1637,6 +2622,14 @@ fn size_unit_multiplier(unit: &str) -> Result<u64> {
     Ok(multiplier)
 }
 
+fn build_regex(pattern: &str, options: SearchOptions) -> Result<regex::Regex> {
+    let mut builder = RegexBuilder::new(pattern);
+    builder.case_insensitive(options.case_insensitive);
+    builder
+        .build()
+        .map_err(|err| anyhow!("Invalid regex pattern: {err}"))
+}
+
 fn filter_nodes(
     nodes: Vec<SlabIndex>,
     token: CancellationToken,
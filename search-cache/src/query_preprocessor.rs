@@ -19,6 +19,7 @@ fn expand_expr(expr: Expr, home: &str) -> Expr {
         Expr::Empty => Expr::Empty,
         Expr::Term(term) => Expr::Term(expand_term(term, home)),
         Expr::Not(inner) => Expr::Not(Box::new(expand_expr(*inner, home))),
+        Expr::Modified(kind, inner) => Expr::Modified(kind, Box::new(expand_expr(*inner, home))),
         Expr::And(parts) => Expr::And(
             parts
                 .into_iter()
@@ -83,6 +84,7 @@ fn strip_expr_quotes(expr: Expr) -> Expr {
         Expr::Empty => Expr::Empty,
         Expr::Term(term) => Expr::Term(strip_term_quotes(term)),
         Expr::Not(inner) => Expr::Not(Box::new(strip_expr_quotes(*inner))),
+        Expr::Modified(kind, inner) => Expr::Modified(kind, Box::new(strip_expr_quotes(*inner))),
         Expr::And(parts) => Expr::And(parts.into_iter().map(strip_expr_quotes).collect()),
         Expr::Or(parts) => Expr::Or(parts.into_iter().map(strip_expr_quotes).collect()),
     }
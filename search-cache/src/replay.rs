@@ -0,0 +1,53 @@
+use crate::{HandleFSEError, SearchCache};
+use anyhow::{Context, Result};
+use cardinal_sdk::{EventLogReader, FsEvent, SourceKind, rebase_path};
+use std::io::BufRead;
+use tracing::info;
+
+/// What `SearchCache::replay_event_log` fed into the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    pub batches: usize,
+    pub events: usize,
+    /// Batches that returned `HandleFSEError::Rescan` and were answered with a rescan.
+    pub rescans: usize,
+}
+
+impl SearchCache {
+    /// Feed a log recorded by `was record` into `handle_source_events`, batch by batch.
+    ///
+    /// Event paths under the recorded root are rebased onto this cache's root,
+    /// so a capture from a user's machine can be replayed against a fixture
+    /// tree. Recorded timing is ignored to keep replays deterministic. A
+    /// `HandleFSEError::Rescan` is answered with `rescan()`, like the app does.
+    pub fn replay_event_log<R: BufRead>(
+        &mut self,
+        log: EventLogReader<R>,
+    ) -> Result<ReplaySummary> {
+        let recorded_root = log.header().root.clone();
+        let root = self.file_nodes.path().to_path_buf();
+        let mut summary = ReplaySummary::default();
+        for batch in log {
+            let batch = batch.context("Read event log batch failed.")?;
+            let events: Vec<FsEvent> = batch
+                .events
+                .into_iter()
+                .map(|event| {
+                    let mut event = FsEvent::from(event);
+                    event.path = rebase_path(&event.path, &recorded_root, &root);
+                    event
+                })
+                .collect();
+            summary.batches += 1;
+            summary.events += events.len();
+            if let Err(HandleFSEError::Rescan) =
+                self.handle_source_events(SourceKind::Replay, events)
+            {
+                info!("Replayed batch {} requested a rescan", summary.batches);
+                self.rescan();
+                summary.rescans += 1;
+            }
+        }
+        Ok(summary)
+    }
+}
@@ -1,9 +1,23 @@
-use query_segmentation::{Segment, SegmentConcrete};
+use crate::diacritics::strip_diacritics;
+use query_segmentation::{Segment, SegmentConcrete, query_segmentation};
 use regex::{Regex, RegexBuilder};
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct SearchOptions {
     pub case_insensitive: bool,
+    /// Words must equal the whole file name instead of matching part of it,
+    /// and slashes no longer split them into path segments (`wfn:`).
+    pub whole_filename: bool,
+    /// Words only match where a word of the file name starts and ends
+    /// (`ww:`), see [`is_word_boundary`].
+    pub whole_word: bool,
+    /// The file name has to start with the word (`startwith:`).
+    pub start_with: bool,
+    /// The file name has to end with the word (`endwith:`).
+    pub end_with: bool,
+    /// Accents and other combining marks are ignored when comparing names
+    /// (`nodiacritics:`).
+    pub ignore_diacritics: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -23,8 +37,21 @@ pub(crate) enum SegmentMatcher {
 
 #[derive(Clone, Debug)]
 pub(crate) enum SegmentMatcherConcrete {
-    Plain { kind: SegmentKind, needle: String },
-    Regex { regex: Regex },
+    Plain {
+        kind: SegmentKind,
+        needle: String,
+    },
+    Regex {
+        regex: Regex,
+    },
+    /// `regex` finds the needle, and an occurrence only counts when both of
+    /// its ends sit on a word boundary of the name.
+    WholeWord {
+        regex: Regex,
+    },
+    /// Built from the needle with its diacritics stripped, and run against
+    /// stripped names.
+    IgnoreDiacritics(Box<SegmentMatcherConcrete>),
 }
 
 impl SegmentMatcherConcrete {
@@ -37,8 +64,48 @@ impl SegmentMatcherConcrete {
                 SegmentKind::Exact => candidate == needle,
             },
             SegmentMatcherConcrete::Regex { regex } => regex.is_match(candidate),
+            SegmentMatcherConcrete::WholeWord { regex } => matches_whole_word(regex, candidate),
+            SegmentMatcherConcrete::IgnoreDiacritics(inner) => {
+                inner.matches(&strip_diacritics(candidate))
+            }
+        }
+    }
+}
+
+fn matches_whole_word(regex: &Regex, candidate: &str) -> bool {
+    let mut start = 0;
+    while let Some(found) = regex.find_at(candidate, start) {
+        if is_word_boundary(candidate, found.start()) && is_word_boundary(candidate, found.end()) {
+            return true;
         }
+        // Occurrences may overlap (`aa` in `aaa`), so retry one char later.
+        let Some(ch) = candidate[found.start()..].chars().next() else {
+            return false;
+        };
+        start = found.start() + ch.len_utf8();
+    }
+    false
+}
+
+/// Whether a word of `name` starts or ends at byte offset `at`. Words are
+/// split by anything that isn't a letter or digit (`_`, `-`, `.`, space, …),
+/// by camelCase steps (`logFile`, and `XMLParser` before the `P`) and by
+/// changes between digits and letters (`v2`).
+fn is_word_boundary(name: &str, at: usize) -> bool {
+    let mut after = name[at..].chars();
+    let (Some(prev), Some(next)) = (name[..at].chars().next_back(), after.next()) else {
+        return true;
+    };
+    if !prev.is_alphanumeric() || !next.is_alphanumeric() {
+        return true;
+    }
+    if prev.is_numeric() != next.is_numeric() {
+        return true;
+    }
+    if prev.is_lowercase() && next.is_uppercase() {
+        return true;
     }
+    prev.is_uppercase() && next.is_uppercase() && after.next().is_some_and(char::is_lowercase)
 }
 
 fn wildcard_to_regex(pattern: &str) -> String {
@@ -59,6 +126,38 @@ fn wildcard_to_regex(pattern: &str) -> String {
     regex
 }
 
+/// Splits a search term into path segments. Under `whole_filename` the term
+/// is a single name that has to match exactly, slashes included.
+/// `start_with` and `end_with` anchor the last segment, which is the one
+/// matched against the file name.
+pub(crate) fn term_segments(text: &str, options: SearchOptions) -> Vec<Segment<'_>> {
+    let mut segments = if options.whole_filename {
+        if text.is_empty() {
+            return Vec::new();
+        }
+        vec![Segment::Concrete(SegmentConcrete::Exact(text))]
+    } else {
+        query_segmentation(text)
+    };
+    if let Some(Segment::Concrete(last)) = segments.last_mut() {
+        *last = anchor_segment(*last, options);
+    }
+    segments
+}
+
+fn anchor_segment(segment: SegmentConcrete<'_>, options: SearchOptions) -> SegmentConcrete<'_> {
+    let value = segment_value(&segment);
+    let kind = segment_kind(&segment);
+    let starts = options.start_with || matches!(kind, SegmentKind::Prefix | SegmentKind::Exact);
+    let ends = options.end_with || matches!(kind, SegmentKind::Suffix | SegmentKind::Exact);
+    match (starts, ends) {
+        (false, false) => SegmentConcrete::Substr(value),
+        (true, false) => SegmentConcrete::Prefix(value),
+        (false, true) => SegmentConcrete::Suffix(value),
+        (true, true) => SegmentConcrete::Exact(value),
+    }
+}
+
 pub(crate) fn build_segment_matchers(
     segments: &[Segment<'_>],
     options: SearchOptions,
@@ -68,19 +167,31 @@ pub(crate) fn build_segment_matchers(
         .map(|segment| match segment {
             Segment::GlobStar => Ok(SegmentMatcher::GlobStar),
             Segment::Star => Ok(SegmentMatcher::Star),
-            Segment::Concrete(concrete) => build_concrete_segment_matcher(concrete, options),
+            Segment::Concrete(concrete) => {
+                let kind = segment_kind(concrete);
+                let value = segment_value(concrete);
+                let matcher = if options.ignore_diacritics {
+                    let inner =
+                        build_concrete_segment_matcher(kind, &strip_diacritics(value), options)?;
+                    SegmentMatcherConcrete::IgnoreDiacritics(Box::new(inner))
+                } else {
+                    build_concrete_segment_matcher(kind, value, options)?
+                };
+                Ok(SegmentMatcher::Concrete(matcher))
+            }
         })
         .collect()
 }
 
 fn build_concrete_segment_matcher(
-    segment: &SegmentConcrete<'_>,
+    kind: SegmentKind,
+    value: &str,
     options: SearchOptions,
-) -> Result<SegmentMatcher, regex::Error> {
-    let kind = segment_kind(segment);
-    let value = segment_value(segment);
+) -> Result<SegmentMatcherConcrete, regex::Error> {
     let is_wildcard = value.contains('*') || value.contains('?');
-    if options.case_insensitive || is_wildcard {
+    // Wildcards and exact segments already have to cover the whole name.
+    let whole_word = options.whole_word && !is_wildcard && !matches!(kind, SegmentKind::Exact);
+    if options.case_insensitive || is_wildcard || whole_word {
         let pattern = if is_wildcard {
             // Wildcard pattern is /exact/ by default, so we don't need to
             // adjust it based on SegmentKind.
@@ -96,14 +207,18 @@ fn build_concrete_segment_matcher(
         };
         let mut builder = RegexBuilder::new(&pattern);
         builder.case_insensitive(options.case_insensitive);
-        builder
-            .build()
-            .map(|regex| SegmentMatcher::Concrete(SegmentMatcherConcrete::Regex { regex }))
+        builder.build().map(|regex| {
+            if whole_word {
+                SegmentMatcherConcrete::WholeWord { regex }
+            } else {
+                SegmentMatcherConcrete::Regex { regex }
+            }
+        })
     } else {
-        Ok(SegmentMatcher::Concrete(SegmentMatcherConcrete::Plain {
+        Ok(SegmentMatcherConcrete::Plain {
             kind,
             needle: value.to_string(),
-        }))
+        })
     }
 }
 
@@ -129,7 +244,7 @@ fn segment_value<'s>(segment: &SegmentConcrete<'s>) -> &'s str {
 mod tests {
     use super::{
         SearchOptions, SegmentKind, SegmentMatcher, SegmentMatcherConcrete, build_segment_matchers,
-        segment_kind, segment_value, wildcard_to_regex,
+        is_word_boundary, segment_kind, segment_value, term_segments, wildcard_to_regex,
     };
     use query_segmentation::{Segment, SegmentConcrete};
 
@@ -243,6 +358,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         assert_eq!(matchers.len(), 4);
@@ -270,6 +386,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         assert_eq!(matchers.len(), 4);
@@ -295,6 +412,7 @@ mod tests {
         let segments = [Segment::exact("foo*bar?baz")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         assert_eq!(matchers.len(), 1);
@@ -311,6 +429,7 @@ mod tests {
         let segments = [Segment::substr("A*B")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         match &matchers[0] {
@@ -327,6 +446,7 @@ mod tests {
         let segments = [Segment::substr("A*B")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         match &matchers[0] {
@@ -387,6 +507,7 @@ mod tests {
         let segments = [Segment::substr("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -402,6 +523,7 @@ mod tests {
         let segments = [Segment::prefix("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -418,6 +540,7 @@ mod tests {
         let segments = [Segment::suffix("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -434,6 +557,7 @@ mod tests {
         let segments = [Segment::exact("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -457,6 +581,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         assert_eq!(matchers.len(), 4);
@@ -488,6 +613,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         for m in matchers {
@@ -505,6 +631,7 @@ mod tests {
         let segments = [Segment::exact("a+b*(c?)")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -525,6 +652,7 @@ mod tests {
         let segments = [Segment::substr("Café")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -540,6 +668,7 @@ mod tests {
         let segments = [Segment::exact("Café")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -562,6 +691,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         assert_eq!(matchers.len(), 3);
@@ -594,6 +724,7 @@ mod tests {
         let segments = [Segment::exact(&long)];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         assert_eq!(matchers.len(), 1);
@@ -612,6 +743,7 @@ mod tests {
         let segments = [Segment::exact("a*b*c?d")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -630,6 +762,7 @@ mod tests {
         let segments = [Segment::substr("mid")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -647,6 +780,101 @@ mod tests {
         }
     }
 
+    // --- whole word matching ---
+
+    #[test]
+    fn word_boundaries_follow_separators_camel_case_and_digits() {
+        assert!(is_word_boundary("a_b", 1));
+        assert!(is_word_boundary("a b", 2));
+        assert!(is_word_boundary("logFile", 3));
+        assert!(is_word_boundary("XMLParser", 3));
+        assert!(!is_word_boundary("XMLParser", 2));
+        assert!(is_word_boundary("v2", 1));
+        assert!(is_word_boundary("abc", 0));
+        assert!(is_word_boundary("abc", 3));
+        assert!(!is_word_boundary("catalog", 4));
+    }
+
+    #[test]
+    fn whole_word_matcher_skips_partial_words() {
+        let segments = [Segment::substr("log")];
+        let opts = SearchOptions {
+            case_insensitive: true,
+            whole_word: true,
+            ..Default::default()
+        };
+        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let SegmentMatcher::Concrete(matcher @ SegmentMatcherConcrete::WholeWord { .. }) =
+            &matchers[0]
+        else {
+            panic!("Expected whole word matcher");
+        };
+        for name in [
+            "log",
+            "app.log",
+            "error_log.txt",
+            "ChangeLog",
+            "logFile",
+            "log2",
+        ] {
+            assert!(matcher.matches(name), "{name}");
+        }
+        for name in ["catalog", "blog", "dialog.txt", "logs", "changelog"] {
+            assert!(!matcher.matches(name), "{name}");
+        }
+        // The first occurrence is inside a word, the second one is not.
+        assert!(matcher.matches("catalog-log"));
+    }
+
+    #[test]
+    fn whole_word_leaves_wildcards_and_exact_segments_alone() {
+        let segments = [Segment::substr("log*"), Segment::exact("log")];
+        let opts = SearchOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        assert!(matches!(
+            matchers[0],
+            SegmentMatcher::Concrete(SegmentMatcherConcrete::Regex { .. })
+        ));
+        assert!(matches!(
+            matchers[1],
+            SegmentMatcher::Concrete(SegmentMatcherConcrete::Plain { .. })
+        ));
+    }
+
+    // --- startwith / endwith anchoring ---
+
+    #[test]
+    fn anchors_apply_to_the_last_segment() {
+        let start = SearchOptions {
+            start_with: true,
+            ..Default::default()
+        };
+        let end = SearchOptions {
+            end_with: true,
+            ..Default::default()
+        };
+        let both = SearchOptions {
+            start_with: true,
+            end_with: true,
+            ..Default::default()
+        };
+        assert_eq!(term_segments("img", start), [Segment::prefix("img")]);
+        assert_eq!(term_segments(".tgz", end), [Segment::suffix(".tgz")]);
+        assert_eq!(term_segments("report", both), [Segment::exact("report")]);
+        assert_eq!(term_segments("docs/", start), [Segment::exact("docs")]);
+        assert_eq!(
+            term_segments("src/main", end),
+            [Segment::suffix("src"), Segment::exact("main")]
+        );
+        assert_eq!(
+            term_segments("src/**", end),
+            [Segment::suffix("src"), Segment::GlobStar]
+        );
+    }
+
     fn expect_concrete<'a>(segment: &'a Segment<'a>) -> &'a SegmentConcrete<'a> {
         match segment {
             Segment::Concrete(concrete) => concrete,
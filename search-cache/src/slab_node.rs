@@ -120,6 +120,11 @@ impl SlabNode {
         self.metadata.state()
     }
 
+    /// Re-parent and rename the node, used when a rename moves it in place.
+    pub fn set_name_and_parent(&mut self, name: &'static str, parent: Option<SlabIndex>) {
+        self.name_and_parent = NameAndParent::new(name, OptionSlabIndex::from_option(parent));
+    }
+
     pub fn add_children(&mut self, children: SlabIndex) {
         if !self.children.contains(&children) {
             self.children.push(children);
@@ -158,8 +163,29 @@ impl<'a> SlabNodeMetadata<'a> {
     pub fn mtime(&self) -> Option<NonZeroU32> {
         NonZeroU32::new(self.0.mtime)
     }
+
+    /// Last access time as of the last scan of this node. Reads don't produce
+    /// change events, so it is refreshed by rescans rather than kept live.
+    pub fn atime(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.0.atime)
+    }
+
+    /// Permission bits of `st_mode`, including setuid, setgid and sticky.
+    pub fn permissions(&self) -> u32 {
+        u32::from(self.0.mode & MODE_PERMISSION_BITS)
+    }
+
+    /// Whether the file carries the `UF_HIDDEN` flag. Dot-prefixed names
+    /// are hidden too, but that is a property of the name.
+    pub fn has_hidden_flag(&self) -> bool {
+        self.0.mode & MODE_HIDDEN_FLAG != 0
+    }
 }
 
+const MODE_PERMISSION_BITS: u16 = 0o7777;
+/// Stored in the bit above the permission bits, which `st_mode` uses for the file type.
+const MODE_HIDDEN_FLAG: u16 = 1 << 15;
+
 /// Use a compact form so that
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct SlabNodeMetadataCompact {
@@ -167,6 +193,9 @@ pub struct SlabNodeMetadataCompact {
     // Actually a Option<NonZeroU32>, but using u32 here due to https://github.com/serde-rs/serde/issues/1834
     ctime: u32,
     mtime: u32,
+    atime: u32,
+    /// Permission bits plus `MODE_HIDDEN_FLAG`; the type lives in `state_type_and_size`.
+    mode: u16,
 }
 
 impl SlabNodeMetadataCompact {
@@ -175,6 +204,8 @@ impl SlabNodeMetadataCompact {
             state_type_and_size: StateTypeSize::unaccessible(),
             ctime: 0,
             mtime: 0,
+            atime: 0,
+            mode: 0,
         }
     }
 
@@ -184,6 +215,9 @@ impl SlabNodeMetadataCompact {
             size,
             ctime,
             mtime,
+            atime,
+            mode,
+            flags,
         }: fswalk::NodeMetadata,
     ) -> Self {
         Self {
@@ -196,6 +230,16 @@ impl SlabNodeMetadataCompact {
                 .and_then(|x| std::num::NonZeroU32::try_from(x).ok())
                 .map(|x| x.get())
                 .unwrap_or_default(),
+            atime: atime
+                .and_then(|x| std::num::NonZeroU32::try_from(x).ok())
+                .map(|x| x.get())
+                .unwrap_or_default(),
+            mode: (mode as u16 & MODE_PERMISSION_BITS)
+                | if flags & fswalk::UF_HIDDEN != 0 {
+                    MODE_HIDDEN_FLAG
+                } else {
+                    0
+                },
         }
     }
 
@@ -204,6 +248,8 @@ impl SlabNodeMetadataCompact {
             state_type_and_size: StateTypeSize::none(),
             ctime: 0,
             mtime: 0,
+            atime: 0,
+            mode: 0,
         }
     }
 
@@ -1,5 +1,5 @@
 use super::prelude::*;
-use cardinal_sdk::{EventFlag, FsEvent};
+use cardinal_sdk::{EventFlag, FsEvent, ResumeCursor, SourceKind};
 use std::sync::atomic::AtomicBool;
 
 static NEVER_STOPPED: AtomicBool = AtomicBool::new(false);
@@ -36,7 +36,7 @@ fn test_remove_node_path_nonexistent_returns_none() {
     // create then delete file and ensure second scan removal returns None
     let file = tmp.path().join("temp_remove.txt");
     fs::write(&file, b"x").unwrap();
-    let id = cache.last_event_id() + 1;
+    let id = cache.resume_cursor().event_id() + 1;
     cache
         .handle_fs_events(vec![FsEvent {
             path: file.clone(),
@@ -101,3 +101,42 @@ fn test_persistent_roundtrip() {
     .unwrap();
     assert_eq!(loaded.get_total_files(), original_total);
 }
+
+#[test]
+fn test_persistent_roundtrip_keeps_resume_cursor() {
+    let tmp = TempDir::new("persist_cursor").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    fs::write(tmp.path().join("polled.txt"), b"x").unwrap();
+    cache
+        .handle_source_events(
+            SourceKind::Polling,
+            vec![FsEvent {
+                path: tmp.path().to_path_buf(),
+                id: 7,
+                flag: EventFlag::ItemIsDir | EventFlag::ItemModified,
+            }],
+        )
+        .unwrap();
+    assert_eq!(
+        cache.resume_cursor(),
+        ResumeCursor::new(SourceKind::Polling, 7)
+    );
+
+    cache.flush_to_file(&cache_path).unwrap();
+    let loaded = SearchCache::try_read_persistent_cache(
+        tmp.path(),
+        &cache_path,
+        &Vec::new(),
+        &Vec::new(),
+        &NEVER_STOPPED,
+    )
+    .unwrap();
+    assert_eq!(
+        loaded.resume_cursor(),
+        ResumeCursor::new(SourceKind::Polling, 7)
+    );
+    assert_eq!(loaded.resume_event_id(SourceKind::Polling), 7);
+    // Another backend can't interpret polling ids and starts from now.
+    assert!(loaded.resume_event_id(SourceKind::Replay) > 7);
+}
@@ -1,6 +1,8 @@
 use super::{
     prelude::*,
-    support::{SECONDS_PER_DAY, assert_file_hits, set_file_times, ts_for_date},
+    support::{
+        SECONDS_PER_DAY, assert_file_hits, set_file_access_time, set_file_times, ts_for_date,
+    },
 };
 
 #[test]
@@ -73,3 +75,41 @@ fn date_filter_reuses_existing_and_base() {
         "date filter should not touch nodes excluded by earlier ext: filters",
     );
 }
+
+#[test]
+fn date_accessed_filter_finds_stale_files() {
+    let tmp = TempDir::new("date_accessed").unwrap();
+    fs::write(tmp.path().join("opened.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("stale.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let opened_idx = cache.search("opened.txt").unwrap()[0];
+    let stale_idx = cache.search("stale.txt").unwrap()[0];
+
+    let now = Timestamp::now().as_second();
+    set_file_access_time(&mut cache, opened_idx, now);
+    set_file_access_time(&mut cache, stale_idx, ts_for_date(2019, 3, 4));
+
+    let da_today = cache.search("da:today").unwrap();
+    assert_file_hits(&cache, &da_today, &["opened.txt"]);
+
+    let da_stale = cache.search("dateaccessed:<2020-01-01").unwrap();
+    assert_file_hits(&cache, &da_stale, &["stale.txt"]);
+
+    let da_range = cache.search("da:2019/01/01-2019/12/31").unwrap();
+    assert_file_hits(&cache, &da_range, &["stale.txt"]);
+
+    // Access times are tracked separately from modification times.
+    let dm_range = cache.search("ext:txt dm:2019/01/01-2019/12/31").unwrap();
+    assert!(dm_range.is_empty());
+}
+
+#[test]
+fn date_accessed_filter_reads_atime_from_disk() {
+    let tmp = TempDir::new("date_accessed_disk").unwrap();
+    fs::write(tmp.path().join("touched.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let hits = cache.search("da:today").unwrap();
+    assert_file_hits(&cache, &hits, &["touched.txt"]);
+}
@@ -16,6 +16,22 @@ pub(super) fn set_file_times(
         size: 0,
         ctime: NonZeroU64::new(created as u64),
         mtime: NonZeroU64::new(modified as u64),
+        atime: None,
+        mode: 0o644,
+        flags: 0,
+    };
+    cache.file_nodes[index].metadata = SlabNodeMetadataCompact::some(metadata);
+}
+
+pub(super) fn set_file_access_time(cache: &mut SearchCache, index: SlabIndex, accessed: i64) {
+    let metadata = NodeMetadata {
+        r#type: NodeFileType::File,
+        size: 0,
+        ctime: None,
+        mtime: None,
+        atime: NonZeroU64::new(accessed as u64),
+        mode: 0o644,
+        flags: 0,
     };
     cache.file_nodes[index].metadata = SlabNodeMetadataCompact::some(metadata);
 }
@@ -0,0 +1,110 @@
+//! Tests for the `startwith:` and `endwith:` modifiers.
+
+mod common;
+
+use common::{cache_with_names, insensitive, paths, query_with};
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    cache_with_names(
+        "anchor_modifier",
+        &[
+            "IMG_0001.jpg",
+            "my_img.jpg",
+            "backup.tar.gz",
+            "backup.tgz",
+            "tar.gz.txt",
+            "report.pdf",
+            "report_final.pdf",
+            "archive/2024.tar.gz",
+        ],
+    )
+}
+
+#[test]
+fn startwith_and_endwith_anchor_the_name() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "img", insensitive()),
+        paths(&["IMG_0001.jpg", "my_img.jpg"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "startwith:img", insensitive()),
+        paths(&["IMG_0001.jpg"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "endwith:img.jpg", insensitive()),
+        paths(&["my_img.jpg"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "startwith:endwith:report.pdf",
+            insensitive()
+        ),
+        paths(&["report.pdf"])
+    );
+    assert!(query_with(&mut cache, root, "case:startwith:img", insensitive()).is_empty());
+}
+
+#[test]
+fn endwith_accepts_a_list() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "endwith:.tar.gz;.tgz", insensitive()),
+        paths(&["archive/2024.tar.gz", "backup.tar.gz", "backup.tgz"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "backup endwith:.tar.gz;.tgz",
+            insensitive()
+        ),
+        paths(&["backup.tar.gz", "backup.tgz"])
+    );
+}
+
+#[test]
+fn anchors_apply_to_groups() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "startwith:<img|my>", insensitive()),
+        paths(&["IMG_0001.jpg", "my_img.jpg"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "endwith:(pdf !final.pdf)", insensitive()),
+        paths(&["report.pdf"])
+    );
+}
+
+#[test]
+fn anchored_words_still_produce_highlights() {
+    let (_tmp, mut cache) = build_cache();
+    let outcome = cache
+        .search_with_options(
+            "startwith:IMG endwith:.tar.gz;.tgz",
+            insensitive(),
+            CancellationToken::noop(),
+        )
+        .unwrap();
+    assert_eq!(outcome.highlights, vec![".tar.gz", ".tgz", "img"]);
+}
+
+#[test]
+fn anchors_without_an_operand_are_rejected() {
+    let (_tmp, mut cache) = build_cache();
+    for line in ["startwith:", "endwith:"] {
+        assert!(
+            cache
+                .search_with_options(line, insensitive(), CancellationToken::noop())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Tests for the attrib: filter on Unix mode bits, hidden names and types.
+
+mod common;
+
+use common::{paths, query};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{
+    fs::{self, Permissions},
+    os::unix::fs::{PermissionsExt, symlink},
+};
+use tempdir::TempDir;
+
+/// root/
+///   build.sh (0o755), notes.txt (0o644), locked.txt (0o444)
+///   .env (0o600), tool (0o4755)
+///   shared/ (0o1777)
+///   .config/settings.json
+///   link -> notes.txt
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("attrib_filter").unwrap();
+    let root = temp_dir.path();
+    let files = [
+        ("build.sh", 0o755),
+        ("notes.txt", 0o644),
+        ("locked.txt", 0o444),
+        (".env", 0o600),
+        ("tool", 0o4755),
+    ];
+    for (name, mode) in files {
+        let path = root.join(name);
+        fs::write(&path, b"x").unwrap();
+        fs::set_permissions(&path, Permissions::from_mode(mode)).unwrap();
+    }
+    fs::create_dir(root.join("shared")).unwrap();
+    fs::set_permissions(root.join("shared"), Permissions::from_mode(0o1777)).unwrap();
+    fs::create_dir(root.join(".config")).unwrap();
+    fs::write(root.join(".config/settings.json"), b"{}").unwrap();
+    symlink(root.join("notes.txt"), root.join("link")).unwrap();
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+#[test]
+fn attrib_executable_composes_with_ext() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:x"),
+        paths(&["build.sh", "tool"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:x ext:sh"),
+        paths(&["build.sh"])
+    );
+}
+
+#[test]
+fn attrib_hidden_matches_dot_names_and_negates() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:h"),
+        paths(&[".config", ".env"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT !attrib:h"),
+        paths(&[
+            ".config/settings.json",
+            "build.sh",
+            "link",
+            "locked.txt",
+            "notes.txt",
+            "shared",
+            "tool"
+        ])
+    );
+}
+
+#[test]
+fn attrib_covers_types_and_special_bits() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:r"),
+        paths(&["locked.txt"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:l"),
+        paths(&["link"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:d"),
+        paths(&[".config", "shared"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:u"),
+        paths(&["tool"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:T"),
+        paths(&["shared"])
+    );
+    // Every letter has to match.
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT attrib:DH"),
+        paths(&[".config"])
+    );
+}
+
+#[test]
+fn attrib_rejects_unknown_letters() {
+    let (_tmp, mut cache) = build_cache();
+    for line in ["attrib:", "attrib:q", "attrib:xz"] {
+        let result =
+            cache.search_with_options(line, SearchOptions::default(), CancellationToken::noop());
+        assert!(result.is_err(), "{line} should be rejected");
+    }
+}
@@ -0,0 +1,433 @@
+//! Tests for the audio tag filters: artist:, album:, title:, genre:, year:,
+//! track: and comment:, over hand-built ID3, FLAC, Ogg and MP4 files.
+
+mod common;
+
+use cardinal_sdk::{EventFlag, FsEvent};
+use common::{cache_with_files, paths, query_with};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    time::{Duration, UNIX_EPOCH},
+};
+use tempdir::TempDir;
+
+fn syncsafe(len: usize) -> [u8; 4] {
+    let len = len as u32;
+    [
+        (len >> 21) as u8 & 0x7f,
+        (len >> 14) as u8 & 0x7f,
+        (len >> 7) as u8 & 0x7f,
+        len as u8 & 0x7f,
+    ]
+}
+
+/// An ID3v2 tag of the given major version with text frames in `encoding`.
+fn id3v2(major: u8, encoding: u8, frames: &[(&str, &str)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (id, text) in frames {
+        let mut payload = vec![encoding];
+        if *id == "COMM" {
+            payload.extend_from_slice(b"eng\0");
+        }
+        match encoding {
+            1 => {
+                payload.extend_from_slice(&[0xff, 0xfe]);
+                payload.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+            _ => payload.extend_from_slice(text.as_bytes()),
+        }
+        body.extend_from_slice(id.as_bytes());
+        if major == 4 {
+            body.extend_from_slice(&syncsafe(payload.len()));
+        } else {
+            body.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        }
+        body.extend_from_slice(&[0, 0]);
+        body.extend_from_slice(&payload);
+    }
+    // Padding, as written by most taggers.
+    body.extend_from_slice(&[0; 16]);
+    let mut tag = b"ID3".to_vec();
+    tag.extend_from_slice(&[major, 0, 0]);
+    tag.extend_from_slice(&syncsafe(body.len()));
+    tag.extend_from_slice(&body);
+    tag
+}
+
+fn id3v1(title: &str, artist: &str, year: &str, track: u8, genre: u8) -> Vec<u8> {
+    let field = |text: &str, len: usize| {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(len, 0);
+        bytes
+    };
+    let mut tag = b"TAG".to_vec();
+    tag.extend(field(title, 30));
+    tag.extend(field(artist, 30));
+    tag.extend(field("", 30));
+    tag.extend(field(year, 4));
+    tag.extend(field("", 29));
+    tag.push(track);
+    tag.push(genre);
+    tag
+}
+
+fn vorbis_comment(entries: &[&str]) -> Vec<u8> {
+    let vendor = b"test vendor";
+    let mut block = (vendor.len() as u32).to_le_bytes().to_vec();
+    block.extend_from_slice(vendor);
+    block.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for entry in entries {
+        block.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+        block.extend_from_slice(entry.as_bytes());
+    }
+    block
+}
+
+fn flac(entries: &[&str]) -> Vec<u8> {
+    let mut file = b"fLaC".to_vec();
+    // STREAMINFO, then the Vorbis comment as the last block.
+    file.extend_from_slice(&[0, 0, 0, 34]);
+    file.extend_from_slice(&[0; 34]);
+    let comment = vorbis_comment(entries);
+    file.push(0x84);
+    file.extend_from_slice(&(comment.len() as u32).to_be_bytes()[1..]);
+    file.extend_from_slice(&comment);
+    file
+}
+
+fn ogg_page(sequence: u32, packet: &[u8]) -> Vec<u8> {
+    let mut lacing = vec![255u8; packet.len() / 255];
+    lacing.push((packet.len() % 255) as u8);
+    let mut page = b"OggS".to_vec();
+    page.push(0);
+    page.push(if sequence == 0 { 2 } else { 0 });
+    page.extend_from_slice(&[0; 8]);
+    page.extend_from_slice(&7u32.to_le_bytes());
+    page.extend_from_slice(&sequence.to_le_bytes());
+    page.extend_from_slice(&[0; 4]);
+    page.push(lacing.len() as u8);
+    page.extend_from_slice(&lacing);
+    page.extend_from_slice(packet);
+    page
+}
+
+fn ogg_vorbis(entries: &[&str]) -> Vec<u8> {
+    let mut identification = b"\x01vorbis".to_vec();
+    identification.extend_from_slice(&[0; 23]);
+    let mut comment = b"\x03vorbis".to_vec();
+    comment.extend(vorbis_comment(entries));
+    comment.push(1);
+    let mut file = ogg_page(0, &identification);
+    file.extend(ogg_page(1, &comment));
+    file
+}
+
+fn atom(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut atom = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    atom.extend_from_slice(kind);
+    atom.extend_from_slice(body);
+    atom
+}
+
+fn mp4_item(kind: &[u8; 4], type_indicator: u32, value: &[u8]) -> Vec<u8> {
+    let mut data = type_indicator.to_be_bytes().to_vec();
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(value);
+    atom(kind, &atom(b"data", &data))
+}
+
+fn m4a(artist: &str, album: &str, year: &str, track: u16) -> Vec<u8> {
+    let ilst = [
+        mp4_item(b"\xa9ART", 1, artist.as_bytes()),
+        mp4_item(b"\xa9alb", 1, album.as_bytes()),
+        mp4_item(b"\xa9day", 1, year.as_bytes()),
+        mp4_item(b"trkn", 0, &[0, 0, 0, track as u8, 0, 12, 0, 0]),
+        // One-based ID3v1 genre, so 19 is Techno.
+        mp4_item(b"gnre", 0, &[0, 19]),
+    ]
+    .concat();
+    let mut meta = vec![0; 4];
+    meta.extend(atom(b"hdlr", &[0; 25]));
+    meta.extend(atom(b"ilst", &ilst));
+    let moov = atom(
+        b"moov",
+        &[
+            atom(b"mvhd", &[0; 100]),
+            atom(b"udta", &atom(b"meta", &meta)),
+        ]
+        .concat(),
+    );
+    [
+        atom(b"ftyp", b"M4A \0\0\0\0"),
+        atom(b"mdat", &[7; 64]),
+        moov,
+    ]
+    .concat()
+}
+
+/// root/
+///   ok_computer/airbag.mp3    ID3v2.3, Radiohead, 1997, track 1, genre (17)
+///   kid_a/idioteque.mp3       ID3v2.4 UTF-16, Radiohead, 2000, track 8
+///   old/legacy.mp3            ID3v1.1 only, Portishead, 1994, track 3, Trip-Hop
+///   hail/there_there.flac     Vorbis comment, Radiohead, 2003-05-26, track 9
+///   moon/daydreaming.ogg      Ogg Vorbis, Radiohead, 2016, comment "single"
+///   itunes/sour_times.m4a     MP4 atoms, Portishead, 1994, track 4, genre Techno
+///   notes.txt                 mentions Radiohead, but isn't audio
+///   untagged.mp3              no tags at all
+fn build_cache() -> (TempDir, SearchCache) {
+    let mut airbag = id3v2(
+        3,
+        0,
+        &[
+            ("TPE1", "Radiohead"),
+            ("TALB", "OK Computer"),
+            ("TIT2", "Airbag"),
+            ("TYER", "1997"),
+            ("TRCK", "1/12"),
+            ("TCON", "(17)"),
+            ("COMM", "Remastered"),
+        ],
+    );
+    airbag.extend_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+    let mut idioteque = id3v2(
+        4,
+        1,
+        &[
+            ("TPE1", "Radiohead"),
+            ("TALB", "Kid A"),
+            ("TIT2", "Idioteque"),
+            ("TDRC", "2000-10-02"),
+            ("TRCK", "8"),
+        ],
+    );
+    idioteque.extend_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+    let mut legacy = vec![0xff, 0xfb, 0x90, 0x00];
+    legacy.extend(id3v1("Glory Box", "Portishead", "1994", 3, 27));
+
+    let files: [(&str, Vec<u8>); 8] = [
+        ("ok_computer/airbag.mp3", airbag),
+        ("kid_a/idioteque.mp3", idioteque),
+        ("old/legacy.mp3", legacy),
+        (
+            "hail/there_there.flac",
+            flac(&[
+                "ARTIST=Radiohead",
+                "ALBUM=Hail to the Thief",
+                "DATE=2003-05-26",
+                "TRACKNUMBER=9",
+            ]),
+        ),
+        (
+            "moon/daydreaming.ogg",
+            ogg_vorbis(&["artist=Radiohead", "date=2016", "comment=single"]),
+        ),
+        (
+            "itunes/sour_times.m4a",
+            m4a("Portishead", "Dummy", "1994-08-22", 4),
+        ),
+        ("notes.txt", b"Radiohead 1997".to_vec()),
+        ("untagged.mp3", vec![0xff, 0xfb, 0x90, 0x00]),
+    ];
+    cache_with_files("audio_tag_filters", files)
+}
+
+#[test]
+fn artist_reads_every_tag_format() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let options = SearchOptions::default();
+
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT artist:Radiohead", options),
+        paths(&[
+            "hail/there_there.flac",
+            "kid_a/idioteque.mp3",
+            "moon/daydreaming.ogg",
+            "ok_computer/airbag.mp3",
+        ])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT artist:Portishead", options),
+        paths(&["itunes/sour_times.m4a", "old/legacy.mp3"])
+    );
+}
+
+#[test]
+fn text_fields_match_substrings_and_follow_case_option() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let sensitive = SearchOptions::default();
+    let insensitive = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT album:\"ok comp\"", sensitive),
+        Vec::<PathBuf>::new()
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT album:\"ok comp\"", insensitive),
+        paths(&["ok_computer/airbag.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT title:Glory;Idio", sensitive),
+        paths(&["kid_a/idioteque.mp3", "old/legacy.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT comment:single", sensitive),
+        paths(&["moon/daydreaming.ogg"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT comment:Remastered", sensitive),
+        paths(&["ok_computer/airbag.mp3"])
+    );
+}
+
+#[test]
+fn genre_resolves_numeric_references() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let options = SearchOptions::default();
+
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT genre:Rock", options),
+        paths(&["ok_computer/airbag.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT genre:Trip-Hop", options),
+        paths(&["old/legacy.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT genre:Techno", options),
+        paths(&["itunes/sour_times.m4a"])
+    );
+}
+
+#[test]
+fn year_and_track_support_numbers_comparisons_and_ranges() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let options = SearchOptions::default();
+
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "in:$ROOT audio: artist:Radiohead year:1997..2000",
+            options
+        ),
+        paths(&["kid_a/idioteque.mp3", "ok_computer/airbag.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT year:1994", options),
+        paths(&["itunes/sour_times.m4a", "old/legacy.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT year:>2000", options),
+        paths(&["hail/there_there.flac", "moon/daydreaming.ogg"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT track:1", options),
+        paths(&["ok_computer/airbag.mp3"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT track:3..4", options),
+        paths(&["itunes/sour_times.m4a", "old/legacy.mp3"])
+    );
+}
+
+#[test]
+fn audio_filters_compose_with_negation() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "in:$ROOT ext:mp3 !artist:Radiohead",
+            SearchOptions::default()
+        ),
+        paths(&["old/legacy.mp3", "untagged.mp3"])
+    );
+}
+
+#[test]
+fn retagged_files_are_read_again_once_their_mtime_changes() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let options = SearchOptions::default();
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT artist:Thom", options),
+        Vec::<PathBuf>::new()
+    );
+
+    let path = root.join("moon/daydreaming.ogg");
+    fs::write(&path, ogg_vorbis(&["artist=Thom Yorke"])).unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path,
+            flag: EventFlag::ItemModified | EventFlag::ItemIsFile,
+            id: 1,
+        }])
+        .unwrap();
+
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT artist:Thom", options),
+        paths(&["moon/daydreaming.ogg"])
+    );
+}
+
+#[test]
+fn oversized_mp4_boxes_are_ignored() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let mut bytes = atom(b"ftyp", b"M4A \0\0\0\0");
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    bytes.extend_from_slice(b"mdat");
+    bytes.extend_from_slice(&(u64::MAX - 8).to_be_bytes());
+    bytes.extend(atom(b"moov", &[]));
+    let path = root.join("itunes/wrapping.m4a");
+    fs::write(&path, bytes).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path,
+            flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+            id: 1,
+        }])
+        .unwrap();
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "in:$ROOT/itunes artist:Portishead",
+            SearchOptions::default()
+        ),
+        paths(&["itunes/sour_times.m4a"])
+    );
+}
+
+#[test]
+fn audio_filters_reject_missing_or_malformed_arguments() {
+    let (_tmp, mut cache) = build_cache();
+    for line in [
+        "artist:",
+        "album:>3",
+        "year:nineties",
+        "track:5..1",
+        "year:1990-2000",
+    ] {
+        let result =
+            cache.search_with_options(line, SearchOptions::default(), CancellationToken::noop());
+        assert!(result.is_err(), "{line} should be rejected");
+    }
+}
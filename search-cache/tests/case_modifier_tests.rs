@@ -0,0 +1,111 @@
+//! Tests for the per-term `case:` modifier on top of a case-insensitive
+//! search.
+
+mod common;
+
+use common::{cache_with_files, insensitive, paths, query_with};
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let files: [(&str, &[u8]); 8] = [
+        ("Report.pdf", b""),
+        ("report.pdf", b""),
+        ("Draft.doc", b""),
+        ("DRAFT.doc", b""),
+        ("README_TODO.md", b""),
+        ("readme_todo.txt", b""),
+        ("logs/Error.log", b"Fatal Error"),
+        ("logs/error.log", b"fatal error"),
+    ];
+    cache_with_files("case_modifier", files)
+}
+
+#[test]
+fn case_word_is_sensitive_while_the_rest_is_not() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "report", insensitive()),
+        paths(&["Report.pdf", "report.pdf"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "case:Report", insensitive()),
+        paths(&["Report.pdf"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "readme case:TODO", insensitive()),
+        paths(&["README_TODO.md"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "ext:pdf !case:report", insensitive()),
+        paths(&["Report.pdf"])
+    );
+}
+
+#[test]
+fn case_applies_to_a_whole_group() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "case:<Report|Draft>", insensitive()),
+        paths(&["Draft.doc", "Report.pdf"])
+    );
+    // Only the group is case-sensitive; `pdf` still matches either way.
+    assert_eq!(
+        query_with(&mut cache, root, "case:(report) PDF", insensitive()),
+        paths(&["report.pdf"])
+    );
+}
+
+#[test]
+fn case_applies_to_regex_and_content() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "case:regex:^R", insensitive()),
+        paths(&["README_TODO.md", "Report.pdf"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "in:$ROOT/logs content:error",
+            insensitive()
+        ),
+        paths(&["logs/Error.log", "logs/error.log"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "in:$ROOT/logs case:content:Error",
+            insensitive()
+        ),
+        paths(&["logs/Error.log"])
+    );
+}
+
+#[test]
+fn case_terms_still_produce_highlights() {
+    let (_tmp, mut cache) = build_cache();
+    let outcome = cache
+        .search_with_options(
+            "case:<Report|Draft> case:TODO",
+            insensitive(),
+            CancellationToken::noop(),
+        )
+        .unwrap();
+    assert_eq!(outcome.highlights, vec!["draft", "report", "todo"]);
+}
+
+#[test]
+fn case_without_an_operand_is_rejected() {
+    let (_tmp, mut cache) = build_cache();
+    assert!(
+        cache
+            .search_with_options("case:", insensitive(), CancellationToken::noop())
+            .is_err()
+    );
+}
@@ -0,0 +1,120 @@
+//! Tests for the folder aggregate filters: childcount:, childfilecount:,
+//! childfoldercount: and empty:.
+
+mod common;
+
+use common::{paths, query};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+/// root/
+///   cache/ (12 files)
+///   project/
+///     a.txt (3 bytes), b.txt (0 bytes)
+///     src/, target/
+///   leftover/
+///   stub.log (0 bytes)
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("child_count_filters").unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("cache")).unwrap();
+    for i in 0..12 {
+        std::fs::write(root.join(format!("cache/blob{i}")), b"x").unwrap();
+    }
+    std::fs::create_dir_all(root.join("project/src")).unwrap();
+    std::fs::create_dir_all(root.join("project/target")).unwrap();
+    std::fs::write(root.join("project/a.txt"), b"abc").unwrap();
+    std::fs::write(root.join("project/b.txt"), b"").unwrap();
+    std::fs::write(root.join("project/src/lib.rs"), b"fn x() {}").unwrap();
+    std::fs::write(root.join("project/target/out"), b"1").unwrap();
+    std::fs::create_dir_all(root.join("leftover")).unwrap();
+    std::fs::write(root.join("stub.log"), b"").unwrap();
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+#[test]
+fn childcount_supports_comparisons_ranges_and_exact_values() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childcount:>10"),
+        paths(&["cache"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childcount:4"),
+        paths(&["project"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childcount:1..4"),
+        paths(&["project", "project/src", "project/target"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childcount:..0"),
+        paths(&["leftover"])
+    );
+}
+
+#[test]
+fn child_file_and_folder_counts_split_by_type() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childfoldercount:2"),
+        paths(&["project"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childfilecount:2"),
+        paths(&["project"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT childfilecount:>=12"),
+        paths(&["cache"])
+    );
+    assert_eq!(
+        query(
+            &mut cache,
+            root,
+            "in:$ROOT childfoldercount:>0 childfilecount:0"
+        ),
+        Vec::<PathBuf>::new()
+    );
+}
+
+#[test]
+fn empty_matches_zero_byte_files_and_childless_folders() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT empty:"),
+        paths(&["leftover", "project/b.txt", "stub.log"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT folder: empty:"),
+        paths(&["leftover"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT empty:*.log"),
+        paths(&["stub.log"])
+    );
+}
+
+#[test]
+fn count_filters_reject_non_numeric_arguments() {
+    let (_tmp, mut cache) = build_cache();
+    for line in [
+        "childcount:",
+        "childcount:many",
+        "childfilecount:>lots",
+        "childfoldercount:5..1",
+    ] {
+        let result =
+            cache.search_with_options(line, SearchOptions::default(), CancellationToken::noop());
+        assert!(result.is_err(), "{line} should be rejected");
+    }
+}
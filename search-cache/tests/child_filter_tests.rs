@@ -0,0 +1,104 @@
+//! Tests for the child: filter, which keeps folders with a matching direct child.
+
+mod common;
+
+use common::{cache_with_names, paths, query_with};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    cache_with_names(
+        "child_filter",
+        &[
+            "Cargo.toml",
+            "app/Cargo.toml",
+            "app/src/main.rs",
+            "art/cover.psd",
+            "art/old/poster.PSD",
+            "notes/todo.txt",
+        ],
+    )
+}
+
+#[test]
+fn child_exact_name_returns_containing_folders() {
+    let (tmp, mut cache) = build_cache();
+    let hits = query_with(
+        &mut cache,
+        tmp.path(),
+        "child:Cargo.toml",
+        SearchOptions::default(),
+    );
+    assert_eq!(hits, paths(&["", "app"]));
+}
+
+#[test]
+fn child_wildcard_matches_direct_children_only() {
+    let (tmp, mut cache) = build_cache();
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    let hits = query_with(&mut cache, tmp.path(), "child:*.psd", options);
+    assert_eq!(hits, paths(&["art", "art/old"]));
+
+    let case_sensitive = query_with(
+        &mut cache,
+        tmp.path(),
+        "child:*.psd",
+        SearchOptions::default(),
+    );
+    assert_eq!(case_sensitive, paths(&["art"]));
+}
+
+#[test]
+fn child_with_path_segments_matches_like_plain_terms() {
+    let (tmp, mut cache) = build_cache();
+    let hits = query_with(
+        &mut cache,
+        tmp.path(),
+        "child:src/main.rs",
+        SearchOptions::default(),
+    );
+    assert_eq!(hits, paths(&["app/src"]));
+}
+
+#[test]
+fn child_composes_with_scope_and_boolean_filters() {
+    let (tmp, mut cache) = build_cache();
+    let options = SearchOptions::default();
+
+    let in_app = query_with(
+        &mut cache,
+        tmp.path(),
+        "infolder:$ROOT/app child:main.rs",
+        options,
+    );
+    assert_eq!(in_app, paths(&["app/src"]));
+
+    let folders = query_with(&mut cache, tmp.path(), "folder: child:*.txt", options);
+    assert_eq!(folders, paths(&["notes"]));
+
+    let either = query_with(
+        &mut cache,
+        tmp.path(),
+        "child:todo.txt | child:cover.psd",
+        options,
+    );
+    assert_eq!(either, paths(&["art", "notes"]));
+
+    let without = query_with(&mut cache, tmp.path(), "folder: !child:Cargo.toml", options);
+    assert_eq!(without, paths(&["app/src", "art", "art/old", "notes"]));
+}
+
+#[test]
+fn child_requires_an_argument() {
+    let (_tmp, mut cache) = build_cache();
+    let result = cache.search_with_options(
+        "child:",
+        SearchOptions::default(),
+        CancellationToken::noop(),
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,109 @@
+//! Helpers shared by the feature tests that query a small fixture tree.
+//! Each test crate only uses some of them.
+#![allow(dead_code)]
+
+use search_cache::{SearchCache, SearchOptions, SlabIndex};
+use search_cancel::CancellationToken;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempdir::TempDir;
+
+/// Writes each `(relative path, contents)` pair below `root`, creating the
+/// parent folders.
+pub fn write_files<P: AsRef<Path>, B: AsRef<[u8]>>(
+    root: &Path,
+    files: impl IntoIterator<Item = (P, B)>,
+) {
+    for (name, bytes) in files {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, bytes).unwrap();
+    }
+}
+
+/// Indexes a fresh temporary folder holding `files`.
+pub fn cache_with_files<P: AsRef<Path>, B: AsRef<[u8]>>(
+    prefix: &str,
+    files: impl IntoIterator<Item = (P, B)>,
+) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new(prefix).unwrap();
+    write_files(temp_dir.path(), files);
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+/// [`cache_with_files`] with empty files.
+pub fn cache_with_names(prefix: &str, names: &[&str]) -> (TempDir, SearchCache) {
+    cache_with_files(prefix, names.iter().map(|name| (name, b"")))
+}
+
+pub fn insensitive() -> SearchOptions {
+    SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    }
+}
+
+/// Runs `line` with `$ROOT` replaced by the fixture root.
+pub fn search(
+    cache: &mut SearchCache,
+    root: &Path,
+    line: &str,
+    options: SearchOptions,
+) -> Vec<SlabIndex> {
+    let line = line.replace("$ROOT", &root.display().to_string());
+    cache
+        .search_with_options(&line, options, CancellationToken::noop())
+        .expect("query should succeed")
+        .nodes
+        .expect("noop token should not cancel")
+}
+
+/// Paths of `nodes` relative to `root`, in the given order. Nodes outside
+/// `root`, such as the ancestors the slab keeps for it, are left out.
+pub fn relative(cache: &SearchCache, root: &Path, nodes: &[SlabIndex]) -> Vec<PathBuf> {
+    nodes
+        .iter()
+        .filter_map(|&index| {
+            let path = cache.node_path(index).unwrap();
+            path.strip_prefix(root).ok().map(Path::to_path_buf)
+        })
+        .collect()
+}
+
+/// Relative paths matching `line`, in result order.
+pub fn query_ordered(
+    cache: &mut SearchCache,
+    root: &Path,
+    line: &str,
+    options: SearchOptions,
+) -> Vec<PathBuf> {
+    let nodes = search(cache, root, line, options);
+    relative(cache, root, &nodes)
+}
+
+/// Relative paths matching `line`, sorted.
+pub fn query_with(
+    cache: &mut SearchCache,
+    root: &Path,
+    line: &str,
+    options: SearchOptions,
+) -> Vec<PathBuf> {
+    sorted(query_ordered(cache, root, line, options))
+}
+
+/// [`query_with`] using the default options.
+pub fn query(cache: &mut SearchCache, root: &Path, line: &str) -> Vec<PathBuf> {
+    query_with(cache, root, line, SearchOptions::default())
+}
+
+pub fn sorted(mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.sort();
+    paths
+}
+
+pub fn paths(expected: &[&str]) -> Vec<PathBuf> {
+    expected.iter().map(PathBuf::from).collect()
+}
@@ -0,0 +1,160 @@
+//! Tests for content-verified duplicates: `SearchCache::content_duplicates`,
+//! `dupe:content` and `hashdupe:`.
+
+mod common;
+
+use cardinal_sdk::{EventFlag, FsEvent};
+use common::{paths, relative, search, sorted, write_files};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{fs, path::PathBuf, sync::atomic::AtomicBool};
+use tempdir::TempDir;
+
+static NEVER_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Larger than both 64 KiB edges together, so the middle is only seen by
+/// the full hash.
+const LARGE: usize = 300 * 1024;
+
+fn large_payload(middle: u8) -> Vec<u8> {
+    let mut bytes: Vec<u8> = (0..LARGE).map(|i| (i % 251) as u8).collect();
+    bytes[LARGE / 2] = middle;
+    bytes
+}
+
+/// root/
+///   a/report.txt, b/copy.txt ("same text")   c/other.txt ("diff text")
+///   big/one.bin, big/two.bin (identical)     big/three.bin (differs mid-file)
+///   content (empty)   folder/
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("content_dupes").unwrap();
+    let root = temp_dir.path();
+    let files: [(&str, Vec<u8>); 7] = [
+        ("a/report.txt", b"same text".to_vec()),
+        ("b/copy.txt", b"same text".to_vec()),
+        ("c/other.txt", b"diff text".to_vec()),
+        ("big/one.bin", large_payload(0)),
+        ("big/two.bin", large_payload(0)),
+        ("big/three.bin", large_payload(1)),
+        ("content", Vec::new()),
+    ];
+    write_files(root, files);
+    fs::create_dir(root.join("folder")).unwrap();
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+#[test]
+fn content_duplicates_groups_byte_identical_files() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let nodes = search(&mut cache, root, "in:$ROOT", SearchOptions::default());
+    let groups = cache
+        .content_duplicates(&nodes, CancellationToken::noop())
+        .expect("noop token should not cancel");
+    let mut groups: Vec<Vec<PathBuf>> = groups
+        .iter()
+        .map(|group| sorted(relative(&cache, root, group)))
+        .collect();
+    groups.sort();
+    assert_eq!(
+        groups,
+        vec![
+            paths(&["a/report.txt", "b/copy.txt"]),
+            paths(&["big/one.bin", "big/two.bin"]),
+        ]
+    );
+}
+
+#[test]
+fn dupe_content_and_hashdupe_filter_the_result_set() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    let hits = search(
+        &mut cache,
+        root,
+        "in:$ROOT dupe:content",
+        SearchOptions::default(),
+    );
+    assert_eq!(
+        sorted(relative(&cache, root, &hits)),
+        paths(&["a/report.txt", "b/copy.txt", "big/one.bin", "big/two.bin"])
+    );
+    let hits = search(
+        &mut cache,
+        root,
+        "in:$ROOT hashdupe:*.txt",
+        SearchOptions::default(),
+    );
+    assert_eq!(
+        sorted(relative(&cache, root, &hits)),
+        paths(&["a/report.txt", "b/copy.txt"])
+    );
+    let hits = search(
+        &mut cache,
+        root,
+        "hashdupe: in:$ROOT/big",
+        SearchOptions::default(),
+    );
+    assert_eq!(
+        sorted(relative(&cache, root, &hits)),
+        paths(&["big/one.bin", "big/two.bin"])
+    );
+    // Quoted, `content` is a name again and the single file has no duplicate.
+    let hits = search(
+        &mut cache,
+        root,
+        "in:$ROOT dupe:\"content\"",
+        SearchOptions::default(),
+    );
+    assert!(hits.is_empty(), "{hits:?}");
+}
+
+#[test]
+fn content_hashes_persist_with_the_cache() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let nodes = search(&mut cache, root, "in:$ROOT", SearchOptions::default());
+    assert!(cache.content_hashes().is_empty());
+    cache
+        .content_duplicates(&nodes, CancellationToken::noop())
+        .unwrap();
+    // Every same-size candidate is hashed; c/other.txt shares its size.
+    assert_eq!(cache.content_hashes().len(), 6);
+
+    let cache_dir = TempDir::new("content_dupes_cache").unwrap();
+    let cache_path = cache_dir.path().join("cache.zstd");
+    cache.flush_snapshot_to_file(&cache_path).unwrap();
+    let mut loaded = SearchCache::try_read_persistent_cache(
+        root,
+        &cache_path,
+        &Vec::new(),
+        &Vec::new(),
+        &NEVER_CANCELLED,
+    )
+    .unwrap();
+    assert_eq!(loaded.content_hashes().len(), 6);
+
+    // Hashes of deleted files are dropped on the next flush.
+    fs::remove_file(root.join("c/other.txt")).unwrap();
+    loaded
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("c/other.txt"),
+            flag: EventFlag::ItemRemoved | EventFlag::ItemIsFile,
+            id: 1,
+        }])
+        .unwrap();
+    loaded.flush_snapshot_to_file(&cache_path).unwrap();
+    assert_eq!(loaded.content_hashes().len(), 5);
+}
+
+#[test]
+fn content_duplicates_stops_when_cancelled() {
+    let (tmp, mut cache) = build_cache();
+    let nodes = search(&mut cache, tmp.path(), "in:$ROOT", SearchOptions::default());
+    let token = CancellationToken::new_search();
+    let _newer = CancellationToken::new_search();
+    assert!(cache.content_duplicates(&nodes, token).is_none());
+    assert!(cache.content_hashes().is_empty());
+}
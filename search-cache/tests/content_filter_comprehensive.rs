@@ -25,6 +25,7 @@ fn content_filter_rejects_empty_needle() {
         r#"content:"""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     );
@@ -52,6 +53,7 @@ fn content_filter_single_byte_exact_match() {
         "content:a",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -64,6 +66,7 @@ fn content_filter_single_byte_exact_match() {
         "content:A",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -88,6 +91,7 @@ fn content_filter_single_byte_case_insensitive() {
         "content:a",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -100,6 +104,7 @@ fn content_filter_single_byte_case_insensitive() {
         "content:A",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -123,6 +128,7 @@ fn content_filter_needle_spans_exact_buffer_boundary() {
         "content:BOUNDARY",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -149,6 +155,7 @@ fn content_filter_needle_spans_three_chunks() {
         "content:LONGNEEDLE",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -175,6 +182,7 @@ fn content_filter_needle_exceeds_buffer_size() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -198,6 +206,7 @@ fn content_filter_needle_equals_overlap_size() {
         "content:AB",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -217,6 +226,7 @@ fn content_filter_file_smaller_than_buffer() {
         "content:content",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -236,6 +246,7 @@ fn content_filter_empty_file_returns_no_match() {
         "content:anything",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -255,6 +266,7 @@ fn content_filter_needle_at_file_start() {
         "content:START",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -274,6 +286,7 @@ fn content_filter_needle_at_file_end() {
         "content:END",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -293,6 +306,7 @@ fn content_filter_multiple_occurrences_in_file() {
         "content:foo",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -315,6 +329,7 @@ fn content_filter_binary_with_null_bytes() {
         "content:TARGET",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -336,6 +351,7 @@ fn content_filter_utf8_multibyte_characters() {
         "content:世界",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -346,6 +362,7 @@ fn content_filter_utf8_multibyte_characters() {
         "content:🦀",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -369,6 +386,7 @@ fn content_filter_utf8_split_across_boundary() {
         "content:世界",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -394,6 +412,7 @@ fn content_filter_special_characters() {
         r#"content:"!@#$%""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -404,6 +423,7 @@ fn content_filter_special_characters() {
         r#"content:"&*()""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -424,6 +444,7 @@ fn content_filter_ignores_directories() {
         "content:content",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -451,6 +472,7 @@ fn content_filter_combined_with_extension() {
         "*.txt content:Bearer",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -463,6 +485,7 @@ fn content_filter_combined_with_extension() {
         "*.md content:Bearer",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -490,6 +513,7 @@ fn content_filter_combined_with_infolder() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -514,6 +538,7 @@ fn content_filter_combined_with_size() {
         "size:>1kb content:t",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -538,6 +563,7 @@ fn content_filter_with_not_operator() {
         "*.txt !content:secret",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -563,6 +589,7 @@ fn content_filter_with_or_operator() {
         "content:TODO | content:FIXME",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -594,6 +621,7 @@ fn content_filter_respects_cancellation() {
         "content:needle",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         token,
     );
@@ -628,6 +656,7 @@ fn content_filter_handles_unreadable_file() {
         "content:secret",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -658,6 +687,7 @@ fn content_filter_regex_special_chars_treated_literally() {
         "content:.*",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -667,6 +697,7 @@ fn content_filter_regex_special_chars_treated_literally() {
         "content:[test]+",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -676,6 +707,7 @@ fn content_filter_regex_special_chars_treated_literally() {
         "content:(group)?",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -699,6 +731,7 @@ fn content_filter_handles_long_lines() {
         "content:NEEDLE",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -720,6 +753,7 @@ fn content_filter_whitespace_in_needle() {
         r#"content:"word three""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -730,6 +764,7 @@ fn content_filter_whitespace_in_needle() {
         r#"content:"three   spaced""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -752,6 +787,7 @@ fn content_filter_case_insensitive_mixed_case() {
             &format!(r#"content:"{needle}""#),
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -775,6 +811,7 @@ fn content_filter_file_exact_buffer_size() {
         "content:TARGET",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -795,6 +832,7 @@ fn content_filter_no_false_positive_at_eof() {
         "content:TARGET",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -814,6 +852,7 @@ fn content_filter_repeated_pattern_in_needle() {
         "content:aaaaaa",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -823,6 +862,7 @@ fn content_filter_repeated_pattern_in_needle() {
         "content:BBBBBB",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -846,6 +886,7 @@ fn content_filter_all_ascii_printable() {
         "content:@ABCDEF",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -866,6 +907,7 @@ fn content_filter_high_bytes() {
         "content:AB",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
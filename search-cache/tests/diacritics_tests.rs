@@ -0,0 +1,140 @@
+//! Tests for diacritic-insensitive name matching: the `ignore_diacritics`
+//! option and the `diacritics:`/`nodiacritics:` modifiers.
+
+mod common;
+
+use cardinal_sdk::{EventFlag, FsEvent};
+use common::{cache_with_names, insensitive, paths, query_with};
+use search_cache::{SearchCache, SearchOptions};
+use std::fs;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    cache_with_names(
+        "diacritics",
+        &[
+            "Caf\u{e9}.txt",        // NFC
+            "Cafe\u{301} menu.txt", // NFD
+            "cafe.md",
+            "Tiếng Việt.doc",
+            "Résumé.pdf",
+            "resume.txt",
+            "đường.txt",
+            "Über/Straße.md",
+        ],
+    )
+}
+
+#[test]
+fn nodiacritics_matches_accented_names_in_either_form() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "cafe", insensitive()),
+        paths(&["Cafe\u{301} menu.txt", "cafe.md"])
+    );
+    let all = paths(&["Cafe\u{301} menu.txt", "Caf\u{e9}.txt", "cafe.md"]);
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:cafe", insensitive()),
+        all
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:Caf\u{e9}", insensitive()),
+        all
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:resume", insensitive()),
+        paths(&["Résumé.pdf", "resume.txt"])
+    );
+}
+
+#[test]
+fn ignore_diacritics_option_and_diacritics_opt_out() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let options = SearchOptions {
+        ignore_diacritics: true,
+        ..insensitive()
+    };
+    assert_eq!(
+        query_with(&mut cache, root, "tieng viet", options),
+        paths(&["Tiếng Việt.doc"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "diacritics:café", options),
+        paths(&["Cafe\u{301} menu.txt", "Caf\u{e9}.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "diacritics:resume", options),
+        paths(&["resume.txt"])
+    );
+}
+
+#[test]
+fn only_combining_marks_are_ignored() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    // `đ` and `ß` have no decomposition.
+    assert!(query_with(&mut cache, root, "nodiacritics:duong", insensitive()).is_empty());
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:đuong", insensitive()),
+        paths(&["đường.txt"])
+    );
+    assert!(query_with(&mut cache, root, "nodiacritics:uber/strasse", insensitive()).is_empty());
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:uber/straße", insensitive()),
+        paths(&["Über/Straße.md"])
+    );
+}
+
+#[test]
+fn nodiacritics_combines_with_other_modifiers() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:ww:viet", insensitive()),
+        paths(&["Tiếng Việt.doc"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:case:cafe", insensitive()),
+        paths(&["cafe.md"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "nodiacritics:wfn:resume.pdf",
+            insensitive()
+        ),
+        paths(&["Résumé.pdf"])
+    );
+}
+
+#[test]
+fn accented_names_follow_file_system_changes() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let created = root.join("Crème brûlée.txt");
+    fs::write(&created, b"").unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: created.clone(),
+            flag: EventFlag::ItemCreated,
+            id: 1,
+        }])
+        .unwrap();
+    assert_eq!(
+        query_with(&mut cache, root, "nodiacritics:brulee", insensitive()),
+        paths(&["Crème brûlée.txt"])
+    );
+
+    fs::remove_file(&created).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: created,
+            flag: EventFlag::ItemRemoved,
+            id: 2,
+        }])
+        .unwrap();
+    assert!(query_with(&mut cache, root, "nodiacritics:brulee", insensitive()).is_empty());
+}
@@ -0,0 +1,130 @@
+//! Tests for dupe:, namepartdupe:, sizedupe:, dmdupe: and attribdupe:.
+
+mod common;
+
+use common::{paths, query_ordered, query_with, sorted};
+use search_cache::{SearchCache, SearchOptions};
+use std::{
+    fs::{self, File, Permissions},
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    time::{Duration, UNIX_EPOCH},
+};
+use tempdir::TempDir;
+
+/// root/
+///   a/logo.png (4 bytes, t=1000)   a/logo.psd (7 bytes, t=2000)
+///   b/logo.png (4 bytes, t=1000)   b/notes.txt (7 bytes, t=3000)
+///   c/LOGO.png (9 bytes, t=4000)   c/run.sh (0o755, 9 bytes, t=5000)
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("duplicate_filters").unwrap();
+    let root = temp_dir.path();
+    let files = [
+        ("a/logo.png", 4, 1000, 0o644),
+        ("a/logo.psd", 7, 2000, 0o644),
+        ("b/logo.png", 4, 1000, 0o644),
+        ("b/notes.txt", 7, 3000, 0o644),
+        ("c/LOGO.png", 9, 4000, 0o644),
+        ("c/run.sh", 9, 5000, 0o755),
+    ];
+    for (name, size, mtime, mode) in files {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, vec![b'x'; size]).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(mtime))
+            .unwrap();
+        fs::set_permissions(&path, Permissions::from_mode(mode)).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+#[test]
+fn dupe_groups_by_name_and_follows_case_option() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let sensitive = SearchOptions::default();
+    let insensitive = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT dupe:", sensitive),
+        paths(&["a/logo.png", "b/logo.png"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT dupe:", insensitive),
+        paths(&["a/logo.png", "b/logo.png", "c/LOGO.png"])
+    );
+}
+
+#[test]
+fn namepartdupe_ignores_the_extension() {
+    let (tmp, mut cache) = build_cache();
+    let hits = query_ordered(
+        &mut cache,
+        tmp.path(),
+        "in:$ROOT namepartdupe:",
+        SearchOptions::default(),
+    );
+    assert_eq!(
+        sorted(hits),
+        paths(&["a/logo.png", "a/logo.psd", "b/logo.png"])
+    );
+}
+
+#[test]
+fn sizedupe_lists_each_group_contiguously() {
+    let (tmp, mut cache) = build_cache();
+    let hits = query_ordered(
+        &mut cache,
+        tmp.path(),
+        "in:$ROOT sizedupe:",
+        SearchOptions::default(),
+    );
+    assert_eq!(hits.len(), 6);
+    let size_of = |path: &PathBuf| fs::metadata(tmp.path().join(path)).unwrap().len();
+    let mut seen_sizes = Vec::new();
+    for path in &hits {
+        let size = size_of(path);
+        if seen_sizes.last() != Some(&size) {
+            assert!(
+                !seen_sizes.contains(&size),
+                "group of size {size} is split: {hits:?}"
+            );
+            seen_sizes.push(size);
+        }
+    }
+    assert_eq!(seen_sizes.len(), 3);
+}
+
+#[test]
+fn dmdupe_and_attribdupe_run_on_the_current_result_set() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let options = SearchOptions::default();
+
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT file: dmdupe:", options),
+        paths(&["a/logo.png", "b/logo.png"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT/a attribdupe:", options),
+        paths(&["a/logo.png", "a/logo.psd"])
+    );
+    // Within c/ only run.sh is executable, so nothing shares its attributes.
+    assert_eq!(
+        query_ordered(&mut cache, root, "in:$ROOT/c attribdupe:", options),
+        Vec::<PathBuf>::new()
+    );
+    // The filter order doesn't matter: the duplicates are found among the pngs.
+    assert_eq!(
+        query_with(&mut cache, root, "sizedupe: in:$ROOT ext:png", options),
+        paths(&["a/logo.png", "b/logo.png"])
+    );
+}
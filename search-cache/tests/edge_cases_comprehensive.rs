@@ -268,6 +268,7 @@ fn test_case_sensitivity() {
             "File",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         )
@@ -286,6 +287,7 @@ fn test_case_sensitivity() {
             "file",
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         )
@@ -0,0 +1,146 @@
+//! Tests for replaying recorded event logs
+//! Covers: path rebasing onto fixture trees, checked-in captures, rescans during replay,
+//! resume cursors left by replays
+
+use cardinal_sdk::{EventFlag, EventLogReader, EventLogWriter, FsEvent, SourceKind};
+use search_cache::{ReplaySummary, SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{
+    fs,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+use tempdir::TempDir;
+
+fn build_cache(files: &[&str]) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("event_log_replay").unwrap();
+    for file in files {
+        let full = temp_dir.path().join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn all_paths(cache: &mut SearchCache) -> Vec<PathBuf> {
+    let nodes = cache
+        .search_with_options("", SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    let mut paths: Vec<PathBuf> = nodes
+        .into_iter()
+        .map(|index| cache.node_path(index).unwrap())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn assert_matches_fresh_walk(cache: &mut SearchCache, root: &Path) {
+    let mut fresh = SearchCache::walk_fs(root);
+    assert_eq!(all_paths(cache), all_paths(&mut fresh));
+}
+
+#[test]
+fn checked_in_capture_replays_onto_fixture_tree() {
+    let (temp_dir, mut cache) = build_cache(&["keep.txt", "scratch.txt"]);
+    let root = temp_dir.path();
+
+    // The state the capture ends in.
+    fs::create_dir_all(root.join("archive")).unwrap();
+    fs::File::create(root.join("archive/todo.txt")).unwrap();
+    fs::remove_file(root.join("scratch.txt")).unwrap();
+
+    let log = fs::File::open(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rename_and_remove.jsonl"),
+    )
+    .unwrap();
+    let log = EventLogReader::new(BufReader::new(log)).unwrap();
+    assert_eq!(log.header().root, PathBuf::from("/home/user/project"));
+    let summary = cache.replay_event_log(log).unwrap();
+
+    assert_eq!(
+        summary,
+        ReplaySummary {
+            batches: 4,
+            events: 5,
+            rescans: 0,
+        }
+    );
+    assert!(
+        cache
+            .node_index_for_path(&root.join("archive/todo.txt"))
+            .is_some()
+    );
+    assert_eq!(cache.node_index_for_path(&root.join("notes")), None);
+    assert_eq!(cache.node_index_for_path(&root.join("scratch.txt")), None);
+    assert_matches_fresh_walk(&mut cache, root);
+}
+
+#[test]
+fn recorded_batches_replay_in_order() {
+    let (temp_dir, mut cache) = build_cache(&["a.txt"]);
+    let root = temp_dir.path();
+    let recorded_root = PathBuf::from("/captured/elsewhere");
+
+    let mut writer = EventLogWriter::new(Vec::new(), recorded_root.clone(), 0).unwrap();
+    writer
+        .write_batch(&[FsEvent {
+            path: recorded_root.join("b.txt"),
+            flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+            id: 1,
+        }])
+        .unwrap();
+    writer
+        .write_batch(&[FsEvent {
+            path: recorded_root.join("a.txt"),
+            flag: EventFlag::ItemRemoved | EventFlag::ItemIsFile,
+            id: 2,
+        }])
+        .unwrap();
+    let bytes = writer.into_inner();
+
+    fs::File::create(root.join("b.txt")).unwrap();
+    fs::remove_file(root.join("a.txt")).unwrap();
+    let summary = cache
+        .replay_event_log(EventLogReader::new(bytes.as_slice()).unwrap())
+        .unwrap();
+
+    assert_eq!((summary.batches, summary.events), (2, 2));
+    assert_matches_fresh_walk(&mut cache, root);
+    // Recorded ids mean nothing to a live watcher.
+    let cursor = cache.resume_cursor();
+    assert_eq!((cursor.kind(), cursor.event_id()), (SourceKind::Replay, 2));
+    assert_eq!(cursor.since(SourceKind::native()), None);
+}
+
+#[test]
+fn replay_answers_rescan_requests() {
+    let (temp_dir, mut cache) = build_cache(&["a.txt"]);
+    let root = temp_dir.path();
+    let recorded_root = PathBuf::from("/captured/root");
+
+    let mut writer = EventLogWriter::new(Vec::new(), recorded_root.clone(), 0).unwrap();
+    // A history gap on an ancestor of the watched root.
+    writer
+        .write_batch(&[FsEvent {
+            path: PathBuf::from("/captured"),
+            flag: EventFlag::MustScanSubDirs | EventFlag::UserDropped,
+            id: 1,
+        }])
+        .unwrap();
+    let bytes = writer.into_inner();
+
+    fs::File::create(root.join("missed.txt")).unwrap();
+    let summary = cache
+        .replay_event_log(EventLogReader::new(bytes.as_slice()).unwrap())
+        .unwrap();
+
+    assert_eq!(summary.rescans, 1);
+    assert!(
+        cache
+            .node_index_for_path(&root.join("missed.txt"))
+            .is_some()
+    );
+}
@@ -2,7 +2,7 @@
 //! Covers: add, remove, rename operations, rescan triggers, event batching
 
 use cardinal_sdk::{EventFlag, FsEvent};
-use search_cache::SearchCache;
+use search_cache::{HandleFSEError, SearchCache, SearchOptions, SlabIndex};
 use search_cancel::CancellationToken;
 use std::path::PathBuf;
 use tempdir::TempDir;
@@ -24,6 +24,14 @@ fn build_initial_cache(files: &[&str]) -> (SearchCache, PathBuf) {
     (cache, root_path)
 }
 
+fn search(cache: &mut SearchCache, query: &str) -> Vec<SlabIndex> {
+    cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap()
+}
+
 #[test]
 fn test_handle_single_file_creation() {
     let initial_files = ["existing1.txt", "existing2.txt"];
@@ -341,7 +349,7 @@ fn test_event_id_tracking() {
     let initial_files = ["test.txt"];
     let (mut cache, root) = build_initial_cache(&initial_files);
 
-    let initial_event_id = cache.last_event_id();
+    let initial_event_id = cache.resume_cursor().event_id();
 
     // Send event with higher ID
     let new_file = root.join("new.txt");
@@ -355,7 +363,7 @@ fn test_event_id_tracking() {
 
     cache.handle_fs_events(vec![event]).unwrap();
 
-    let new_event_id = cache.last_event_id();
+    let new_event_id = cache.resume_cursor().event_id();
     assert!(
         new_event_id > initial_event_id,
         "Event ID should be updated"
@@ -383,6 +391,74 @@ fn test_rescan_trigger_on_root_changed() {
     assert!(result.is_err(), "RootChanged should trigger rescan error");
 }
 
+#[test]
+fn test_dropped_events_covering_root_trigger_rescan() {
+    let (mut cache, root) = build_initial_cache(&["test.txt"]);
+
+    for path in [root.clone(), root.parent().unwrap().to_path_buf()] {
+        let result = cache.handle_fs_events(vec![FsEvent {
+            path,
+            flag: EventFlag::MustScanSubDirs | EventFlag::KernelDropped | EventFlag::ItemIsDir,
+            id: 60,
+        }]);
+        assert!(matches!(result, Err(HandleFSEError::Rescan)));
+    }
+    assert_eq!(cache.rescan_count(), 2);
+}
+
+#[test]
+fn test_dropped_events_below_root_rescan_only_that_subtree() {
+    let (mut cache, root) = build_initial_cache(&["Library/Caches/a.txt", "Documents/b.txt"]);
+    let untouched = cache
+        .node_index_for_path(&root.join("Documents/b.txt"))
+        .unwrap();
+
+    std::fs::remove_file(root.join("Library/Caches/a.txt")).unwrap();
+    std::fs::File::create(root.join("Library/Caches/c.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("Library"),
+            flag: EventFlag::MustScanSubDirs | EventFlag::UserDropped,
+            id: 61,
+        }])
+        .unwrap();
+
+    assert_eq!(cache.rescan_count(), 0);
+    assert!(search(&mut cache, "a.txt").is_empty());
+    assert_eq!(search(&mut cache, "c.txt").len(), 1);
+    assert_eq!(
+        cache.node_index_for_path(&root.join("Documents/b.txt")),
+        Some(untouched)
+    );
+}
+
+#[test]
+fn test_root_directory_change_reconciles_root_entries() {
+    let (mut cache, root) = build_initial_cache(&["old_top.txt", "nested/keep.txt"]);
+    let untouched = cache
+        .node_index_for_path(&root.join("nested/keep.txt"))
+        .unwrap();
+
+    std::fs::remove_file(root.join("old_top.txt")).unwrap();
+    std::fs::create_dir_all(root.join("new_dir/inner")).unwrap();
+    std::fs::File::create(root.join("new_dir/inner/deep.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: root.clone(),
+            flag: EventFlag::ItemModified | EventFlag::ItemIsDir,
+            id: 62,
+        }])
+        .unwrap();
+
+    assert_eq!(cache.rescan_count(), 0);
+    assert!(search(&mut cache, "old_top").is_empty());
+    assert_eq!(search(&mut cache, "deep.txt").len(), 1);
+    assert_eq!(
+        cache.node_index_for_path(&root.join("nested/keep.txt")),
+        Some(untouched)
+    );
+}
+
 #[test]
 fn test_history_done_event() {
     let initial_files = ["test.txt"];
@@ -518,3 +594,262 @@ fn test_rapid_create_delete_cycle() {
         "File should be removed after cycles"
     );
 }
+
+#[test]
+fn test_polled_dirs_track_recorded_directory_mtimes() {
+    let (cache, root) = build_initial_cache(&["a/b/file.txt", "c/x.txt", "top.txt"]);
+
+    let dirs = cache.polled_dirs();
+    let mut paths: Vec<_> = dirs.iter().map(|dir| dir.path.clone()).collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            root.clone(),
+            root.join("a"),
+            root.join("a/b"),
+            root.join("c")
+        ]
+    );
+    // The walker records directory metadata even when file metadata is skipped.
+    assert!(dirs.iter().all(|dir| dir.mtime.is_some()));
+    let recorded = std::fs::metadata(root.join("a/b"))
+        .unwrap()
+        .modified()
+        .unwrap()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as u32;
+    let dir_b = dirs
+        .iter()
+        .find(|dir| dir.path == root.join("a/b"))
+        .unwrap();
+    assert_eq!(dir_b.mtime, Some(recorded));
+}
+
+#[test]
+fn test_polling_watcher_events_update_cache() {
+    use cardinal_sdk::{EventWatcher, PollConfig};
+    use std::time::Duration;
+
+    let (mut cache, root) = build_initial_cache(&["watched/old.txt"]);
+    // Make sure the next write lands on a different mtime second.
+    std::thread::sleep(Duration::from_millis(1100));
+
+    let (_, watcher) = EventWatcher::spawn_polling(
+        root.to_str().unwrap().to_string(),
+        cache.polled_dirs(),
+        PollConfig {
+            interval: Duration::from_millis(20),
+            io_budget: 64,
+        },
+        cache.ignore_paths(),
+        cache.include_paths(),
+    );
+    let history_done = watcher.recv().unwrap();
+    assert!(history_done[0].flag.contains(EventFlag::HistoryDone));
+
+    std::fs::File::create(root.join("watched/new.txt")).unwrap();
+    let events = watcher.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(events[0].path, root.join("watched"));
+    cache.handle_fs_events(events).unwrap();
+
+    let nodes = cache
+        .query_files("new.txt", CancellationToken::noop())
+        .unwrap()
+        .unwrap();
+    assert_eq!(nodes.len(), 1);
+}
+
+#[test]
+fn test_paired_rename_moves_subtree_in_place() {
+    let (mut cache, root) = build_initial_cache(&[
+        "src/alpha_dir/one.txt",
+        "src/alpha_dir/deep/two.txt",
+        "dest/keep.txt",
+    ]);
+    let total = cache.get_total_files();
+    let dir = cache
+        .node_index_for_path(&root.join("src/alpha_dir"))
+        .unwrap();
+    let one = cache
+        .node_index_for_path(&root.join("src/alpha_dir/one.txt"))
+        .unwrap();
+    let two = cache
+        .node_index_for_path(&root.join("src/alpha_dir/deep/two.txt"))
+        .unwrap();
+
+    std::fs::rename(root.join("src/alpha_dir"), root.join("dest/beta_dir")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            FsEvent {
+                path: root.join("src/alpha_dir"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                id: 10,
+            },
+            FsEvent {
+                path: root.join("dest/beta_dir"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                id: 11,
+            },
+        ])
+        .unwrap();
+
+    assert_eq!(cache.node_index_for_path(&root.join("src/alpha_dir")), None);
+    assert_eq!(
+        cache.node_index_for_path(&root.join("dest/beta_dir")),
+        Some(dir)
+    );
+    assert_eq!(
+        cache.node_index_for_path(&root.join("dest/beta_dir/one.txt")),
+        Some(one)
+    );
+    assert_eq!(
+        cache.node_index_for_path(&root.join("dest/beta_dir/deep/two.txt")),
+        Some(two)
+    );
+    assert_eq!(cache.get_total_files(), total);
+    assert_eq!(search(&mut cache, "beta_dir"), vec![dir]);
+    assert!(search(&mut cache, "alpha_dir").is_empty());
+}
+
+#[test]
+fn test_paired_rename_keeps_name_index_path_order() {
+    let (mut cache, root) = build_initial_cache(&["a/x.txt", "m/x.txt", "z/x.txt"]);
+    let moved = cache.node_index_for_path(&root.join("a/x.txt")).unwrap();
+
+    std::fs::rename(root.join("a"), root.join("n")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            FsEvent {
+                path: root.join("a"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                id: 20,
+            },
+            FsEvent {
+                path: root.join("n"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                id: 21,
+            },
+        ])
+        .unwrap();
+
+    let paths: Vec<PathBuf> = search(&mut cache, "x.txt")
+        .into_iter()
+        .map(|index| cache.node_path(index).unwrap())
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            root.join("m/x.txt"),
+            root.join("n/x.txt"),
+            root.join("z/x.txt")
+        ]
+    );
+    assert_eq!(
+        cache.node_index_for_path(&root.join("n/x.txt")),
+        Some(moved)
+    );
+}
+
+#[test]
+fn test_paired_rename_replaces_existing_destination() {
+    let (mut cache, root) = build_initial_cache(&["old.txt", "target.txt"]);
+    let moved = cache.node_index_for_path(&root.join("old.txt")).unwrap();
+    let total = cache.get_total_files();
+
+    std::fs::rename(root.join("old.txt"), root.join("target.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            FsEvent {
+                path: root.join("old.txt"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsFile,
+                id: 30,
+            },
+            FsEvent {
+                path: root.join("target.txt"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsFile,
+                id: 31,
+            },
+        ])
+        .unwrap();
+
+    assert_eq!(
+        cache.node_index_for_path(&root.join("target.txt")),
+        Some(moved)
+    );
+    assert_eq!(search(&mut cache, "target.txt"), vec![moved]);
+    assert!(search(&mut cache, "old.txt").is_empty());
+    assert_eq!(cache.get_total_files(), total - 1);
+}
+
+#[test]
+fn test_paired_rename_applies_earlier_changes_at_new_location() {
+    let (mut cache, root) = build_initial_cache(&["dir/existing.txt"]);
+
+    std::fs::File::create(root.join("dir/created.txt")).unwrap();
+    std::fs::rename(root.join("dir"), root.join("renamed")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            FsEvent {
+                path: root.join("dir/created.txt"),
+                flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+                id: 40,
+            },
+            FsEvent {
+                path: root.join("dir"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                id: 41,
+            },
+            FsEvent {
+                path: root.join("renamed"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                id: 42,
+            },
+        ])
+        .unwrap();
+
+    assert!(
+        cache
+            .node_index_for_path(&root.join("renamed/created.txt"))
+            .is_some()
+    );
+    assert!(
+        cache
+            .node_index_for_path(&root.join("renamed/existing.txt"))
+            .is_some()
+    );
+    assert_eq!(cache.node_index_for_path(&root.join("dir")), None);
+}
+
+#[test]
+fn test_rename_pair_that_does_not_match_filesystem_is_rescanned() {
+    let (mut cache, root) = build_initial_cache(&["first.txt", "second.txt"]);
+
+    // Consecutive ids, but `first.txt` was never moved: both paths get rescanned.
+    std::fs::remove_file(root.join("second.txt")).unwrap();
+    std::fs::write(root.join("moved_in.txt"), "different content").unwrap();
+    cache
+        .handle_fs_events(vec![
+            FsEvent {
+                path: root.join("second.txt"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsFile,
+                id: 50,
+            },
+            FsEvent {
+                path: root.join("moved_in.txt"),
+                flag: EventFlag::ItemRenamed | EventFlag::ItemIsFile,
+                id: 51,
+            },
+        ])
+        .unwrap();
+
+    assert!(search(&mut cache, "second.txt").is_empty());
+    let moved_in = search(&mut cache, "moved_in.txt");
+    assert_eq!(moved_in.len(), 1);
+    assert_eq!(
+        cache.node_path(moved_in[0]).unwrap(),
+        root.join("moved_in.txt")
+    );
+    assert_eq!(search(&mut cache, "first.txt").len(), 1);
+}
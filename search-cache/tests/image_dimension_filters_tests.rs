@@ -0,0 +1,377 @@
+//! Tests for the image filters: width:, height:, dimensions:, orientation:
+//! and bitdepth:, over hand-built PNG, JPEG, GIF, BMP, WebP, TIFF and HEIF
+//! headers.
+
+mod common;
+
+use cardinal_sdk::{EventFlag, FsEvent};
+use common::{cache_with_files, paths, query};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{
+    fs::{self, File},
+    time::{Duration, UNIX_EPOCH},
+};
+use tempdir::TempDir;
+
+fn png(width: u32, height: u32, depth: u8, color_type: u8) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+    bytes.extend_from_slice(&13u32.to_be_bytes());
+    bytes.extend_from_slice(b"IHDR");
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+    bytes.extend_from_slice(&[0; 4]);
+    bytes
+}
+
+fn jpeg_segment(marker: u8, body: &[u8]) -> Vec<u8> {
+    let mut segment = vec![0xff, marker];
+    segment.extend_from_slice(&(body.len() as u16 + 2).to_be_bytes());
+    segment.extend_from_slice(body);
+    segment
+}
+
+/// A baseline JPEG with a JFIF header and, optionally, a big-endian EXIF
+/// block carrying only the orientation.
+fn jpeg(width: u16, height: u16, orientation: Option<u16>) -> Vec<u8> {
+    let mut bytes = vec![0xff, 0xd8];
+    bytes.extend(jpeg_segment(0xe0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0"));
+    if let Some(orientation) = orientation {
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08".to_vec();
+        exif.extend_from_slice(&1u16.to_be_bytes());
+        exif.extend_from_slice(&274u16.to_be_bytes());
+        exif.extend_from_slice(&3u16.to_be_bytes());
+        exif.extend_from_slice(&1u32.to_be_bytes());
+        exif.extend_from_slice(&orientation.to_be_bytes());
+        exif.extend_from_slice(&[0; 2]);
+        exif.extend_from_slice(&[0; 4]);
+        bytes.extend(jpeg_segment(0xe1, &exif));
+    }
+    let mut frame = vec![8];
+    frame.extend_from_slice(&height.to_be_bytes());
+    frame.extend_from_slice(&width.to_be_bytes());
+    frame.push(3);
+    for component in 1..=3 {
+        frame.extend_from_slice(&[component, 0x11, 0]);
+    }
+    bytes.extend(jpeg_segment(0xc0, &frame));
+    bytes.extend(jpeg_segment(0xda, &[1, 1, 0, 0, 0x3f, 0]));
+    bytes.extend_from_slice(&[0xff, 0xd9]);
+    bytes
+}
+
+fn gif(width: u16, height: u16) -> Vec<u8> {
+    let mut bytes = b"GIF89a".to_vec();
+    bytes.extend_from_slice(&width.to_le_bytes());
+    bytes.extend_from_slice(&height.to_le_bytes());
+    // A global color table of 256 entries.
+    bytes.extend_from_slice(&[0xf7, 0, 0]);
+    bytes.extend_from_slice(&[0; 768]);
+    bytes.push(0x3b);
+    bytes
+}
+
+/// A BITMAPINFOHEADER bitmap; a negative height is stored top-down.
+fn bmp(width: i32, height: i32, bits: u16) -> Vec<u8> {
+    let mut bytes = b"BM".to_vec();
+    bytes.extend_from_slice(&[0; 12]);
+    bytes.extend_from_slice(&40u32.to_le_bytes());
+    bytes.extend_from_slice(&width.to_le_bytes());
+    bytes.extend_from_slice(&height.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&bits.to_le_bytes());
+    bytes.extend_from_slice(&[0; 24]);
+    bytes
+}
+
+fn webp_vp8x(width: u32, height: u32, alpha: bool) -> Vec<u8> {
+    let mut chunk = vec![if alpha { 0x10 } else { 0 }, 0, 0, 0];
+    chunk.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+    chunk.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+    let mut bytes = b"RIFF".to_vec();
+    bytes.extend_from_slice(&(4 + 8 + chunk.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WEBPVP8X");
+    bytes.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&chunk);
+    bytes
+}
+
+/// A little-endian TIFF whose three bits-per-sample values live outside the
+/// directory, as they do in RGB files.
+fn tiff(width: u32, height: u32) -> Vec<u8> {
+    let entries: [(u16, u16, u32, u32); 4] = [
+        (256, 4, 1, width),
+        (257, 4, 1, height),
+        (258, 3, 3, 8 + 2 + 4 * 12 + 4),
+        (277, 3, 1, 3),
+    ];
+    let mut bytes = b"II\x2a\0".to_vec();
+    bytes.extend_from_slice(&8u32.to_le_bytes());
+    bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, kind, count, value) in entries {
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&kind.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    for _ in 0..3 {
+        bytes.extend_from_slice(&8u16.to_le_bytes());
+    }
+    bytes
+}
+
+fn atom(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut atom = (8 + body.len() as u32).to_be_bytes().to_vec();
+    atom.extend_from_slice(kind);
+    atom.extend_from_slice(body);
+    atom
+}
+
+fn ispe(width: u32, height: u32) -> Vec<u8> {
+    let mut body = vec![0; 4];
+    body.extend_from_slice(&width.to_be_bytes());
+    body.extend_from_slice(&height.to_be_bytes());
+    atom(b"ispe", &body)
+}
+
+/// A HEIC whose primary image is rotated a quarter turn, next to a smaller
+/// thumbnail.
+fn heic(width: u32, height: u32) -> Vec<u8> {
+    let mut ipco = ispe(320, 240);
+    ipco.extend(ispe(width, height));
+    ipco.extend(atom(b"irot", &[1]));
+    let mut meta = vec![0; 4];
+    meta.extend(atom(b"hdlr", &[0; 21]));
+    meta.extend(atom(b"iprp", &atom(b"ipco", &ipco)));
+    let mut bytes = atom(b"ftyp", b"heic\0\0\0\0mif1heic");
+    bytes.extend(atom(b"meta", &meta));
+    bytes.extend(atom(b"mdat", &[0; 16]));
+    bytes
+}
+
+/// A HEIF whose box after `ftyp` claims a 64-bit size of `size`.
+fn heif_with_extended_box(brand: &[u8; 4], size: u64) -> Vec<u8> {
+    let mut bytes = atom(b"ftyp", &[&brand[..], b"\0\0\0\0mif1"].concat());
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    bytes.extend_from_slice(b"free");
+    bytes.extend_from_slice(&size.to_be_bytes());
+    bytes.extend(atom(b"meta", &[0; 4]));
+    bytes
+}
+
+/// root/
+///   photos/wide.png (3840x2160 RGBA)   photos/rotated.jpg (4000x3000, EXIF 6)
+///   photos/plain.jpg (1920x1080)       icons/logo.gif (64x64)
+///   icons/old.bmp (100x50, top-down)   web/banner.webp (1200x300, alpha)
+///   scans/page.tiff (2480x3508 RGB)    phone/shot.heic (4032x3024, irot 1)
+///   broken.png   notes.txt
+fn build_cache() -> (TempDir, SearchCache) {
+    let files: [(&str, Vec<u8>); 10] = [
+        ("photos/wide.png", png(3840, 2160, 8, 6)),
+        ("photos/rotated.jpg", jpeg(4000, 3000, Some(6))),
+        ("photos/plain.jpg", jpeg(1920, 1080, None)),
+        ("icons/logo.gif", gif(64, 64)),
+        ("icons/old.bmp", bmp(100, -50, 24)),
+        ("web/banner.webp", webp_vp8x(1200, 300, true)),
+        ("scans/page.tiff", tiff(2480, 3508)),
+        ("phone/shot.heic", heic(4032, 3024)),
+        ("broken.png", b"not an image at all".to_vec()),
+        ("notes.txt", b"1920x1080".to_vec()),
+    ];
+    cache_with_files("image_dimension_filters", files)
+}
+
+#[test]
+fn width_and_height_read_every_format() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT width:>=1000"),
+        paths(&[
+            "phone/shot.heic",
+            "photos/plain.jpg",
+            "photos/rotated.jpg",
+            "photos/wide.png",
+            "scans/page.tiff",
+            "web/banner.webp",
+        ])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT height:50"),
+        paths(&["icons/old.bmp"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT width:60..200"),
+        paths(&["icons/logo.gif", "icons/old.bmp"])
+    );
+}
+
+#[test]
+fn orientation_follows_exif_and_heif_rotation() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT orientation:portrait"),
+        paths(&["phone/shot.heic", "photos/rotated.jpg", "scans/page.tiff"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT orientation:Square"),
+        paths(&["icons/logo.gif"])
+    );
+    assert_eq!(
+        query(
+            &mut cache,
+            root,
+            "in:$ROOT/icons orientation:landscape;square"
+        ),
+        paths(&["icons/logo.gif", "icons/old.bmp"])
+    );
+    assert_eq!(
+        query(
+            &mut cache,
+            root,
+            "in:$ROOT type:picture width:>=3840 orientation:landscape"
+        ),
+        paths(&["photos/wide.png"])
+    );
+}
+
+#[test]
+fn dimensions_compare_both_sides() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT dimensions:1920x1080"),
+        paths(&["photos/plain.jpg"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT dimensions:>=2400x2400"),
+        paths(&["phone/shot.heic", "photos/rotated.jpg", "scans/page.tiff"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT dimensions:1000x200..1999X1999"),
+        paths(&["photos/plain.jpg", "web/banner.webp"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT/icons dimensions:!=64x64"),
+        paths(&["icons/old.bmp"])
+    );
+}
+
+#[test]
+fn bitdepth_sums_the_channels() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT bitdepth:32"),
+        paths(&["photos/wide.png", "web/banner.webp"])
+    );
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT bitdepth:24"),
+        paths(&[
+            "icons/old.bmp",
+            "photos/plain.jpg",
+            "photos/rotated.jpg",
+            "scans/page.tiff",
+        ])
+    );
+    // No `pixi` property, so the HEIC has no known depth.
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT bitdepth:<=8"),
+        paths(&["icons/logo.gif"])
+    );
+}
+
+#[test]
+fn image_filters_compose_with_negation() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT/photos !width:1920"),
+        paths(&["photos/rotated.jpg", "photos/wide.png"])
+    );
+}
+
+#[test]
+fn edited_images_are_read_again_once_their_mtime_changes() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT width:100"),
+        paths(&["icons/old.bmp"])
+    );
+
+    let path = root.join("photos/wide.png");
+    fs::write(&path, png(100, 100, 8, 2)).unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path,
+            flag: EventFlag::ItemModified | EventFlag::ItemIsFile,
+            id: 1,
+        }])
+        .unwrap();
+
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT width:100"),
+        paths(&["icons/old.bmp", "photos/wide.png"])
+    );
+}
+
+#[test]
+fn oversized_heif_boxes_are_ignored() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let files = [
+        (
+            "phone/wrapping.heic",
+            heif_with_extended_box(b"heic", u64::MAX - 8),
+        ),
+        (
+            "phone/truncated.avif",
+            heif_with_extended_box(b"avif", 1 << 40),
+        ),
+    ];
+    for (id, (name, bytes)) in (1..).zip(files) {
+        let path = root.join(name);
+        fs::write(&path, bytes).unwrap();
+        cache
+            .handle_fs_events(vec![FsEvent {
+                path,
+                flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+                id,
+            }])
+            .unwrap();
+    }
+    assert_eq!(
+        query(&mut cache, root, "in:$ROOT/phone width:>0"),
+        paths(&["phone/shot.heic"])
+    );
+}
+
+#[test]
+fn image_filters_reject_missing_or_malformed_arguments() {
+    let (_tmp, mut cache) = build_cache();
+    for line in [
+        "width:",
+        "height:tall",
+        "width:5..1",
+        "dimensions:1920",
+        "dimensions:1920x1080;800x600",
+        "dimensions:2000x100..1000x200",
+        "orientation:diagonal",
+        "orientation:>1",
+        "bitdepth:deep",
+    ] {
+        let result =
+            cache.search_with_options(line, SearchOptions::default(), CancellationToken::noop());
+        assert!(result.is_err(), "{line} should be rejected");
+    }
+}
@@ -0,0 +1,256 @@
+//! Tests for the index change subscription API
+//! Covers: added/removed/metadata/moved records, event ids, rescans, subscriber lifetime
+
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::{IndexChange, IndexChangeKind, SearchCache};
+use std::{fs, path::PathBuf};
+use tempdir::TempDir;
+
+fn build_cache(files: &[&str]) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("index_changes").unwrap();
+    for file in files {
+        let full = temp_dir.path().join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn event(path: PathBuf, flag: EventFlag, id: u64) -> FsEvent {
+    FsEvent { path, flag, id }
+}
+
+fn of_kind(changes: &[IndexChange], kind: &IndexChangeKind) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = changes
+        .iter()
+        .filter(|change| &change.kind == kind)
+        .map(|change| change.path.clone())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn created_subtree_is_reported_as_added_with_event_id() {
+    let (temp_dir, mut cache) = build_cache(&["keep.txt"]);
+    let root = temp_dir.path();
+    let changes = cache.subscribe_changes();
+
+    fs::create_dir(root.join("fresh")).unwrap();
+    fs::File::create(root.join("fresh/inner.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("fresh"),
+            EventFlag::ItemCreated | EventFlag::ItemIsDir,
+            42,
+        )])
+        .unwrap();
+
+    let batch = changes.try_recv().unwrap();
+    assert_eq!(
+        of_kind(&batch, &IndexChangeKind::Added),
+        vec![root.join("fresh"), root.join("fresh/inner.txt")]
+    );
+    assert!(batch.iter().all(|change| change.event_id == 42));
+    for change in &batch {
+        assert_eq!(cache.node_path(change.index).as_ref(), Some(&change.path));
+    }
+    assert!(changes.try_recv().is_err(), "one batch per handled batch");
+}
+
+#[test]
+fn removed_subtree_is_reported_with_its_old_paths() {
+    let (temp_dir, mut cache) = build_cache(&["gone/a.txt", "gone/deep/b.txt", "keep.txt"]);
+    let root = temp_dir.path();
+    let gone = cache.node_index_for_path(&root.join("gone")).unwrap();
+    let changes = cache.subscribe_changes();
+
+    fs::remove_dir_all(root.join("gone")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("gone"),
+            EventFlag::ItemRemoved | EventFlag::ItemIsDir,
+            7,
+        )])
+        .unwrap();
+
+    let batch = changes.try_recv().unwrap();
+    assert_eq!(
+        of_kind(&batch, &IndexChangeKind::Removed),
+        vec![
+            root.join("gone"),
+            root.join("gone/a.txt"),
+            root.join("gone/deep"),
+            root.join("gone/deep/b.txt"),
+        ]
+    );
+    assert!(batch.iter().any(|change| change.index == gone));
+    assert!(batch.iter().all(|change| change.event_id == 7));
+}
+
+#[test]
+fn modified_file_keeps_its_index_and_reports_metadata_change() {
+    let (temp_dir, mut cache) = build_cache(&["notes.txt"]);
+    let root = temp_dir.path();
+    let notes = cache.node_index_for_path(&root.join("notes.txt")).unwrap();
+    let changes = cache.subscribe_changes();
+
+    fs::write(root.join("notes.txt"), "grown").unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("notes.txt"),
+            EventFlag::ItemModified | EventFlag::ItemIsFile,
+            3,
+        )])
+        .unwrap();
+
+    assert_eq!(
+        cache.node_index_for_path(&root.join("notes.txt")),
+        Some(notes)
+    );
+    let batch = changes.try_recv().unwrap();
+    assert_eq!(
+        batch,
+        vec![IndexChange {
+            kind: IndexChangeKind::MetadataChanged,
+            index: notes,
+            path: root.join("notes.txt"),
+            event_id: 3,
+        }]
+    );
+
+    // Scanning it again without a change reports nothing.
+    cache
+        .handle_fs_events(vec![event(
+            root.join("notes.txt"),
+            EventFlag::ItemModified | EventFlag::ItemIsFile,
+            4,
+        )])
+        .unwrap();
+    assert!(changes.try_recv().is_err());
+}
+
+#[test]
+fn paired_rename_reports_moves_from_old_paths() {
+    let (temp_dir, mut cache) = build_cache(&["src/alpha_dir/one.txt", "dest/keep.txt"]);
+    let root = temp_dir.path();
+    let dir = cache
+        .node_index_for_path(&root.join("src/alpha_dir"))
+        .unwrap();
+    let changes = cache.subscribe_changes();
+
+    fs::rename(root.join("src/alpha_dir"), root.join("dest/beta_dir")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(
+                root.join("src/alpha_dir"),
+                EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                10,
+            ),
+            event(
+                root.join("dest/beta_dir"),
+                EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                11,
+            ),
+        ])
+        .unwrap();
+
+    let batch = changes.try_recv().unwrap();
+    let moved: Vec<&IndexChange> = batch
+        .iter()
+        .filter(|change| matches!(change.kind, IndexChangeKind::Moved { .. }))
+        .collect();
+    assert_eq!(moved.len(), 2);
+    let dir_move = moved.iter().find(|change| change.index == dir).unwrap();
+    assert_eq!(dir_move.path, root.join("dest/beta_dir"));
+    assert_eq!(
+        dir_move.kind,
+        IndexChangeKind::Moved {
+            from: root.join("src/alpha_dir")
+        }
+    );
+    assert_eq!(dir_move.event_id, 11);
+    assert!(
+        moved
+            .iter()
+            .any(|change| change.path == root.join("dest/beta_dir/one.txt")
+                && change.kind
+                    == IndexChangeKind::Moved {
+                        from: root.join("src/alpha_dir/one.txt")
+                    })
+    );
+}
+
+#[test]
+fn rescan_reports_reconciled_changes() {
+    let (temp_dir, mut cache) = build_cache(&["old.txt", "stay.txt"]);
+    let root = temp_dir.path();
+    let changes = cache.subscribe_changes();
+
+    fs::remove_file(root.join("old.txt")).unwrap();
+    fs::File::create(root.join("new.txt")).unwrap();
+    let summary = cache.rescan().unwrap();
+
+    let batch = changes.try_recv().unwrap();
+    assert_eq!(
+        of_kind(&batch, &IndexChangeKind::Added),
+        vec![root.join("new.txt")]
+    );
+    assert_eq!(
+        of_kind(&batch, &IndexChangeKind::Removed),
+        vec![root.join("old.txt")]
+    );
+    assert_eq!(summary.added, 1);
+    assert_eq!(summary.removed, 1);
+}
+
+#[test]
+fn changes_before_subscribing_are_not_delivered() {
+    let (temp_dir, mut cache) = build_cache(&["keep.txt"]);
+    let root = temp_dir.path();
+
+    fs::File::create(root.join("early.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("early.txt"),
+            EventFlag::ItemCreated,
+            1,
+        )])
+        .unwrap();
+    let changes = cache.subscribe_changes();
+
+    fs::File::create(root.join("late.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("late.txt"),
+            EventFlag::ItemCreated,
+            2,
+        )])
+        .unwrap();
+
+    let batch = changes.try_recv().unwrap();
+    assert_eq!(
+        of_kind(&batch, &IndexChangeKind::Added),
+        vec![root.join("late.txt")]
+    );
+    assert!(changes.try_recv().is_err());
+}
+
+#[test]
+fn dropped_subscriber_does_not_affect_others() {
+    let (temp_dir, mut cache) = build_cache(&["keep.txt"]);
+    let root = temp_dir.path();
+    let dropped = cache.subscribe_changes();
+    let kept = cache.subscribe_changes();
+    drop(dropped);
+
+    for (id, name) in [(1, "first.txt"), (2, "second.txt")] {
+        fs::File::create(root.join(name)).unwrap();
+        cache
+            .handle_fs_events(vec![event(root.join(name), EventFlag::ItemCreated, id)])
+            .unwrap();
+    }
+
+    assert_eq!(kept.try_iter().count(), 2);
+}
@@ -0,0 +1,348 @@
+//! Tests for live query subscriptions
+//! Covers: added/removed deltas for names, filters, metadata and renames, reused indices,
+//! duplicate filters and the throttling of their whole-index re-runs
+
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::{QueryDelta, QuerySubscription, SearchCache, SearchOptions, SlabIndex};
+use search_cancel::CancellationToken;
+use std::{fs, path::PathBuf, time::Duration};
+use tempdir::TempDir;
+
+fn build_cache(files: &[&str]) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("live_query").unwrap();
+    for file in files {
+        let full = temp_dir.path().join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn subscribe(cache: &mut SearchCache, query: &str) -> QuerySubscription {
+    cache
+        .subscribe_query(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+}
+
+fn search(cache: &mut SearchCache, query: &str) -> Vec<SlabIndex> {
+    let mut nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    nodes.sort_unstable();
+    nodes
+}
+
+fn event(path: PathBuf, flag: EventFlag, id: u64) -> FsEvent {
+    FsEvent { path, flag, id }
+}
+
+/// Applies every received delta to `results`, returning them sorted.
+fn apply_deltas(subscription: &QuerySubscription, results: &mut Vec<SlabIndex>) {
+    for QueryDelta { added, removed, .. } in subscription.deltas.try_iter() {
+        results.retain(|index| !removed.contains(index));
+        results.extend(added);
+    }
+    results.sort_unstable();
+}
+
+#[test]
+fn created_and_removed_matches_are_pushed() {
+    let (temp_dir, mut cache) = build_cache(&["app.log", "notes.txt"]);
+    let root = temp_dir.path();
+    let subscription = subscribe(&mut cache, "ext:log");
+    assert_eq!(subscription.nodes.len(), 1);
+
+    fs::File::create(root.join("other.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("other.txt"),
+            EventFlag::ItemCreated,
+            5,
+        )])
+        .unwrap();
+    assert!(
+        subscription.deltas.try_recv().is_err(),
+        "non-matching changes push nothing"
+    );
+
+    fs::File::create(root.join("server.log")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("server.log"),
+            EventFlag::ItemCreated,
+            6,
+        )])
+        .unwrap();
+    let server = cache.node_index_for_path(&root.join("server.log")).unwrap();
+    assert_eq!(
+        subscription.deltas.try_recv().unwrap(),
+        QueryDelta {
+            added: vec![server],
+            removed: vec![],
+            event_id: 6,
+        }
+    );
+
+    let app = subscription.nodes[0];
+    fs::remove_file(root.join("app.log")).unwrap();
+    cache
+        .handle_fs_events(vec![event(root.join("app.log"), EventFlag::ItemRemoved, 7)])
+        .unwrap();
+    assert_eq!(
+        subscription.deltas.try_recv().unwrap(),
+        QueryDelta {
+            added: vec![],
+            removed: vec![app],
+            event_id: 7,
+        }
+    );
+}
+
+#[test]
+fn scoped_filter_query_only_reports_nodes_in_scope() {
+    let (temp_dir, mut cache) = build_cache(&["logs/old.log", "elsewhere/keep.txt"]);
+    let root = temp_dir.path();
+    let query = format!("ext:log infolder:{}", root.join("logs").display());
+    let subscription = subscribe(&mut cache, &query);
+    let mut results = subscription.nodes.clone();
+
+    fs::File::create(root.join("elsewhere/stray.log")).unwrap();
+    fs::File::create(root.join("logs/new.log")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(root.join("elsewhere/stray.log"), EventFlag::ItemCreated, 1),
+            event(root.join("logs/new.log"), EventFlag::ItemCreated, 2),
+        ])
+        .unwrap();
+
+    apply_deltas(&subscription, &mut results);
+    assert_eq!(results, search(&mut cache, &query));
+    assert!(
+        results.contains(
+            &cache
+                .node_index_for_path(&root.join("logs/new.log"))
+                .unwrap()
+        )
+    );
+}
+
+#[test]
+fn metadata_change_moves_node_in_and_out_of_results() {
+    let (temp_dir, mut cache) = build_cache(&["data.bin"]);
+    let root = temp_dir.path();
+    let data = cache.node_index_for_path(&root.join("data.bin")).unwrap();
+    let subscription = subscribe(&mut cache, "size:>3");
+    assert!(subscription.nodes.is_empty());
+
+    fs::write(root.join("data.bin"), "grown").unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("data.bin"),
+            EventFlag::ItemModified | EventFlag::ItemIsFile,
+            1,
+        )])
+        .unwrap();
+    assert_eq!(subscription.deltas.try_recv().unwrap().added, vec![data]);
+
+    fs::write(root.join("data.bin"), "").unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("data.bin"),
+            EventFlag::ItemModified | EventFlag::ItemIsFile,
+            2,
+        )])
+        .unwrap();
+    assert_eq!(subscription.deltas.try_recv().unwrap().removed, vec![data]);
+}
+
+#[test]
+fn rename_in_place_updates_name_queries() {
+    let (temp_dir, mut cache) = build_cache(&["draft.txt", "keep.txt"]);
+    let root = temp_dir.path();
+    let draft = cache.node_index_for_path(&root.join("draft.txt")).unwrap();
+    let subscription = subscribe(&mut cache, "final");
+
+    fs::rename(root.join("draft.txt"), root.join("final.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(root.join("draft.txt"), EventFlag::ItemRenamed, 10),
+            event(root.join("final.txt"), EventFlag::ItemRenamed, 11),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        subscription.deltas.try_recv().unwrap(),
+        QueryDelta {
+            added: vec![draft],
+            removed: vec![],
+            event_id: 11,
+        }
+    );
+}
+
+#[test]
+fn reused_index_is_reported_as_removed_and_added() {
+    let (temp_dir, mut cache) = build_cache(&["first.log", "keep.txt"]);
+    let root = temp_dir.path();
+    let subscription = subscribe(&mut cache, "ext:log");
+    let mut results = subscription.nodes.clone();
+
+    fs::remove_file(root.join("first.log")).unwrap();
+    fs::File::create(root.join("second.log")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(root.join("first.log"), EventFlag::ItemRemoved, 1),
+            event(root.join("second.log"), EventFlag::ItemCreated, 2),
+        ])
+        .unwrap();
+
+    apply_deltas(&subscription, &mut results);
+    assert_eq!(results, search(&mut cache, "ext:log"));
+    assert_eq!(
+        results,
+        vec![cache.node_index_for_path(&root.join("second.log")).unwrap()]
+    );
+}
+
+#[test]
+fn duplicate_query_tracks_copies_of_untouched_nodes() {
+    let (temp_dir, mut cache) = build_cache(&["report.txt", "archive/notes.txt"]);
+    let root = temp_dir.path();
+    cache.set_live_query_rerun_interval(Duration::ZERO);
+    let subscription = subscribe(&mut cache, "dupe: ext:txt");
+    assert!(subscription.nodes.is_empty());
+    let mut results = subscription.nodes.clone();
+
+    fs::File::create(root.join("archive/report.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("archive/report.txt"),
+            EventFlag::ItemCreated,
+            1,
+        )])
+        .unwrap();
+    apply_deltas(&subscription, &mut results);
+    let mut copies = vec![
+        cache.node_index_for_path(&root.join("report.txt")).unwrap(),
+        cache
+            .node_index_for_path(&root.join("archive/report.txt"))
+            .unwrap(),
+    ];
+    copies.sort_unstable();
+    assert_eq!(results, copies);
+
+    // Touching one copy keeps both, although the other wasn't touched.
+    fs::write(root.join("report.txt"), "edited").unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("report.txt"),
+            EventFlag::ItemModified | EventFlag::ItemIsFile,
+            2,
+        )])
+        .unwrap();
+    apply_deltas(&subscription, &mut results);
+    assert_eq!(results, copies);
+
+    fs::remove_file(root.join("archive/report.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("archive/report.txt"),
+            EventFlag::ItemRemoved,
+            3,
+        )])
+        .unwrap();
+    apply_deltas(&subscription, &mut results);
+    assert!(results.is_empty());
+    assert_eq!(results, search(&mut cache, "dupe: ext:txt"));
+}
+
+#[test]
+fn duplicate_query_reruns_are_throttled() {
+    let (temp_dir, mut cache) = build_cache(&["report.txt", "archive/notes.txt"]);
+    let root = temp_dir.path();
+    cache.set_live_query_rerun_interval(Duration::from_secs(3600));
+    let dupes = subscribe(&mut cache, "dupe: ext:txt");
+    let names = subscribe(&mut cache, "report");
+
+    // Both batches land within the interval of the initial search.
+    for (id, name) in [(1, "archive/report.txt"), (2, "archive/notes-2.txt")] {
+        fs::File::create(root.join(name)).unwrap();
+        cache
+            .handle_fs_events(vec![event(root.join(name), EventFlag::ItemCreated, id)])
+            .unwrap();
+    }
+    assert!(dupes.deltas.try_recv().is_err());
+    // Queries without a duplicate filter are not throttled.
+    let mut names_results = names.nodes.clone();
+    apply_deltas(&names, &mut names_results);
+    assert_eq!(names_results, search(&mut cache, "report"));
+
+    // Not due yet.
+    cache.refresh_live_queries();
+    assert!(dupes.deltas.try_recv().is_err());
+
+    cache.set_live_query_rerun_interval(Duration::ZERO);
+    cache.refresh_live_queries();
+    let deltas: Vec<QueryDelta> = dupes.deltas.try_iter().collect();
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].event_id, 2);
+    let mut results = dupes.nodes.clone();
+    results.extend(deltas[0].added.iter().copied());
+    results.sort_unstable();
+    assert_eq!(results, search(&mut cache, "dupe: ext:txt"));
+
+    // Nothing left to flush.
+    cache.refresh_live_queries();
+    assert!(dupes.deltas.try_recv().is_err());
+}
+
+#[test]
+fn rescan_pushes_deltas() {
+    let (temp_dir, mut cache) = build_cache(&["old.log", "keep.txt"]);
+    let root = temp_dir.path();
+    let subscription = subscribe(&mut cache, "ext:log");
+    let mut results = subscription.nodes.clone();
+
+    fs::remove_file(root.join("old.log")).unwrap();
+    fs::File::create(root.join("new.log")).unwrap();
+    cache.rescan().unwrap();
+
+    apply_deltas(&subscription, &mut results);
+    assert_eq!(results, search(&mut cache, "ext:log"));
+}
+
+#[test]
+fn dropped_subscription_stops_updates() {
+    let (temp_dir, mut cache) = build_cache(&["keep.txt"]);
+    let root = temp_dir.path();
+    drop(subscribe(&mut cache, "ext:log"));
+    let kept = subscribe(&mut cache, "ext:log");
+
+    fs::File::create(root.join("fresh.log")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("fresh.log"),
+            EventFlag::ItemCreated,
+            1,
+        )])
+        .unwrap();
+    assert_eq!(kept.deltas.try_iter().count(), 1);
+}
+
+#[test]
+fn invalid_query_is_rejected_on_subscribe() {
+    let (_temp_dir, mut cache) = build_cache(&["keep.txt"]);
+    assert!(
+        cache
+            .subscribe_query(
+                "regex:[",
+                SearchOptions::default(),
+                CancellationToken::noop()
+            )
+            .is_err()
+    );
+}
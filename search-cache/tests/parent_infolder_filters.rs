@@ -384,6 +384,7 @@ fn scope_filters_follow_case_insensitive_option() {
     let wrong_case_scope = root.join("casescope");
     let case_insensitive = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
 
     let parent_query = format!("parent:{}", wrong_case_scope.display());
@@ -439,6 +440,7 @@ fn scope_filters_follow_case_insensitive_option() {
                 &parent_query,
                 SearchOptions {
                     case_insensitive: false,
+                    ..Default::default()
                 },
                 CancellationToken::noop(),
             )
@@ -163,6 +163,7 @@ fn case_insensitive_option_matrix() {
     let mut cache = build_cache();
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let insensitive = cache
         .search_with_options("readme.md", opts, CancellationToken::noop())
@@ -172,6 +173,7 @@ fn case_insensitive_option_matrix() {
         .len();
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let sensitive = cache
         .search_with_options("readme.md", opts, CancellationToken::noop())
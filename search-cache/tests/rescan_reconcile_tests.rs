@@ -0,0 +1,145 @@
+//! Tests for diff-based rescans
+//! Covers: SlabIndex stability, added/removed/modified summary, ignore changes
+
+use search_cache::{SearchCache, SearchOptions, SlabIndex, WalkData};
+use search_cancel::CancellationToken;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tempdir::TempDir;
+
+fn build_cache(files: &[&str]) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("rescan_reconcile").unwrap();
+    for file in files {
+        let full = temp_dir.path().join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn search_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap()
+        .into_iter()
+        .map(|index| cache.node_path(index).unwrap())
+        .collect()
+}
+
+fn index_of(cache: &SearchCache, path: &Path) -> SlabIndex {
+    cache.node_index_for_path(path).unwrap()
+}
+
+#[test]
+fn rescan_keeps_indices_of_unchanged_nodes() {
+    let (temp_dir, mut cache) = build_cache(&["src/main.rs", "src/lib.rs", "docs/readme.md"]);
+    let root = temp_dir.path();
+    let main_rs = index_of(&cache, &root.join("src/main.rs"));
+    let src = index_of(&cache, &root.join("src"));
+    let readme = index_of(&cache, &root.join("docs/readme.md"));
+
+    fs::remove_file(root.join("src/lib.rs")).unwrap();
+    fs::File::create(root.join("src/new.rs")).unwrap();
+    fs::create_dir(root.join("new_dir")).unwrap();
+    fs::File::create(root.join("new_dir/a.rs")).unwrap();
+
+    let summary = cache.rescan().unwrap();
+    assert_eq!(summary.added, 3);
+    assert_eq!(summary.removed, 1);
+
+    assert_eq!(index_of(&cache, &root.join("src/main.rs")), main_rs);
+    assert_eq!(index_of(&cache, &root.join("src")), src);
+    assert_eq!(index_of(&cache, &root.join("docs/readme.md")), readme);
+    assert_eq!(cache.node_index_for_path(&root.join("src/lib.rs")), None);
+
+    // The reconciled cache answers like a freshly walked one, in the same order.
+    let mut fresh = SearchCache::walk_fs(root);
+    assert_eq!(cache.get_total_files(), fresh.get_total_files());
+    assert_eq!(
+        search_paths(&mut cache, ".rs"),
+        search_paths(&mut fresh, ".rs")
+    );
+    assert_eq!(
+        search_paths(&mut cache, ".rs"),
+        vec![
+            root.join("new_dir/a.rs"),
+            root.join("src/main.rs"),
+            root.join("src/new.rs"),
+        ]
+    );
+}
+
+#[test]
+fn rescan_of_unchanged_tree_adds_and_removes_nothing() {
+    let (temp_dir, mut cache) = build_cache(&["a/b/c.txt", "d.txt"]);
+    let root = temp_dir.path();
+    let before: Vec<SlabIndex> = ["a", "a/b", "a/b/c.txt", "d.txt"]
+        .iter()
+        .map(|path| index_of(&cache, &root.join(path)))
+        .collect();
+
+    let summary = cache.rescan().unwrap();
+    assert_eq!((summary.added, summary.removed), (0, 0));
+
+    let after: Vec<SlabIndex> = ["a", "a/b", "a/b/c.txt", "d.txt"]
+        .iter()
+        .map(|path| index_of(&cache, &root.join(path)))
+        .collect();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn rescan_counts_nodes_with_changed_metadata() {
+    let (temp_dir, mut cache) = build_cache(&["docs/readme.md"]);
+    let root = temp_dir.path();
+    let docs = index_of(&cache, &root.join("docs"));
+
+    // Directory mtimes have second granularity in the slab.
+    std::thread::sleep(Duration::from_millis(1100));
+    fs::File::create(root.join("docs/guide.md")).unwrap();
+
+    let summary = cache.rescan().unwrap();
+    assert_eq!(summary.added, 1);
+    assert_eq!(summary.removed, 0);
+    assert!(summary.modified >= 1, "{summary:?}");
+    assert_eq!(index_of(&cache, &root.join("docs")), docs);
+}
+
+#[test]
+fn rescan_with_new_ignore_paths_frees_ignored_subtree() {
+    let (temp_dir, mut cache) = build_cache(&["keep/a.txt", "skip/b.txt", "skip/deep/c.txt"]);
+    let root = temp_dir.path();
+    let keep = index_of(&cache, &root.join("keep/a.txt"));
+
+    let ignore_paths = vec![root.join("skip")];
+    let walk_data = WalkData::new(root, &ignore_paths, &[], false, || false);
+    let summary = cache.rescan_with_walk_data(&walk_data).unwrap();
+
+    assert_eq!((summary.added, summary.removed), (0, 4));
+    assert_eq!(cache.node_index_for_path(&root.join("skip")), None);
+    assert_eq!(index_of(&cache, &root.join("keep/a.txt")), keep);
+    assert_eq!(&*cache.ignore_paths(), ignore_paths.as_slice());
+}
+
+#[test]
+fn rescan_of_noop_cache_reports_every_node_as_added() {
+    static NEVER_STOPPED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    let temp_dir = TempDir::new("rescan_reconcile_noop").unwrap();
+    fs::write(temp_dir.path().join("file.txt"), "data").unwrap();
+    let mut cache = SearchCache::noop(
+        temp_dir.path().to_path_buf(),
+        vec![],
+        vec![],
+        &NEVER_STOPPED,
+    );
+
+    let summary = cache.rescan().unwrap();
+    assert_eq!(summary.added, cache.get_total_files());
+    assert_eq!(summary.removed, 0);
+}
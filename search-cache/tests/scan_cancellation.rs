@@ -1,3 +1,4 @@
+use cardinal_sdk::{ResumeCursor, SourceKind};
 use search_cache::SearchCache;
 use search_cancel::CancellationToken;
 use std::{
@@ -276,15 +277,15 @@ fn noop_cache_signals_do_not_persist() {
     );
 }
 
-// ── noop cache property tests (last_event_id, rescan_count, search) ──────────
+// ── noop cache property tests (resume cursor, rescan_count, search) ───────────
 
 #[test]
 fn noop_cache_last_event_id_is_zero() {
-    let mut cache = SearchCache::noop(PathBuf::from("/w"), vec![], vec![], &NEVER_STOPPED);
+    let cache = SearchCache::noop(PathBuf::from("/w"), vec![], vec![], &NEVER_STOPPED);
     assert_eq!(
-        cache.last_event_id(),
-        0,
-        "noop cache must have last_event_id == 0 (used to spawn EventWatcher)"
+        cache.resume_cursor(),
+        ResumeCursor::new(SourceKind::native(), 0),
+        "noop cache must resume the native watcher from event id 0"
     );
 }
 
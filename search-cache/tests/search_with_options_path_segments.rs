@@ -30,6 +30,7 @@ fn leading_slash_anchors_to_root_segment() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/foo/bar/baz.txt",
@@ -57,6 +58,7 @@ fn trailing_slash_requires_exact_last_segment() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("docs/guide/", opts, CancellationToken::noop()));
@@ -98,6 +100,7 @@ fn case_insensitive_segments_match_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("/foo/bar/baz/", opts, CancellationToken::noop()));
@@ -119,6 +122,7 @@ fn mixed_prefix_suffix_segments_for_files() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo/report.txt", opts, CancellationToken::noop()));
@@ -159,6 +163,7 @@ fn trailing_slash_deep_exact_directory() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("a/b/c/d/", opts, CancellationToken::noop()));
@@ -217,6 +222,7 @@ fn mixed_case_segments_case_sensitive_behavior() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo/bar/baz/", opts, CancellationToken::noop()));
@@ -245,6 +251,7 @@ fn mixed_case_segments_case_insensitive_behavior() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("/foo/bar/baz/", opts, CancellationToken::noop()));
@@ -274,6 +281,7 @@ fn wildcard_last_segment_multiple_extensions() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "docs/guide/readme.*",
@@ -309,6 +317,7 @@ fn wildcard_last_segment_multiple_extensions_case_insensitive() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "docs/guide/readme*.md",
@@ -583,6 +592,7 @@ fn globstar_case_sensitive_vs_insensitive_variants() {
 
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let insensitive =
         guard_indices(cache.search_with_options("aa/**/file.txt", opts, CancellationToken::noop()));
@@ -809,6 +819,7 @@ fn unicode_path_segments_case_insensitive() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/café/文件/notes.txt",
@@ -840,6 +851,7 @@ fn unicode_normalization_equivalent_forms_should_match() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
 
     // Filesystems may surface NFC or NFD names; query using the opposite form
@@ -880,6 +892,7 @@ fn unicode_already_nfd_query_keeps_matching() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -907,6 +920,7 @@ fn unicode_noncanonical_combining_order_is_normalized() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -936,6 +950,7 @@ fn unicode_composed_query_with_wildcard_matches_decomposed_path() {
         &query,
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -971,6 +986,7 @@ fn unicode_normalization_preserves_boolean_or_behavior() {
         &query,
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -997,6 +1013,7 @@ fn unicode_path_segments_case_sensitive() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "café/文件/notes.txt",
@@ -1178,6 +1195,7 @@ fn case_sensitive_exact_segment_casing() {
     // Case sensitive: only exact lower-case path should be returned for lower-case query.
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("src/lib/core/", opts, CancellationToken::noop()));
@@ -1207,6 +1225,7 @@ fn case_insensitive_directory_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("/src/lib/core/", opts, CancellationToken::noop()));
@@ -1234,6 +1253,7 @@ fn mixed_wildcard_case_sensitive_file_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "app/config/readme.*",
@@ -1270,6 +1290,7 @@ fn mixed_wildcard_case_insensitive_file_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/app/config/readme.*",
@@ -1298,6 +1319,7 @@ fn case_sensitive_file_exact_match_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "guide/ReadMe.md",
@@ -1323,6 +1345,7 @@ fn case_insensitive_file_exact_match_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "guide/readme.md",
@@ -1352,6 +1375,7 @@ fn unicode_ascii_only_query_uses_fast_path_correctly() {
         "ascii_docs/readme.txt",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1378,6 +1402,7 @@ fn unicode_trailing_slash_nfc_query_matches_nfd_directory() {
         query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1409,6 +1434,7 @@ fn unicode_angstrom_sign_query_matches_a_ring_decomposed_path() {
         query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1438,6 +1464,7 @@ fn unicode_uppercase_nfc_query_matches_lowercase_nfd_dir_case_insensitive() {
         query,
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1472,6 +1499,7 @@ fn unicode_nfc_wildcard_suffix_matches_nfd_paths() {
         query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1515,6 +1543,7 @@ fn unicode_nfc_or_query_matches_two_nfd_directories() {
         query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1552,6 +1581,7 @@ fn unicode_nfc_multi_segment_deep_path_matches_nfd_filesystem() {
         query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1578,6 +1608,7 @@ fn unicode_nfc_composed_filename_matches_nfd_stored_filename() {
         nfc_query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1612,6 +1643,7 @@ fn unicode_write_nfc_then_search_nfc_exact_path() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1648,6 +1680,7 @@ fn unicode_write_nfc_then_search_nfc_wildcard() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
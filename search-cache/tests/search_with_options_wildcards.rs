@@ -22,6 +22,7 @@ fn single_segment_wildcard_complex_pattern_case_sensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo*alpha*.txt", opts, CancellationToken::noop()));
@@ -43,6 +44,7 @@ fn single_segment_wildcard_complex_pattern_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo*bar*.txt", opts, CancellationToken::noop()));
@@ -66,6 +68,7 @@ fn leading_wildcard_matches_suffix() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("*beta.txt", opts, CancellationToken::noop()));
@@ -86,6 +89,7 @@ fn trailing_wildcard_matches_prefix() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("alpha*", opts, CancellationToken::noop()));
@@ -107,6 +111,7 @@ fn question_mark_single_character() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("file?.txt", opts, CancellationToken::noop()));
@@ -128,6 +133,7 @@ fn star_only_matches_all_files() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options("*", opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
@@ -156,6 +162,7 @@ fn multi_segment_wildcard_intersection_case_sensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Both segments must match: alpha* AND *beta*.txt (beta can appear later)
     let indices = guard_indices(cache.search_with_options(
@@ -184,6 +191,7 @@ fn multi_segment_wildcard_intersection_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "alpha* *beta*.txt",
@@ -211,6 +219,7 @@ fn complex_mixed_wildcards_and_question_mark() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Pattern: a*b?c*.txt => a then any, b then any single char, c then any, .txt
     let indices =
@@ -66,6 +66,7 @@ fn tag_filter_matches_case_insensitive() {
         "tag:project",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -92,6 +93,7 @@ fn tag_filter_matches_substring() {
         "tag:Alpha",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -100,6 +102,40 @@ fn tag_filter_matches_substring() {
     assert!(nodes[0].path.ends_with("first.txt"));
 }
 
+#[test]
+fn tag_filter_folds_non_ascii_case() {
+    let temp_dir = TempDir::new("tag_filter_unicode").unwrap();
+    let dir = temp_dir.path();
+
+    let first = dir.join("first.txt");
+    fs::write(&first, b"dummy").unwrap();
+    write_tags(&first, &["ÄRGER", "Straße"]);
+
+    let second = dir.join("second.txt");
+    fs::write(&second, b"dummy").unwrap();
+    write_tags(&second, &["Istanbul"]);
+
+    let mut cache = SearchCache::walk_fs(dir);
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    for query in ["tag:ärger", "tag:STRAẞE"] {
+        let indices =
+            guard_indices(cache.search_with_options(query, options, CancellationToken::noop()));
+        assert_eq!(indices.len(), 1, "{query}");
+        let nodes = cache.expand_file_nodes(&indices);
+        assert!(nodes[0].path.ends_with("first.txt"));
+    }
+    // Dotless ı only matches itself.
+    let indices = guard_indices(cache.search_with_options(
+        "tag:ıstanbul",
+        options,
+        CancellationToken::noop(),
+    ));
+    assert!(indices.is_empty());
+}
+
 #[test]
 fn tag_filter_accepts_semicolon_list_matches_any() {
     let temp_dir = TempDir::new("tag_filter_list_any").unwrap();
@@ -140,6 +176,7 @@ fn tag_filter_case_sensitive_exact_match() {
         "tag:Project",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -170,6 +207,7 @@ fn tag_filter_case_insensitive_matches_both() {
         "tag:project",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -830,6 +868,7 @@ fn tag_filter_case_sensitive_substring() {
         "tag:Alpha",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -870,6 +909,7 @@ fn tag_filter_mixed_case_in_query() {
         "tag:PrOjEcT",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -998,6 +1038,7 @@ fn tag_filter_list_case_insensitive_duplicates() {
         "tag:Project;project;PROJECT",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1221,6 +1262,7 @@ fn tag_filter_partial_match_at_word_boundary() {
         "tag:work",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1266,6 +1308,7 @@ fn tag_filter_list_case_sensitive_no_match() {
         "tag:Project;Important",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1287,6 +1330,7 @@ fn tag_filter_list_case_insensitive_match() {
         "tag:Project;Important",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -0,0 +1,124 @@
+//! Case-insensitive search folds case the same way for names, path
+//! segments, extensions and file content.
+
+mod common;
+
+use common::{cache_with_files, insensitive, paths, query_with};
+use search_cache::SearchCache;
+use tempdir::TempDir;
+
+const CONTENT_BUFFER_BYTES: usize = 64 * 1024;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let mut split = vec![b'x'; CONTENT_BUFFER_BYTES - 1];
+    // `Ä` straddles the first read.
+    split.extend_from_slice("ÄRGER".as_bytes());
+    let files: [(&str, &[u8]); 8] = [
+        ("ÄRGER.txt", "Ärger im Büro".as_bytes()),
+        ("Straße.md", "STRAẞE".as_bytes()),
+        ("STRASSE.md", b"strasse"),
+        ("ISTANBUL.txt", "İstanbul".as_bytes()),
+        ("ıstakoz.txt", b""),
+        ("ΟΔΥΣΣΕΥΣ.txt", "ΟΔΥΣΣΕΥΣ".as_bytes()),
+        ("Über/notes.ÄBC", b""),
+        ("split.bin", &split),
+    ];
+    cache_with_files("unicode_case_fold", files)
+}
+
+#[test]
+fn names_fold_beyond_ascii() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "ärger", insensitive()),
+        paths(&["ÄRGER.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "οδυσσευς", insensitive()),
+        paths(&["ΟΔΥΣΣΕΥΣ.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "über", insensitive()),
+        paths(&["Über"])
+    );
+}
+
+#[test]
+fn sharp_s_folds_to_capital_sharp_s_but_not_ss() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "STRAẞE", insensitive()),
+        paths(&["Straße.md"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "strasse", insensitive()),
+        paths(&["STRASSE.md"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "content:straße", insensitive()),
+        paths(&["Straße.md"])
+    );
+}
+
+#[test]
+fn turkish_dotted_and_dotless_i_stay_distinct() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "istanbul", insensitive()),
+        paths(&["ISTANBUL.txt"])
+    );
+    assert!(query_with(&mut cache, root, "ıstanbul", insensitive()).is_empty());
+    assert_eq!(
+        query_with(&mut cache, root, "ıstakoz", insensitive()),
+        paths(&["ıstakoz.txt"])
+    );
+    assert!(query_with(&mut cache, root, "istakoz", insensitive()).is_empty());
+    assert!(query_with(&mut cache, root, "content:istanbul", insensitive()).is_empty());
+    assert_eq!(
+        query_with(&mut cache, root, "content:İSTANBUL", insensitive()),
+        paths(&["ISTANBUL.txt"])
+    );
+}
+
+#[test]
+fn content_folds_utf8_text() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "ext:txt content:ärger", insensitive()),
+        paths(&["ÄRGER.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "content:büro", insensitive()),
+        paths(&["ÄRGER.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "content:οδυσσευς", insensitive()),
+        paths(&["ΟΔΥΣΣΕΥΣ.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "ext:bin content:ärger", insensitive()),
+        paths(&["split.bin"])
+    );
+}
+
+#[test]
+fn path_segments_and_extensions_fold() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "in:$ROOT/über notes", insensitive()),
+        paths(&["Über/notes.ÄBC"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "parent:$ROOT/ÜBER", insensitive()),
+        paths(&["Über/notes.ÄBC"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "ext:äbc", insensitive()),
+        paths(&["Über/notes.ÄBC"])
+    );
+}
@@ -0,0 +1,125 @@
+//! Tests for the `wfn:` modifier, which requires words to equal the whole
+//! file name.
+
+mod common;
+
+use common::{cache_with_names, insensitive, paths, query_with};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    cache_with_names(
+        "whole_filename",
+        &[
+            "report.pdf",
+            "Report.pdf",
+            "old report.pdf",
+            "report.pdf.bak",
+            "notes.txt",
+            "docs/report.pdf",
+            "docs/summary.pdf",
+        ],
+    )
+}
+
+#[test]
+fn wfn_requires_the_whole_name() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "report.pdf", insensitive()),
+        paths(&[
+            "Report.pdf",
+            "docs/report.pdf",
+            "old report.pdf",
+            "report.pdf",
+            "report.pdf.bak",
+        ])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "wfn:report.pdf", insensitive()),
+        paths(&["Report.pdf", "docs/report.pdf", "report.pdf"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "wholefilename:\"old report.pdf\"",
+            insensitive()
+        ),
+        paths(&["old report.pdf"])
+    );
+    assert!(query_with(&mut cache, root, "wfn:report", insensitive()).is_empty());
+}
+
+#[test]
+fn wfn_wildcards_are_anchored() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "wfn:report.*", insensitive()),
+        paths(&[
+            "Report.pdf",
+            "docs/report.pdf",
+            "report.pdf",
+            "report.pdf.bak",
+        ])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "wfn:s*.pdf", insensitive()),
+        paths(&["docs/summary.pdf"])
+    );
+}
+
+#[test]
+fn wfn_combines_with_case_in_either_order() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "wfn:case:Report.pdf", insensitive()),
+        paths(&["Report.pdf"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "case:wfn:report.pdf", insensitive()),
+        paths(&["docs/report.pdf", "report.pdf"])
+    );
+}
+
+#[test]
+fn wfn_applies_to_groups_and_nowfn_opts_out() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "wfn:<notes.txt|summary.pdf>",
+            insensitive()
+        ),
+        paths(&["docs/summary.pdf", "notes.txt"])
+    );
+    assert_eq!(
+        query_with(
+            &mut cache,
+            root,
+            "wfn:(summary.pdf | nowfn:old)",
+            insensitive()
+        ),
+        paths(&["docs/summary.pdf", "old report.pdf"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "wfn:(nowholefilename:bak)", insensitive()),
+        paths(&["report.pdf.bak"])
+    );
+}
+
+#[test]
+fn wfn_without_an_operand_is_rejected() {
+    let (_tmp, mut cache) = build_cache();
+    assert!(
+        cache
+            .search_with_options("wfn:", SearchOptions::default(), CancellationToken::noop())
+            .is_err()
+    );
+}
@@ -0,0 +1,102 @@
+//! Tests for the `ww:` modifier, which only matches whole words of file
+//! names.
+
+mod common;
+
+use common::{cache_with_names, insensitive, paths, query_with};
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    cache_with_names(
+        "whole_word",
+        &[
+            "log",
+            "app.log",
+            "error_log.txt",
+            "ChangeLog",
+            "logFile.txt",
+            "log2.txt",
+            "catalog.txt",
+            "blog.md",
+            "dialog.swift",
+            "changelog.md",
+            "logs/readme.md",
+        ],
+    )
+}
+
+#[test]
+fn ww_skips_words_that_only_contain_the_term() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(query_with(&mut cache, root, "log", insensitive()).len(), 11);
+    assert_eq!(
+        query_with(&mut cache, root, "ww:log", insensitive()),
+        paths(&[
+            "ChangeLog",
+            "app.log",
+            "error_log.txt",
+            "log",
+            "log2.txt",
+            "logFile.txt",
+        ])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "wholeword:\"log.txt\"", insensitive()),
+        paths(&["error_log.txt"])
+    );
+}
+
+#[test]
+fn ww_combines_with_case_and_groups() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "case:ww:Log", insensitive()),
+        paths(&["ChangeLog"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "ww:<blog|catalog>", insensitive()),
+        paths(&["blog.md", "catalog.txt"])
+    );
+    assert_eq!(
+        query_with(&mut cache, root, "ww:(log noww:file)", insensitive()),
+        paths(&["logFile.txt"])
+    );
+}
+
+#[test]
+fn ww_applies_to_every_path_segment() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        query_with(&mut cache, root, "ww:logs/readme", insensitive()),
+        paths(&["logs/readme.md"])
+    );
+    assert!(query_with(&mut cache, root, "ww:logs/read", insensitive()).is_empty());
+    assert_eq!(
+        query_with(&mut cache, root, "logs/read", insensitive()),
+        paths(&["logs/readme.md"])
+    );
+}
+
+#[test]
+fn ww_highlights_the_word_itself() {
+    let (_tmp, mut cache) = build_cache();
+    let outcome = cache
+        .search_with_options("ww:<Log|dialog>", insensitive(), CancellationToken::noop())
+        .unwrap();
+    assert_eq!(outcome.highlights, vec!["dialog", "log"]);
+}
+
+#[test]
+fn ww_without_an_operand_is_rejected() {
+    let (_tmp, mut cache) = build_cache();
+    assert!(
+        cache
+            .search_with_options("ww:", insensitive(), CancellationToken::noop())
+            .is_err()
+    );
+}
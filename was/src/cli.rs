@@ -0,0 +1,90 @@
+use cardinal_sdk::EventFlag;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use glob::Pattern;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub watch: WatchArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Record event batches into a JSON-lines log that `search-cache` can replay.
+    ///
+    /// Runs until interrupted; every batch is flushed as it arrives.
+    Record {
+        /// File the log is written to.
+        #[clap(long, short)]
+        output: PathBuf,
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Path to watch, default to current directory.
+    pub path: Option<String>,
+    /// Start event id, default to 0.
+    #[clap(long, default_value_t = 0)]
+    pub since: u64,
+    /// Only keep events whose path matches one of these globs (`*` also matches `/`).
+    #[clap(long, value_name = "GLOB")]
+    pub include: Vec<Pattern>,
+    /// Drop events whose path matches one of these globs.
+    #[clap(long, value_name = "GLOB")]
+    pub exclude: Vec<Pattern>,
+    /// Only keep events carrying any of these flags, e.g. `ItemRenamed,ItemRemoved`.
+    #[clap(long, value_delimiter = ',', value_parser = parse_flag)]
+    pub flags: Vec<EventFlag>,
+    /// Stop after the batch that reports `HistoryDone`.
+    #[clap(long)]
+    pub until_history_done: bool,
+    /// Stop after this many events passed the filters.
+    #[clap(long, value_name = "N")]
+    pub max_events: Option<usize>,
+}
+
+impl WatchArgs {
+    pub fn path(&self) -> String {
+        self.path.clone().unwrap_or_else(|| {
+            std::env::current_dir()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
+    }
+}
+
+#[derive(Args)]
+pub struct OutputArgs {
+    /// How every event is printed.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Instead of printing events, print event counts per directory and per
+    /// flag every SECS seconds. Runs until interrupted, `--until-history-done`
+    /// or `--max-events`.
+    #[clap(long, value_name = "SECS")]
+    pub summary: Option<f64>,
+    /// Number of directories listed in each summary.
+    #[clap(long, default_value_t = 20)]
+    pub top: usize,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Jsonl,
+    Csv,
+}
+
+fn parse_flag(name: &str) -> Result<EventFlag, String> {
+    EventFlag::from_name(name.trim()).ok_or_else(|| format!("unknown event flag {name:?}"))
+}
@@ -0,0 +1,87 @@
+use crate::cli::WatchArgs;
+use cardinal_sdk::{EventFlag, FsEvent};
+use glob::{MatchOptions, Pattern};
+
+/// Path globs and flags selected on the command line.
+pub struct EventFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    /// Empty means every flag is kept.
+    flags: EventFlag,
+}
+
+impl EventFilter {
+    pub fn new(args: &WatchArgs) -> Self {
+        Self {
+            include: args.include.clone(),
+            exclude: args.exclude.clone(),
+            flags: args
+                .flags
+                .iter()
+                .fold(EventFlag::empty(), |flags, &flag| flags | flag),
+        }
+    }
+
+    pub fn keeps(&self, event: &FsEvent) -> bool {
+        // `*` matching `/` lets `*.log` select logs anywhere below the root.
+        let options = MatchOptions {
+            require_literal_separator: false,
+            ..MatchOptions::default()
+        };
+        let matches = |pattern: &Pattern| pattern.matches_path_with(&event.path, options);
+        (self.flags.is_empty() || event.flag.intersects(self.flags))
+            && (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn filter(include: &[&str], exclude: &[&str], flags: EventFlag) -> EventFilter {
+        let patterns = |globs: &[&str]| globs.iter().map(|g| Pattern::new(g).unwrap()).collect();
+        EventFilter {
+            include: patterns(include),
+            exclude: patterns(exclude),
+            flags,
+        }
+    }
+
+    fn event(path: &str, flag: EventFlag) -> FsEvent {
+        FsEvent {
+            path: PathBuf::from(path),
+            flag,
+            id: 1,
+        }
+    }
+
+    #[test]
+    fn empty_filter_keeps_everything() {
+        let filter = filter(&[], &[], EventFlag::empty());
+        assert!(filter.keeps(&event("/a/b", EventFlag::ItemCreated)));
+        assert!(filter.keeps(&event("/", EventFlag::HistoryDone)));
+    }
+
+    #[test]
+    fn globs_include_then_exclude() {
+        let filter = filter(
+            &["*.log", "/var/**"],
+            &["/var/cache/**"],
+            EventFlag::empty(),
+        );
+        assert!(filter.keeps(&event("/home/me/app.log", EventFlag::ItemModified)));
+        assert!(filter.keeps(&event("/var/lib/db", EventFlag::ItemModified)));
+        assert!(!filter.keeps(&event("/var/cache/x", EventFlag::ItemModified)));
+        assert!(!filter.keeps(&event("/home/me/app.txt", EventFlag::ItemModified)));
+    }
+
+    #[test]
+    fn flags_match_any_selected_flag() {
+        let filter = filter(&[], &[], EventFlag::ItemRenamed | EventFlag::ItemRemoved);
+        assert!(filter.keeps(&event("/a", EventFlag::ItemRenamed | EventFlag::ItemIsFile)));
+        assert!(filter.keeps(&event("/a", EventFlag::ItemRemoved)));
+        assert!(!filter.keeps(&event("/a", EventFlag::ItemCreated | EventFlag::ItemIsFile)));
+    }
+}
@@ -1,53 +1,121 @@
-use cardinal_sdk::{EventFlag, EventWatcher, event_id_to_timestamp};
-use clap::Parser;
-use std::time::Duration;
+mod cli;
+mod filter;
+mod output;
 
-#[derive(Parser)]
-struct Cli {
-    /// Path to watch, default to current directory.
-    path: Option<String>,
-    /// Start event id, default to 0.
-    #[clap(long, default_value_t = 0)]
-    since: u64,
-}
+use cardinal_sdk::{ChangeSource, EventFlag, EventLogWriter, EventWatcher};
+use clap::Parser;
+use cli::{Cli, Command, OutputArgs, WatchArgs};
+use filter::EventFilter;
+use output::{EventPrinter, Recorder, Sink, Summary};
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-fn main() {
-    let cli = Cli::parse();
-    let path = cli.path.unwrap_or_else(|| {
-        std::env::current_dir()
-            .unwrap()
-            .to_string_lossy()
-            .to_string()
-    });
-    let (dev, event_stream) = EventWatcher::spawn(
-        path,
-        cli.since,
-        0.1,
-        Vec::new().into_boxed_slice(),
-        Vec::new().into_boxed_slice(),
-    );
-    let cache = &mut std::collections::HashMap::new();
+/// Feed filtered event batches into `sink` until a stop condition from
+/// `watch_args` is met. With `stop_when_idle`, also stop once the history is
+/// replayed and the stream stays quiet.
+fn drain(
+    event_stream: EventWatcher,
+    watch_args: &WatchArgs,
+    stop_when_idle: bool,
+    sink: &mut dyn Sink,
+) -> io::Result<()> {
+    let filter = EventFilter::new(watch_args);
+    let mut remaining = watch_args.max_events;
     let mut history_done = false;
-    let timezone = chrono::Local::now().timezone();
+    let mut last_batch = Instant::now();
     loop {
-        let events = if history_done {
-            // If history is done, we try to drain the event stream with a timeout.
-            event_stream.recv_timeout(Duration::from_secs_f32(0.5)).ok()
-        } else {
-            event_stream.recv().ok()
+        let idle_deadline =
+            (history_done && stop_when_idle).then(|| last_batch + Duration::from_secs_f32(0.5));
+        let deadline = match (idle_deadline, sink.next_tick()) {
+            (Some(idle), Some(tick)) => Some(idle.min(tick)),
+            (idle, tick) => idle.or(tick),
         };
-        let Some(events) = events else {
-            break;
+        let received = match deadline {
+            Some(deadline) => event_stream
+                .recv_deadline(deadline)
+                .map_err(|e| e.is_timeout()),
+            None => event_stream.recv().map_err(|_| false),
         };
-        for event in events {
-            if event.flag.contains(EventFlag::HistoryDone) {
-                history_done = true;
+        let mut events = match received {
+            Ok(events) => events,
+            Err(true) => {
+                sink.tick()?;
+                // If history is done, we stop once the stream has been quiet for a while.
+                if idle_deadline.is_some_and(|idle| Instant::now() >= idle) {
+                    break;
+                }
+                continue;
             }
-            let timestamp = event_id_to_timestamp(dev, event.id, cache);
-            let time = chrono::DateTime::from_timestamp(timestamp, 0)
-                .unwrap()
-                .with_timezone(&timezone);
-            println!("{}, {}, {:?}, {:?}", time, event.id, event.path, event.flag);
+            Err(false) => break,
+        };
+        last_batch = Instant::now();
+        let reached_history_done = events
+            .iter()
+            .any(|event| event.flag.contains(EventFlag::HistoryDone));
+        history_done |= reached_history_done;
+
+        events.retain(|event| filter.keeps(event));
+        if let Some(remaining) = &mut remaining {
+            events.truncate(*remaining);
+            *remaining -= events.len();
+        }
+        if !events.is_empty() {
+            sink.batch(events)?;
         }
+        sink.tick()?;
+        if remaining == Some(0) || (reached_history_done && watch_args.until_history_done) {
+            break;
+        }
+    }
+    sink.finish()
+}
+
+fn print(watch_args: WatchArgs, output_args: OutputArgs) -> io::Result<()> {
+    let (_, event_stream) = EventWatcher::spawn(
+        watch_args.path(),
+        watch_args.since,
+        0.1,
+        Vec::new().into_boxed_slice(),
+        Vec::new().into_boxed_slice(),
+    );
+    if let Some(window) = output_args.summary {
+        let mut summary = Summary::new(Duration::from_secs_f64(window), output_args.top);
+        // Windows only add up on a running watch, so summaries keep going
+        // until a stop flag or Ctrl-C.
+        return drain(event_stream, &watch_args, false, &mut summary);
+    }
+    let mut printer = EventPrinter::new(output_args.format, event_stream.clock());
+    drain(event_stream, &watch_args, true, &mut printer)
+}
+
+fn record(output: PathBuf, watch_args: WatchArgs) -> io::Result<()> {
+    let path = watch_args.path();
+    let (_, event_stream) = EventWatcher::spawn(
+        path.clone(),
+        watch_args.since,
+        0.1,
+        Vec::new().into_boxed_slice(),
+        Vec::new().into_boxed_slice(),
+    );
+    let writer = EventLogWriter::new(
+        BufWriter::new(File::create(&output)?),
+        PathBuf::from(path),
+        watch_args.since,
+    )?;
+    let mut recorder = Recorder::new(writer, std::path::absolute(&output)?);
+    drain(event_stream, &watch_args, false, &mut recorder)?;
+    eprintln!("Recorded {} events to {output:?}", recorder.recorded);
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Record { output, watch }) => record(output, watch),
+        None => print(cli.watch, cli.output),
     }
 }
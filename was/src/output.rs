@@ -0,0 +1,232 @@
+use crate::cli::OutputFormat;
+use cardinal_sdk::{EventClock, EventLogWriter, FsEvent};
+use chrono::{DateTime, Local};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, StdoutLock, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Where filtered event batches go.
+pub trait Sink {
+    fn batch(&mut self, events: Vec<FsEvent>) -> io::Result<()>;
+
+    /// When `tick` wants to be called next, if ever.
+    fn next_tick(&self) -> Option<Instant> {
+        None
+    }
+
+    fn tick(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Prints every event as text, JSON lines or CSV.
+pub struct EventPrinter {
+    format: OutputFormat,
+    clock: EventClock,
+    out: StdoutLock<'static>,
+    header_written: bool,
+}
+
+impl EventPrinter {
+    /// `clock` comes from the source the events are read from.
+    pub fn new(format: OutputFormat, clock: EventClock) -> Self {
+        Self {
+            format,
+            clock,
+            out: io::stdout().lock(),
+            header_written: false,
+        }
+    }
+}
+
+impl Sink for EventPrinter {
+    fn batch(&mut self, events: Vec<FsEvent>) -> io::Result<()> {
+        for event in &events {
+            let time = self.clock.timestamp(event.id).map(|timestamp| {
+                DateTime::from_timestamp(timestamp, 0)
+                    .unwrap()
+                    .with_timezone(&Local)
+            });
+            match self.format {
+                OutputFormat::Text => match time {
+                    Some(time) => writeln!(
+                        self.out,
+                        "{}, {}, {:?}, {:?}",
+                        time, event.id, event.path, event.flag
+                    )?,
+                    None => writeln!(self.out, "{}, {:?}, {:?}", event.id, event.path, event.flag)?,
+                },
+                OutputFormat::Jsonl => {
+                    let line = serde_json::json!({
+                        "time": time.map(|time| time.to_rfc3339()),
+                        "id": event.id,
+                        "path": event.path.to_string_lossy(),
+                        "flags": flag_names(event).collect::<Vec<_>>(),
+                    });
+                    writeln!(self.out, "{line}")?
+                }
+                OutputFormat::Csv => {
+                    if !self.header_written {
+                        writeln!(self.out, "time,id,path,flags")?;
+                        self.header_written = true;
+                    }
+                    writeln!(
+                        self.out,
+                        "{},{},{},{}",
+                        time.map(|time| time.to_rfc3339()).unwrap_or_default(),
+                        event.id,
+                        csv_field(&event.path.to_string_lossy()),
+                        flag_names(event).collect::<Vec<_>>().join("|"),
+                    )?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn flag_names(event: &FsEvent) -> impl Iterator<Item = &'static str> {
+    event.flag.iter_names().map(|(name, _)| name)
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Aggregates event counts per parent directory and per flag, printed once
+/// per window and when watching stops.
+pub struct Summary {
+    window: Duration,
+    top: usize,
+    window_end: Instant,
+    events: usize,
+    directories: HashMap<PathBuf, usize>,
+    flags: BTreeMap<&'static str, usize>,
+}
+
+impl Summary {
+    pub fn new(window: Duration, top: usize) -> Self {
+        Self {
+            window,
+            top,
+            window_end: Instant::now() + window,
+            events: 0,
+            directories: HashMap::new(),
+            flags: BTreeMap::new(),
+        }
+    }
+
+    fn print(&mut self) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        writeln!(
+            out,
+            "== {} events in the last {:?}",
+            self.events, self.window
+        )?;
+        let mut directories: Vec<_> = self.directories.drain().collect();
+        directories.sort_unstable_by(|(a_path, a_count), (b_path, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_path.cmp(b_path))
+        });
+        for (directory, count) in directories.iter().take(self.top) {
+            writeln!(out, "{count:>8}  {directory:?}")?;
+        }
+        if directories.len() > self.top {
+            writeln!(
+                out,
+                "     ...  {} more directories",
+                directories.len() - self.top
+            )?;
+        }
+        for (flag, count) in std::mem::take(&mut self.flags) {
+            writeln!(out, "{count:>8}  {flag}")?;
+        }
+        self.events = 0;
+        Ok(())
+    }
+}
+
+impl Sink for Summary {
+    fn batch(&mut self, events: Vec<FsEvent>) -> io::Result<()> {
+        for event in &events {
+            self.events += 1;
+            let directory = event.path.parent().unwrap_or(Path::new("/"));
+            *self.directories.entry(directory.to_path_buf()).or_default() += 1;
+            for name in flag_names(event) {
+                *self.flags.entry(name).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        Some(self.window_end)
+    }
+
+    fn tick(&mut self) -> io::Result<()> {
+        if Instant::now() < self.window_end {
+            return Ok(());
+        }
+        self.window_end = Instant::now() + self.window;
+        self.print()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.events > 0 {
+            self.print()?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes batches to an event log, leaving out events on the log file itself.
+pub struct Recorder<W: Write> {
+    writer: EventLogWriter<W>,
+    log_path: PathBuf,
+    pub recorded: usize,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(writer: EventLogWriter<W>, log_path: PathBuf) -> Self {
+        Self {
+            writer,
+            log_path,
+            recorded: 0,
+        }
+    }
+}
+
+impl<W: Write> Sink for Recorder<W> {
+    fn batch(&mut self, mut events: Vec<FsEvent>) -> io::Result<()> {
+        // Writes to the log would otherwise record themselves when it lives in the watched tree.
+        events.retain(|event| event.path != self.log_path);
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.recorded += events.len();
+        self.writer.write_batch(&events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("/plain/path"), "/plain/path");
+        assert_eq!(csv_field("/a,b"), "\"/a,b\"");
+        assert_eq!(csv_field("/say \"hi\""), "\"/say \"\"hi\"\"\"");
+    }
+}